@@ -1,56 +1,48 @@
 #version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
 
 in vec3  v_world_pos;
 in vec3  v_normal;
 in float v_view_z;
-in vec4  v_cascade_pos[3];
+in vec4  v_cascade_pos[MAX_CASCADES];
+in vec4  v_clip_pos;
+in vec4  v_prev_clip_pos;
 
-// Directional light (sun)
-uniform vec3  u_dir_light_dir;
-uniform vec3  u_dir_light_color;
-uniform float u_dir_light_intensity;
-
-// Cascaded shadow maps (3 cascades, separate samplers for GL 3.3 compatibility)
+// Cascaded shadow maps (up to MAX_CASCADES, separate samplers for GL 3.3 compatibility)
 uniform sampler2D u_shadow_map_0;
 uniform sampler2D u_shadow_map_1;
 uniform sampler2D u_shadow_map_2;
-uniform int       u_shadows_enabled;
-// Camera-depth thresholds (positive, metres): [C0→C1 boundary, C1→C2 boundary]
-uniform float     u_cascade_splits[2];
-
-// Point lights (max 8)
-#define MAX_POINT_LIGHTS 8
-uniform int   u_num_point_lights;
-uniform vec3  u_point_light_pos[MAX_POINT_LIGHTS];
-uniform vec3  u_point_light_color[MAX_POINT_LIGHTS];
-uniform float u_point_light_intensity[MAX_POINT_LIGHTS];
-uniform float u_point_light_constant[MAX_POINT_LIGHTS];
-uniform float u_point_light_linear[MAX_POINT_LIGHTS];
-uniform float u_point_light_quadratic[MAX_POINT_LIGHTS];
-
-// Spot lights (max 4)
-#define MAX_SPOT_LIGHTS 4
-uniform int   u_num_spot_lights;
-uniform vec3  u_spot_light_pos[MAX_SPOT_LIGHTS];
-uniform vec3  u_spot_light_dir[MAX_SPOT_LIGHTS];
-uniform vec3  u_spot_light_color[MAX_SPOT_LIGHTS];
-uniform float u_spot_light_intensity[MAX_SPOT_LIGHTS];
-uniform float u_spot_light_inner_cone[MAX_SPOT_LIGHTS];
-uniform float u_spot_light_outer_cone[MAX_SPOT_LIGHTS];
-uniform float u_spot_light_constant[MAX_SPOT_LIGHTS];
-uniform float u_spot_light_linear[MAX_SPOT_LIGHTS];
-uniform float u_spot_light_quadratic[MAX_SPOT_LIGHTS];
+uniform sampler2D u_shadow_map_3;
+
+// Point light attenuation modes (match `Attenuation` in components/lighting.rs)
+#define ATTEN_PRESET         0
+#define ATTEN_INVERSE_SQUARE 1
+#define ATTEN_WINDOWED       2
+
+// Tiled point lights, used instead of the Frame arrays when more lights are visible than
+// they hold. u_tile_grid has one texel per u_tile_size-pixel screen tile: (offset, count)
+// into u_tile_indices, whose rows continue the flat index list. u_tile_light_data holds
+// three texels per light:
+//   0: xyz = position, w = radius
+//   1: rgb = colour * intensity, a = attenuation mode
+//   2: x = constant, y = linear, z = quadratic
+uniform isampler2D u_tile_grid;
+uniform isampler2D u_tile_indices;
+uniform sampler2D  u_tile_light_data;
 
 uniform vec3  u_object_color;
 uniform vec3  u_object_color_2;
-uniform vec3  u_ambient_color;
-uniform vec3  u_camera_pos;
-uniform vec3  u_fog_color;
-uniform float u_fog_start;
-uniform float u_fog_end;
 uniform int   u_checkerboard;
+uniform int   u_receive_shadows; // 0 for NoShadowReceive entities
+uniform float u_reflectivity;    // Reflective strength, 0 for matte entities
+uniform float u_mirror_strength; // PlanarMirror strength on the mirror entity, else 0
+uniform sampler2D u_mirror_texture; // scene reflected in the mirror plane, screen aligned
 
-out vec4 frag_color;
+layout(location = 0) out vec4 frag_color;
+// Screen motion since last frame in UV units; only stored when rendering offscreen
+layout(location = 1) out vec2 frag_velocity;
+// View-space normal (0–1 encoded) and reflectivity, for screen-space reflections
+layout(location = 2) out vec4 frag_material;
 
 // Cel-shade an NdotL value into 3-band discrete intensity
 float cel_band(float ndotl) {
@@ -60,6 +52,22 @@ float cel_band(float ndotl) {
     return 0.2;
 }
 
+// Point light distance falloff for one of the ATTEN_* modes
+float point_atten(float dist, int mode, float radius, float c, float l, float q) {
+    if (mode == ATTEN_PRESET) {
+        return 1.0 / (c + l * dist + q * dist * dist);
+    }
+    // Clamp at 10 cm so surfaces touching the light don't blow out.
+    float atten = 1.0 / max(dist * dist, 0.01);
+    if (mode == ATTEN_WINDOWED) {
+        // (1 - (d/r)^4)^2: zero value and zero slope at the radius.
+        float ratio  = dist / radius;
+        float window = clamp(1.0 - ratio * ratio * ratio * ratio, 0.0, 1.0);
+        atten *= window * window;
+    }
+    return atten;
+}
+
 // PCF 3x3 shadow test for one cascade
 float pcf_shadow(sampler2D shadow_map, vec4 ls_pos, float bias) {
     vec3 proj = ls_pos.xyz / ls_pos.w;
@@ -79,17 +87,41 @@ float pcf_shadow(sampler2D shadow_map, vec4 ls_pos, float bias) {
 
 // Select cascade by camera depth and sample the appropriate shadow map
 float calc_shadow(vec3 N) {
-    if (u_shadows_enabled == 0) return 0.0;
+    if (u_shadows_enabled == 0 || u_receive_shadows == 0) return 0.0;
 
     float bias = max(0.005 * (1.0 - dot(N, normalize(-u_dir_light_dir))), 0.001);
     float depth = -v_view_z; // positive camera distance
 
-    if (depth < u_cascade_splits[0])
-        return pcf_shadow(u_shadow_map_0, v_cascade_pos[0], bias);
-    else if (depth < u_cascade_splits[1])
-        return pcf_shadow(u_shadow_map_1, v_cascade_pos[1], bias);
-    else
-        return pcf_shadow(u_shadow_map_2, v_cascade_pos[2], bias);
+    // First cascade whose far boundary lies beyond this fragment; the last live
+    // cascade catches everything past the final split.
+    int cascade = u_num_cascades - 1;
+    for (int i = 0; i < MAX_CASCADES - 1; ++i) {
+        if (i < u_num_cascades - 1 && depth < u_cascade_splits[i]) {
+            cascade = i;
+            break;
+        }
+    }
+
+    // Sampler arrays need constant indices in GL 3.3, so dispatch explicitly.
+    if (cascade == 0) return pcf_shadow(u_shadow_map_0, v_cascade_pos[0], bias);
+    if (cascade == 1) return pcf_shadow(u_shadow_map_1, v_cascade_pos[1], bias);
+    if (cascade == 2) return pcf_shadow(u_shadow_map_2, v_cascade_pos[2], bias);
+    return pcf_shadow(u_shadow_map_3, v_cascade_pos[3], bias);
+}
+
+// Analytic sphere occlusion: solid-angle falloff weighted by how directly the
+// surface faces each sphere. Produces soft dark discs under resting bodies.
+float contact_ao(vec3 N) {
+    float visibility = 1.0;
+    for (int i = 0; i < u_num_ao_spheres; i++) {
+        vec3  to_sphere = u_ao_spheres[i].xyz - v_world_pos;
+        float dist      = length(to_sphere);
+        float r         = u_ao_spheres[i].w;
+        float facing    = max(dot(N, to_sphere / dist), 0.0);
+        float occ       = clamp(facing * (r * r) / (dist * dist), 0.0, 1.0);
+        visibility     *= 1.0 - occ;
+    }
+    return 1.0 - visibility;
 }
 
 void main() {
@@ -109,6 +141,12 @@ void main() {
     vec3  dir_contribution = u_dir_light_color * u_dir_light_intensity
                            * cel_band(ndotl_dir) * (1.0 - shadow);
 
+    // Fill directional lights
+    for (int i = 0; i < u_num_fill_lights; i++) {
+        float ndotl = dot(N, normalize(-u_fill_light_dir[i]));
+        dir_contribution += u_fill_light_color[i] * u_fill_light_intensity[i] * cel_band(ndotl);
+    }
+
     // Point lights
     vec3 point_contribution = vec3(0.0);
     for (int i = 0; i < u_num_point_lights; i++) {
@@ -116,11 +154,29 @@ void main() {
         float dist      = length(to_light);
         vec3  L         = to_light / dist;
         float intensity = cel_band(dot(N, L));
-        float atten     = 1.0 / (u_point_light_constant[i]
-                               + u_point_light_linear[i]    * dist
-                               + u_point_light_quadratic[i] * dist * dist);
+        float atten     = point_atten(dist, u_point_light_atten[i], u_point_light_radius[i],
+                                      u_point_light_constant[i], u_point_light_linear[i],
+                                      u_point_light_quadratic[i]);
         point_contribution += u_point_light_color[i] * u_point_light_intensity[i] * intensity * atten;
     }
+    if (u_tiled_lights == 1) {
+        ivec2 tile   = ivec2(gl_FragCoord.xy) / u_tile_size;
+        ivec2 range  = texelFetch(u_tile_grid, tile, 0).xy;
+        int   width  = textureSize(u_tile_indices, 0).x;
+        for (int k = 0; k < range.y; k++) {
+            int   entry = range.x + k;
+            int   li    = texelFetch(u_tile_indices, ivec2(entry % width, entry / width), 0).x;
+            vec4  t0    = texelFetch(u_tile_light_data, ivec2(li * 3,     0), 0);
+            vec4  t1    = texelFetch(u_tile_light_data, ivec2(li * 3 + 1, 0), 0);
+            vec4  t2    = texelFetch(u_tile_light_data, ivec2(li * 3 + 2, 0), 0);
+            vec3  to_light  = t0.xyz - v_world_pos;
+            float dist      = length(to_light);
+            vec3  L         = to_light / dist;
+            float intensity = cel_band(dot(N, L));
+            float atten     = point_atten(dist, int(t1.a + 0.5), t0.w, t2.x, t2.y, t2.z);
+            point_contribution += t1.rgb * intensity * atten;
+        }
+    }
 
     // Spot lights
     vec3 spot_contribution = vec3(0.0);
@@ -140,10 +196,21 @@ void main() {
 
     // Combine lighting
     vec3 total_light = u_ambient_color + dir_contribution + point_contribution + spot_contribution;
+    total_light     *= 1.0 - contact_ao(N) * u_ao_strength;
     vec3 lit_color   = base_color * total_light;
 
     // Linear depth fog
     float fog_dist   = length(v_world_pos - u_camera_pos);
     float fog_factor = clamp((u_fog_end - fog_dist) / (u_fog_end - u_fog_start), 0.0, 1.0);
-    frag_color = vec4(mix(u_fog_color, lit_color, fog_factor), 1.0);
+    // Linear colour out, scaled by exposure; the sRGB framebuffer does the encoding.
+    vec3 color = mix(u_fog_color, lit_color, fog_factor) * u_exposure;
+    // The reflection was drawn from the mirrored camera with the same projection, so this
+    // fragment's own screen position looks up what it reflects (already fogged and exposed).
+    if (u_mirror_strength > 0.0) {
+        vec2 mirror_uv = v_clip_pos.xy / v_clip_pos.w * 0.5 + 0.5;
+        color = mix(color, texture(u_mirror_texture, mirror_uv).rgb, u_mirror_strength);
+    }
+    frag_color = vec4(color, 1.0);
+    frag_velocity = (v_clip_pos.xy / v_clip_pos.w - v_prev_clip_pos.xy / v_prev_clip_pos.w) * 0.5;
+    frag_material = vec4(normalize(mat3(u_view) * N) * 0.5 + 0.5, u_reflectivity);
 }
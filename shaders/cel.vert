@@ -1,26 +1,31 @@
 #version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
 
 layout(location = 0) in vec3 a_position;
 layout(location = 1) in vec3 a_normal;
 
 uniform mat4 u_model;
-uniform mat4 u_view;
-uniform mat4 u_projection;
-uniform mat4 u_cascade_light_space[3];
+uniform mat4 u_prev_model; // last frame's u_model, for the velocity buffer
+uniform vec4 u_clip_plane;  // world plane for the mirror pass (only read with CLIP_DISTANCE0 on)
 
 out vec3  v_world_pos;
 out vec3  v_normal;
 out float v_view_z;
-out vec4  v_cascade_pos[3];
+out vec4  v_cascade_pos[MAX_CASCADES];
+out vec4  v_clip_pos;
+out vec4  v_prev_clip_pos;
 
 void main() {
     vec4 world    = u_model * vec4(a_position, 1.0);
     vec4 view_pos = u_view * world;
     v_world_pos   = world.xyz;
+    gl_ClipDistance[0] = dot(u_clip_plane, world);
     v_normal      = mat3(transpose(inverse(u_model))) * a_normal;
     v_view_z      = view_pos.z; // negative in right-handed (fragment uses -v_view_z for depth)
-    for (int i = 0; i < 3; ++i) {
-        v_cascade_pos[i] = u_cascade_light_space[i] * world;
+    for (int i = 0; i < MAX_CASCADES; ++i) {
+        v_cascade_pos[i] = (i < u_num_cascades) ? u_cascade_light_space[i] * world : vec4(0.0);
     }
-    gl_Position = u_projection * view_pos;
+    gl_Position     = u_projection * view_pos;
+    v_clip_pos      = gl_Position;
+    v_prev_clip_pos = u_prev_view_proj * u_prev_model * vec4(a_position, 1.0);
 }
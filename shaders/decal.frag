@@ -0,0 +1,17 @@
+#version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
+
+in vec2 v_uv;
+
+uniform sampler2D u_decal_mask;
+uniform vec3      u_decal_color; // linear
+uniform float     u_decal_alpha; // lifetime fade
+
+out vec4 frag_color;
+
+void main() {
+    float alpha = texture(u_decal_mask, v_uv).r * u_decal_alpha;
+    if (alpha <= 0.0) discard;
+    // Unlit: decals are dark marks, so skipping the light loops reads fine and stays cheap.
+    frag_color = vec4(u_decal_color * u_exposure, alpha);
+}
@@ -0,0 +1,14 @@
+#version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
+
+// Unit quad corner in [-0.5, 0.5]², laid out in the decal's tangent plane
+layout(location = 0) in vec2 a_corner;
+
+uniform mat4 u_model;
+
+out vec2 v_uv;
+
+void main() {
+    v_uv = a_corner + 0.5;
+    gl_Position = u_projection * u_view * u_model * vec4(a_corner.x, 0.0, a_corner.y, 1.0);
+}
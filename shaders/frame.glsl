@@ -0,0 +1,70 @@
+// Per-frame data shared by every program, spliced in after each shader's #version line.
+// It lives in one std140 uniform buffer that the renderer fills once per frame, in exactly
+// this member order (see `Renderer::draw_scene`). Under std140 a vec3 starts a 16-byte
+// slot whose last 4 bytes the next scalar may use, and every array element (float and int
+// included) takes a full 16-byte slot.
+#define MAX_CASCADES     4
+#define MAX_FILL_LIGHTS  3
+#define MAX_POINT_LIGHTS 8
+#define MAX_SPOT_LIGHTS  4
+#define MAX_AO_SPHERES   8
+
+layout(std140) uniform Frame {
+    mat4  u_view;
+    mat4  u_projection;
+    vec3  u_camera_pos;
+    vec3  u_ambient_color;
+    vec3  u_fog_color;
+    float u_fog_start;
+    float u_fog_end;
+    float u_exposure;
+
+    // Directional light (sun) and its cascaded shadows
+    vec3  u_dir_light_dir;
+    float u_dir_light_intensity;
+    vec3  u_dir_light_color;
+    int   u_shadows_enabled;
+    int   u_num_cascades;
+    mat4  u_cascade_light_space[MAX_CASCADES];
+    // Camera-depth thresholds (positive, metres): entry i is the Ci→Ci+1 boundary
+    float u_cascade_splits[MAX_CASCADES - 1];
+
+    // Fill directional lights (unshadowed)
+    int   u_num_fill_lights;
+    vec3  u_fill_light_dir[MAX_FILL_LIGHTS];
+    vec3  u_fill_light_color[MAX_FILL_LIGHTS];
+    float u_fill_light_intensity[MAX_FILL_LIGHTS];
+
+    // Point lights
+    int   u_num_point_lights;
+    vec3  u_point_light_pos[MAX_POINT_LIGHTS];
+    vec3  u_point_light_color[MAX_POINT_LIGHTS];
+    float u_point_light_intensity[MAX_POINT_LIGHTS];
+    float u_point_light_constant[MAX_POINT_LIGHTS];
+    float u_point_light_linear[MAX_POINT_LIGHTS];
+    float u_point_light_quadratic[MAX_POINT_LIGHTS];
+    float u_point_light_radius[MAX_POINT_LIGHTS];
+    int   u_point_light_atten[MAX_POINT_LIGHTS];
+    int   u_tiled_lights;
+    int   u_tile_size;
+
+    // Spot lights
+    int   u_num_spot_lights;
+    vec3  u_spot_light_pos[MAX_SPOT_LIGHTS];
+    vec3  u_spot_light_dir[MAX_SPOT_LIGHTS];
+    vec3  u_spot_light_color[MAX_SPOT_LIGHTS];
+    float u_spot_light_intensity[MAX_SPOT_LIGHTS];
+    float u_spot_light_inner_cone[MAX_SPOT_LIGHTS];
+    float u_spot_light_outer_cone[MAX_SPOT_LIGHTS];
+    float u_spot_light_constant[MAX_SPOT_LIGHTS];
+    float u_spot_light_linear[MAX_SPOT_LIGHTS];
+    float u_spot_light_quadratic[MAX_SPOT_LIGHTS];
+
+    // Contact AO spheres: xyz = centre, w = radius
+    int   u_num_ao_spheres;
+    float u_ao_strength;
+    vec4  u_ao_spheres[MAX_AO_SPHERES];
+
+    // Last frame's camera, for per-pixel velocity
+    mat4  u_prev_view_proj;
+};
@@ -0,0 +1,10 @@
+#version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
+
+in vec3 v_color;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = vec4(v_color * u_exposure, 1.0);
+}
@@ -0,0 +1,12 @@
+#version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
+
+layout(location = 0) in vec3 a_position; // world space
+layout(location = 1) in vec3 a_color;    // linear
+
+out vec3 v_color;
+
+void main() {
+    v_color     = a_color;
+    gl_Position = u_projection * u_view * vec4(a_position, 1.0);
+}
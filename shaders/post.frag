@@ -0,0 +1,213 @@
+#version 330 core
+
+in vec2 v_uv;
+
+uniform sampler2D u_scene;    // sRGB texture: samples come back linear
+uniform sampler2D u_velocity; // screen-space motion this frame, in UV units
+uniform sampler2D u_depth;
+uniform sampler2D u_material; // view-space normal (rgb, 0–1 encoded) and reflectivity (a)
+
+// Motion blur: fraction of the frame's motion to smear over (0 = off)
+uniform float u_motion_blur;
+// Camera reprojection for the background, which has no geometry to carry a velocity
+uniform mat4 u_inv_view_proj;
+uniform mat4 u_prev_view_proj;
+// Screen-space reflections on reflective surfaces, falling back to the (linear) fog colour
+uniform bool u_reflections;
+uniform mat4 u_projection;
+uniform vec3 u_fog_color;
+// Depth of field: view depth that stays sharp, and blur strength (0 = off)
+uniform float u_focus_distance;
+uniform float u_aperture;
+uniform mat4  u_inv_projection;
+// Vignette: corner darkening (0 = off), and where it starts (fraction of centre-to-corner)
+uniform float u_vignette_strength;
+uniform float u_vignette_radius;
+// Colour grading on the display-encoded image (identity: 0, 1, 1, 1)
+uniform vec3  u_grade_lift;
+uniform vec3  u_grade_gamma;
+uniform vec3  u_grade_gain;
+uniform float u_grade_saturation;
+
+out vec4 frag_color;
+
+#define MAX_BLUR_SAMPLES 8
+// Longest smear in UV units, so fast spins stay a hint rather than a wash
+const float MAX_BLUR_LENGTH = 0.04;
+
+#define SSR_STEPS 48
+// Longest reflected ray (view units) and how far behind a surface a ray may pass and still
+// count as hitting it
+const float SSR_MAX_DISTANCE = 40.0;
+const float SSR_THICKNESS    = 0.5;
+// Fraction of the screen at each edge over which reflections fade out
+const float SSR_EDGE_FADE    = 0.1;
+
+#define DOF_SAMPLES 24
+// Largest circle of confusion radius, in scene target pixels
+const float MAX_COC_PIXELS = 10.0;
+const float GOLDEN_ANGLE   = 2.39996323;
+
+// Width of the vignette's fade from `u_vignette_radius` outwards
+const float VIGNETTE_SOFTNESS = 0.5;
+
+vec2 screen_velocity(vec2 uv) {
+    if (texture(u_depth, uv).r < 1.0) {
+        return texture(u_velocity, uv).rg;
+    }
+    vec4 far_point = u_inv_view_proj * vec4(uv * 2.0 - 1.0, 1.0, 1.0);
+    vec4 prev_clip = u_prev_view_proj * vec4(far_point.xyz / far_point.w, 1.0);
+    return uv - (prev_clip.xy / prev_clip.w * 0.5 + 0.5);
+}
+
+vec3 motion_blur(vec2 uv, vec3 center) {
+    vec2 velocity = screen_velocity(uv) * u_motion_blur;
+    float len = length(velocity);
+    if (len > MAX_BLUR_LENGTH) {
+        velocity *= MAX_BLUR_LENGTH / len;
+    }
+    vec2 pixels = velocity * vec2(textureSize(u_scene, 0));
+    int samples = clamp(int(length(pixels) * 0.5), 1, MAX_BLUR_SAMPLES);
+    if (samples < 2) {
+        return center;
+    }
+    vec3 sum = vec3(0.0);
+    for (int i = 0; i < samples; ++i) {
+        float t = float(i) / float(samples - 1) - 0.5;
+        sum += texture(u_scene, uv + velocity * t).rgb;
+    }
+    return sum / float(samples);
+}
+
+vec3 view_position(vec2 uv) {
+    float depth = texture(u_depth, uv).r;
+    vec4 view = u_inv_projection * vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
+    return view.xyz / view.w;
+}
+
+float view_depth(vec2 uv) {
+    return -view_position(uv).z;
+}
+
+// March the reflected view ray through the depth buffer, with steps growing with distance.
+// A hit takes the scene colour there, faded towards the fog colour near the screen edges and
+// at the end of the ray; a ray that leaves the screen or finds nothing gets the fog colour.
+vec3 reflection(vec2 uv, vec3 color, vec4 material) {
+    vec3 origin = view_position(uv);
+    vec3 normal = normalize(material.rgb * 2.0 - 1.0);
+    vec3 dir    = reflect(normalize(origin), normal);
+
+    vec3 reflected = u_fog_color;
+    float prev_distance = 0.0;
+    for (int i = 1; i <= SSR_STEPS; ++i) {
+        float t = float(i) / float(SSR_STEPS);
+        float distance = SSR_MAX_DISTANCE * t * t;
+        vec3 p = origin + dir * distance;
+        vec4 clip = u_projection * vec4(p, 1.0);
+        if (clip.w <= 0.0) {
+            break;
+        }
+        vec2 hit_uv = clip.xy / clip.w * 0.5 + 0.5;
+        if (any(lessThan(hit_uv, vec2(0.0))) || any(greaterThan(hit_uv, vec2(1.0)))) {
+            break;
+        }
+        // Positive once the ray has passed behind the surface seen at hit_uv.
+        float behind = view_position(hit_uv).z - p.z;
+        if (behind > 0.0 && behind < SSR_THICKNESS + distance - prev_distance) {
+            vec2 edge = smoothstep(0.0, SSR_EDGE_FADE, hit_uv)
+                      * smoothstep(0.0, SSR_EDGE_FADE, 1.0 - hit_uv);
+            float fade = edge.x * edge.y * (1.0 - t);
+            reflected = mix(u_fog_color, texture(u_scene, hit_uv).rgb, fade);
+            break;
+        }
+        prev_distance = distance;
+    }
+    return mix(color, reflected, material.a);
+}
+
+// Circle of confusion radius in pixels for a point at view depth `depth`
+float coc_radius(float depth) {
+    return min(u_aperture * abs(depth - u_focus_distance) / depth, 1.0) * MAX_COC_PIXELS;
+}
+
+// Gather over a golden-angle disc. A sample only counts if its own blur reaches this pixel,
+// so a sharp foreground edge never smears onto the blurred background behind it; samples
+// further away than this pixel blur no more than it does, so the background doesn't leak
+// over a sharp foreground either.
+vec3 depth_of_field(vec2 uv, vec3 center) {
+    float center_depth = view_depth(uv);
+    float center_coc   = coc_radius(center_depth);
+    vec2 texel = 1.0 / vec2(textureSize(u_scene, 0));
+
+    vec3 sum = center;
+    float weight = 1.0;
+    for (int i = 1; i < DOF_SAMPLES; ++i) {
+        float radius = MAX_COC_PIXELS * sqrt(float(i) / float(DOF_SAMPLES - 1));
+        float angle  = float(i) * GOLDEN_ANGLE;
+        vec2 sample_uv = uv + vec2(cos(angle), sin(angle)) * radius * texel;
+
+        float sample_depth = view_depth(sample_uv);
+        float sample_coc   = coc_radius(sample_depth);
+        if (sample_depth > center_depth) {
+            sample_coc = min(sample_coc, center_coc);
+        }
+        float w = clamp(sample_coc - radius + 1.0, 0.0, 1.0);
+        sum    += texture(u_scene, sample_uv).rgb * w;
+        weight += w;
+    }
+    return sum / weight;
+}
+
+vec3 linear_to_srgb(vec3 c) {
+    return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c));
+}
+
+vec3 srgb_to_linear(vec3 c) {
+    return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+}
+
+// Lift/gamma/gain then saturation, in sRGB space so the controls act on what's displayed.
+// The result is decoded again because the framebuffer re-encodes on write.
+vec3 color_grade(vec3 linear) {
+    vec3 c = clamp(linear_to_srgb(clamp(linear, 0.0, 1.0)), 0.0, 1.0);
+    c = u_grade_gain * (c + u_grade_lift * (1.0 - c));
+    c = pow(max(c, 0.0), 1.0 / u_grade_gamma);
+    float luma = dot(c, vec3(0.2126, 0.7152, 0.0722));
+    c = max(mix(vec3(luma), c, u_grade_saturation), 0.0);
+    return srgb_to_linear(c);
+}
+
+void main() {
+    vec3 scene = texture(u_scene, v_uv).rgb;
+    vec3 color = scene;
+
+    if (u_reflections) {
+        vec4 material = texture(u_material, v_uv);
+        if (material.a > 0.0) {
+            color = reflection(v_uv, color, material);
+        }
+    }
+    if (u_aperture > 0.0) {
+        color = depth_of_field(v_uv, color);
+    }
+    // Motion blur reads the unfocused scene; add its change on top of the focused image.
+    if (u_motion_blur > 0.0) {
+        color = max(color + motion_blur(v_uv, scene) - scene, 0.0);
+    }
+
+    if (u_vignette_strength > 0.0) {
+        // Aspect-corrected so the falloff stays round; 1 at the corners.
+        vec2 aspect = vec2(textureSize(u_scene, 0)) / float(textureSize(u_scene, 0).y);
+        float dist  = length((v_uv - 0.5) * aspect) / length(0.5 * aspect);
+        float fade  = smoothstep(u_vignette_radius, u_vignette_radius + VIGNETTE_SOFTNESS, dist);
+        color      *= 1.0 - u_vignette_strength * fade;
+    }
+
+    // Last, so grading sees the finished image.
+    if (u_grade_lift != vec3(0.0) || u_grade_gamma != vec3(1.0) || u_grade_gain != vec3(1.0)
+        || u_grade_saturation != 1.0) {
+        color = color_grade(color);
+    }
+
+    frag_color = vec4(color, 1.0);
+}
@@ -4,6 +4,12 @@ out vec4 frag_color;
 
 uniform vec4 u_color;
 
+// UI colours are authored in sRGB; the framebuffer re-encodes on write, so decode here to
+// keep them as authored while blending happens in linear space.
+vec3 srgb_to_linear(vec3 c) {
+    return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+}
+
 void main() {
-    frag_color = u_color;
+    frag_color = vec4(srgb_to_linear(u_color.rgb), u_color.a);
 }
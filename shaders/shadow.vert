@@ -1,10 +1,12 @@
 #version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
 
 layout(location = 0) in vec3 a_position;
 
 uniform mat4 u_model;
-uniform mat4 u_light_space;
+// Index into the Frame block's cascade matrices
+uniform int  u_cascade;
 
 void main() {
-    gl_Position = u_light_space * u_model * vec4(a_position, 1.0);
+    gl_Position = u_cascade_light_space[u_cascade] * u_model * vec4(a_position, 1.0);
 }
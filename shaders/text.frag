@@ -3,11 +3,21 @@
 in vec2 v_texcoord;
 out vec4 frag_color;
 
-uniform sampler2D u_font_atlas;
+uniform sampler2D u_font_atlas; // signed distance field, edge at 0.5
 uniform vec3 u_text_color;
+uniform float u_text_opacity;
+
+// sRGB → linear, as in quad.frag
+vec3 srgb_to_linear(vec3 c) {
+    return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+}
 
 void main() {
-    float alpha = texture(u_font_atlas, v_texcoord).r;
-    if (alpha < 0.5) discard;
-    frag_color = vec4(u_text_color, alpha);
+    // The atlas is a distance field with the glyph edge at 0.5; blend across about one
+    // screen pixel either side of it for smooth edges at any scale.
+    float dist  = texture(u_font_atlas, v_texcoord).r;
+    float width = max(fwidth(dist), 1e-4);
+    float alpha = smoothstep(0.5 - width, 0.5 + width, dist) * u_text_opacity;
+    if (alpha <= 0.0) discard;
+    frag_color = vec4(srgb_to_linear(u_text_color), alpha);
 }
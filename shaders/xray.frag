@@ -0,0 +1,18 @@
+#version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
+
+in vec3 v_world_pos;
+in vec3 v_normal;
+
+uniform vec3 u_xray_color; // linear
+
+out vec4 frag_color;
+
+void main() {
+    // Rim term: opaque where the surface turns away from the view, faint face-on, so the
+    // hidden shape reads as an outline rather than a solid blob.
+    vec3  V     = normalize(u_camera_pos - v_world_pos);
+    float rim   = 1.0 - abs(dot(normalize(v_normal), V));
+    float alpha = mix(0.12, 0.85, rim * rim);
+    frag_color  = vec4(u_xray_color * u_exposure, alpha);
+}
@@ -0,0 +1,17 @@
+#version 330 core
+// Frame uniform block and MAX_* limits: shaders/frame.glsl (inserted at load)
+
+layout(location = 0) in vec3 a_position;
+layout(location = 1) in vec3 a_normal;
+
+uniform mat4 u_model;
+
+out vec3 v_world_pos;
+out vec3 v_normal;
+
+void main() {
+    vec4 world  = u_model * vec4(a_position, 1.0);
+    v_world_pos = world.xyz;
+    v_normal    = mat3(transpose(inverse(u_model))) * a_normal;
+    gl_Position = u_projection * u_view * world;
+}
@@ -1,42 +1,182 @@
-use crate::camera::{Camera, CameraMode};
-use crate::components::{Children, Held, Hidden, LocalTransform, PreviousPosition, SwordPosition, SwordState};
-use crate::engine::input::{InputEvent, InputState};
+use crate::camera::{Camera, CameraMode, CameraSettings};
+use crate::components::{
+    ancestors, descendants, CharacterBody, Collider, ContactStatus, GlobalTransform, Grounded, Held,
+    Hidden, LocalTransform, Noclip, NoclipRecovery, PlayerFsm, PlayerState, PreviousPosition,
+    SwordPosition, SwordState, Velocity,
+};
+use crate::engine::audio::Audio;
+use crate::engine::config::Config;
+use crate::engine::haptics::Haptics;
+use crate::engine::input::{InputBuffer, InputEvent, InputState, DEFAULT_STICK_DEADZONE};
+use crate::engine::input_map::{GameAction, InputMap};
 use crate::engine::time::FrameTimer;
 use crate::engine::window::GameWindow;
+use crate::engine::world_up::WorldUp;
 use crate::recording;
-use crate::renderer::{MeshStore, Renderer};
+use crate::renderer::{
+    AutoExposure, ColorGrading, DepthOfField, MeshStore, Renderer, ShadowSettings, Vignette,
+};
+use crate::scene::loader::{SceneFile, SceneWatch};
+use crate::scene::{load_scene, save_scene};
 use crate::systems::{
-    collision_system, grab_throw_system, grounded_system, physics_step, player_movement_system,
-    player_state_system, raycast_static, transform_propagation_system, PHYSICS_DT,
+    apply_hit_impulses, arm_aim_system, billboard_system, collision_system, damage_number_system,
+    draw_hierarchy, drop_held, flicker_system, footprint_system, grab_throw_system, grounded_system,
+    lifetime_system, locomotion_system, physics_step, player_movement_system, player_state_system,
+    raycast_screen, raycast_static, screen_ray, spawn_damage_numbers, sweep_sphere_static,
+    sword_attack_system, transform_propagation_system, ContactTracker, EntityDrag, GridSnap,
+    LightEditor, PhysicsConfig, PhysicsDebug, SystemKind, SystemToggles, TransformGuard, PHYSICS_DT,
 };
-use crate::ui::{DebugHud, GameState, PauseAction, PauseMenu, TextRenderer};
-use glam::{Mat4, Vec3};
+use crate::ui::{
+    draw_damage_numbers, DebugHud, EntityBrowser, GameState, Inspector, LabelView, PauseAction,
+    PauseMenu, TextRenderer,
+};
+use glam::{Mat4, Vec2, Vec3};
 use hecs::{Entity, World};
 use sdl2::keyboard::Scancode;
+use sdl2::mouse::MouseButton;
 use sdl2::Sdl;
+use std::path::{Path, PathBuf};
+
+/// Eye height above the player root for a player without a `CharacterBody` to take it from.
+const DEFAULT_EYE_HEIGHT: f32 = 0.7;
+/// How long collision pushes are rate-limited after leaving noclip (seconds).
+const NOCLIP_RECOVERY_TIME: f32 = 0.5;
+/// Landing impacts faster than this (m/s closing speed) rumble the gamepad; rumble reaches full
+/// strength at HARD_LANDING_SPEED + HARD_LANDING_RANGE.
+const HARD_LANDING_SPEED: f32 = 8.0;
+const HARD_LANDING_RANGE: f32 = 12.0;
+/// Debug teleport (T): ray length, fallback distance when nothing is hit, how far to stop
+/// short of the hit surface, and how high above the landing point to place the player root.
+const TELEPORT_MAX_DISTANCE: f32 = 200.0;
+const TELEPORT_MISS_DISTANCE: f32 = 20.0;
+const TELEPORT_BACKOFF: f32 = 0.4;
+const TELEPORT_LIFT: f32 = 1.4;
+/// Spectator mode: the player walks forward while turning at this rate (degrees/s),
+/// tracing a circle.
+const SPECTATOR_TURN_RATE: f32 = 30.0;
+/// How often the window title's FPS / entity count refreshes (seconds).
+const TITLE_UPDATE_INTERVAL: f32 = 1.0;
+/// Right-stick look speed at full tilt, in mouse counts per second, so it follows the
+/// camera's mouse sensitivity (1500 at the default 0.1 is 150°/s).
+const STICK_LOOK_RATE: f32 = 1500.0;
+/// Knockback speed that maps to full-strength rumble on a sword hit.
+const HIT_RUMBLE_KNOCKBACK: f32 = 15.0;
+/// User settings file (volumes), saved whenever the pause menu closes with changes.
+const CONFIG_PATH: &str = "settings.ron";
+/// Whole-world snapshot the editor writes with Ctrl+Shift+S and reads back with Ctrl+Shift+O.
+const SNAPSHOT_PATH: &str = "snapshot.ron";
+/// Background music tracks (names under `assets/audio`) for exploring and for fighting.
+const AMBIENT_MUSIC: &str = "ambient";
+const COMBAT_MUSIC: &str = "combat";
+/// Sound effect for a landed sword hit.
+const HIT_SOUND: &str = "hit";
+/// Pause menu blips for moving the selection (or a slider) and for choosing an item.
+const MENU_MOVE_SOUND: &str = "menu_move";
+const MENU_SELECT_SOUND: &str = "menu_select";
+/// Crossfade between music tracks (seconds).
+const MUSIC_CROSSFADE: f32 = 2.0;
+/// Combat music keeps playing this long after the last sword hit (seconds).
+const COMBAT_MUSIC_HOLD: f32 = 8.0;
+/// Light editor steps: intensity and radius scale per press, hue turn per press (degrees),
+/// and how far one arrow press moves the light (world units).
+const LIGHT_EDIT_SCALE: f32 = 1.1;
+const LIGHT_EDIT_HUE_STEP: f32 = 15.0;
+const LIGHT_EDIT_NUDGE: f32 = 0.25;
+/// Longest pick ray for click-to-inspect.
+const INSPECT_MAX_DISTANCE: f32 = 200.0;
+/// Editor: how far one scroll notch turns the dragged entity (degrees), and the extent
+/// (cells from the centre) and colour of the snap grid drawn around it.
+const EDITOR_ROTATE_STEP: f32 = 5.0;
+const SNAP_GRID_CELLS: u32 = 5;
+const SNAP_GRID_COLOR: Vec3 = Vec3::new(0.6, 0.6, 0.65);
+
+/// Populates an empty world with a scene. Returns the scene's meshes and the player entity.
+/// Kept by the app so the scene can be rebuilt from scratch on restart.
+pub type SceneLoader = Box<dyn Fn(&mut World) -> (MeshStore, Entity)>;
 
 pub struct GameApp {
     world: World,
     meshes: MeshStore,
     player_entity: Entity,
+    scene_loader: SceneLoader,
+    /// Scene file the world was loaded from; the editor saves back to it (Ctrl+S).
+    scene_path: Option<PathBuf>,
+    /// Hot reload of a scene file (`--watch-scene`).
+    scene_watch: Option<SceneWatch>,
+    /// Carry the player's transform over hot reloads instead of respawning it.
+    reload_keep_player: bool,
     camera: Camera,
     renderer: Renderer,
     text_renderer: TextRenderer,
     pause_menu: PauseMenu,
     debug_hud: DebugHud,
+    entity_browser: EntityBrowser,
+    inspector: Inspector,
+    /// Inspect mode (I): cursor released, left click picks an entity for the inspector
+    /// instead of attacking, and the mouse no longer turns the camera.
+    inspect_mode: bool,
+    /// Draw parent links as lines (H).
+    show_hierarchy: bool,
+    /// Editor mode (E): cursor released and physics paused; left-drag moves the picked
+    /// entity and the scroll wheel turns it. Ctrl constrains the move to one axis; Shift
+    /// snaps to `grid_snap`. Ctrl+Shift+S and Ctrl+Shift+O save and restore a snapshot of
+    /// the whole world.
+    editor_mode: bool,
+    grid_snap: GridSnap,
+    /// Light editing: `light_edit_key` cycles the selected point/spot light (Shift for
+    /// backwards); while one is selected, [ ] scale its intensity, - = its radius, , . turn
+    /// its hue, and the arrow keys move it (Ctrl + Up/Down for height).
+    light_editor: LightEditor,
+    light_edit_key: Scancode,
+    /// Velocity arrows and contact normals, toggled with `physics_debug_key`.
+    physics_debug: PhysicsDebug,
+    physics_debug_key: Scancode,
+    /// The entity being dragged in editor mode, while the left button is held.
+    drag: Option<EntityDrag>,
     game_state: GameState,
+    /// Player body entities hidden for first person, restored on leaving it.
+    hidden_body: Vec<Entity>,
+    /// Recent action presses, so slightly early jump/dash/attack inputs still register.
+    input_buffer: InputBuffer,
+    input_map: InputMap,
+    haptics: Haptics,
+    audio: Audio,
+    /// Settings persisted to `CONFIG_PATH`, as last saved.
+    config: Config,
+    /// Time left before combat music gives way to the ambient track again.
+    combat_music: f32,
+    physics: PhysicsConfig,
+    /// Debug switches for skipping individual systems (F5–F10).
+    toggles: SystemToggles,
+    /// Contact pairs across physics ticks (began / persisted / ended).
+    contacts: ContactTracker,
+    /// Non-finite transforms already reported by transform propagation.
+    transform_guard: TransformGuard,
+    /// Facing of the scripted player while the camera is in spectator mode (degrees).
+    spectator_yaw: f32,
     physics_accum: f32,
+    /// Depth of field keeps the player in focus, refreshed every render.
+    dof_focus_player: bool,
+    /// Window aspect ratio as of the last render, for sizing the camera's collision sphere.
+    aspect_ratio: f32,
+    /// Window size in pixels as of the last render, for picking under the cursor.
+    window_size: Vec2,
+    /// Real time and frames since the window title was last refreshed.
+    title_timer: f32,
+    title_frames: u32,
     recorder: Option<recording::Recorder>,
     record_elapsed: f32,
     record_frame_debt: f32,
+    /// Gamepad stick deadzone handed to the input state when the game starts.
+    stick_deadzone: f32,
 }
 
 impl GameApp {
     pub fn new(
-        world: World,
-        meshes: MeshStore,
-        player_entity: Entity,
+        scene_loader: SceneLoader,
         record: bool,
+        shadow_settings: ShadowSettings,
+        camera_settings: CameraSettings,
         window: &GameWindow,
     ) -> Self {
         let recorder = if record {
@@ -46,32 +186,77 @@ impl GameApp {
             None
         };
 
+        let mut world = World::new();
+        let (meshes, player_entity) = scene_loader(&mut world);
+
         Self {
             world,
             meshes,
             player_entity,
-            camera: Camera::new(),
-            renderer: Renderer::init(),
+            scene_loader,
+            scene_path: None,
+            scene_watch: None,
+            reload_keep_player: false,
+            camera: Camera::with_settings(camera_settings),
+            renderer: Renderer::init(shadow_settings),
             text_renderer: TextRenderer::new(),
             pause_menu: PauseMenu::new(),
             debug_hud: DebugHud::new(),
+            entity_browser: EntityBrowser::new(),
+            inspector: Inspector::new(),
+            inspect_mode: false,
+            show_hierarchy: false,
+            editor_mode: false,
+            drag: None,
+            grid_snap: GridSnap::new(),
+            light_editor: LightEditor::new(),
+            light_edit_key: Scancode::L,
+            physics_debug: PhysicsDebug::new(),
+            physics_debug_key: Scancode::F11,
             game_state: GameState::Running,
+            hidden_body: Vec::new(),
+            input_buffer: InputBuffer::new(),
+            input_map: InputMap::new(),
+            haptics: Haptics::disabled(),
+            audio: Audio::disabled(),
+            config: Config::default(),
+            combat_music: 0.0,
+            physics: PhysicsConfig::new(),
+            toggles: SystemToggles::new(),
+            contacts: ContactTracker::new(),
+            transform_guard: TransformGuard::new(),
+            spectator_yaw: 0.0,
             physics_accum: 0.0,
+            dof_focus_player: false,
+            aspect_ratio: window.aspect_ratio(),
+            window_size: Vec2::new(window.size().0 as f32, window.size().1 as f32),
+            title_timer: 0.0,
+            title_frames: 0,
             recorder,
             record_elapsed: 0.0,
             record_frame_debt: 0.0,
+            stick_deadzone: DEFAULT_STICK_DEADZONE,
         }
     }
 
-    pub fn run(&mut self, sdl: &Sdl, window: &GameWindow) {
+    pub fn run(&mut self, sdl: &Sdl, window: &mut GameWindow) {
         sdl.mouse().set_relative_mouse_mode(true);
+        self.haptics = Haptics::new(sdl);
+        self.audio = Audio::new(sdl);
+        self.config = Config::load(Path::new(CONFIG_PATH));
+        self.pause_menu.set_volumes(self.config.volumes);
+        self.audio.set_volumes(self.config.volumes);
+        self.audio.play_music(AMBIENT_MUSIC, 0.0);
         let mut event_pump = sdl.event_pump().expect("Failed to get event pump");
         let mut input = InputState::new();
+        input.stick_deadzone = self.stick_deadzone;
+        input.open_controllers(sdl);
         let mut timer = FrameTimer::new();
 
         'main: loop {
             timer.tick();
             input.update(&mut event_pump);
+            self.haptics.follow(input.controller_id());
 
             if input.should_quit() {
                 break;
@@ -84,6 +269,7 @@ impl GameApp {
                     if self.game_state == GameState::Running {
                         self.game_state = GameState::Paused;
                         self.pause_menu.reset_selection();
+                        self.audio.set_paused(true);
                         sdl.mouse().set_relative_mouse_mode(false);
                         just_paused = true;
                     }
@@ -100,6 +286,13 @@ impl GameApp {
                         match self.handle_paused_input(&input) {
                             PauseAction::Resume => {
                                 self.game_state = GameState::Running;
+                                self.audio.set_paused(false);
+                                sdl.mouse().set_relative_mouse_mode(true);
+                            }
+                            PauseAction::Restart => {
+                                self.restart_scene();
+                                self.game_state = GameState::Running;
+                                self.audio.set_paused(false);
                                 sdl.mouse().set_relative_mouse_mode(true);
                             }
                             PauseAction::Quit => break 'main,
@@ -109,48 +302,146 @@ impl GameApp {
                 }
                 GameState::Running => {
                     alpha = self.update_systems(&input, timer.dt);
+                    let relative = !self.cursor_mode();
+                    if sdl.mouse().relative_mouse_mode() != relative {
+                        sdl.mouse().set_relative_mouse_mode(relative);
+                    }
                     if self.debug_hud.is_visible() {
                         self.debug_hud.update(timer.dt);
                     }
+                    self.update_music(timer.dt);
                 }
             }
 
             // Propagate transforms before rendering (always, even when paused).
-            transform_propagation_system(&mut self.world, alpha);
+            transform_propagation_system(&mut self.world, alpha, &mut self.transform_guard);
+            billboard_system(&mut self.world, self.camera.position, self.physics.world_up.vector());
+            self.renderer.adapt_exposure(timer.dt);
             self.render(window);
 
             if self.tick_recorder(timer.dt) {
                 break;
             }
 
+            self.tick_window_title(window, timer.dt);
             window.swap();
         }
     }
 
+    /// Drop back to the ambient track once combat has been quiet for `COMBAT_MUSIC_HOLD`.
+    fn update_music(&mut self, dt: f32) {
+        if self.combat_music <= 0.0 {
+            return;
+        }
+        self.combat_music -= dt;
+        if self.combat_music <= 0.0 {
+            self.audio.play_music(AMBIENT_MUSIC, MUSIC_CROSSFADE);
+        }
+    }
+
+    /// Show FPS and entity count in the title bar, refreshed once per
+    /// `TITLE_UPDATE_INTERVAL` rather than every frame.
+    fn tick_window_title(&mut self, window: &mut GameWindow, dt: f32) {
+        self.title_timer += dt;
+        self.title_frames += 1;
+        if self.title_timer < TITLE_UPDATE_INTERVAL {
+            return;
+        }
+        let fps = self.title_frames as f32 / self.title_timer;
+        let title = format!(
+            "{} — {:.0} FPS — {} entities",
+            window.base_title(),
+            fps,
+            self.world.len()
+        );
+        window.set_title(&title);
+        self.title_timer = 0.0;
+        self.title_frames = 0;
+    }
+
     fn handle_running_input(&mut self, input: &InputState) {
         for event in &input.events {
+            if let InputEvent::KeyPressed(key) = event {
+                if self.handle_light_edit_key(input, *key) {
+                    continue;
+                }
+                if *key == self.physics_debug_key {
+                    self.physics_debug.toggle();
+                    continue;
+                }
+            }
+            if self.input_map.is_press_of(event, GameAction::ToggleCamera) {
+                if self.is_noclip() {
+                    self.set_noclip(false);
+                } else if self.camera.mode == CameraMode::Spectator {
+                    self.set_spectator(false);
+                } else {
+                    self.camera.toggle_mode();
+                }
+                continue;
+            }
             match event {
-                InputEvent::KeyPressed(Scancode::F1) => self.camera.toggle_mode(),
+                InputEvent::KeyPressed(Scancode::F2) => {
+                    let on = self.camera.mode != CameraMode::Spectator;
+                    self.set_spectator(on);
+                }
+                InputEvent::KeyPressed(Scancode::N) => {
+                    let on = !self.is_noclip();
+                    self.set_noclip(on);
+                }
                 InputEvent::KeyPressed(Scancode::F3) => self.debug_hud.toggle(),
+                InputEvent::KeyPressed(Scancode::F12) => self.cycle_solver_iterations(),
+                InputEvent::KeyPressed(Scancode::F4) => self.entity_browser.toggle(),
+                InputEvent::KeyPressed(Scancode::PageUp) if self.entity_browser.is_visible() => {
+                    self.entity_browser.scroll(-1);
+                }
+                InputEvent::KeyPressed(Scancode::PageDown) if self.entity_browser.is_visible() => {
+                    self.entity_browser.scroll(1);
+                }
+                InputEvent::KeyPressed(Scancode::I) => self.inspect_mode = !self.inspect_mode,
+                InputEvent::KeyPressed(Scancode::H) => self.show_hierarchy = !self.show_hierarchy,
+                InputEvent::KeyPressed(Scancode::E) => {
+                    self.editor_mode = !self.editor_mode;
+                    self.drag = None;
+                    println!("[editor] {}", if self.editor_mode { "on" } else { "off" });
+                }
+                InputEvent::KeyPressed(Scancode::S)
+                    if self.editor_mode && is_ctrl_held(input) && is_shift_held(input) =>
+                {
+                    self.save_snapshot();
+                }
+                InputEvent::KeyPressed(Scancode::O)
+                    if self.editor_mode && is_ctrl_held(input) && is_shift_held(input) =>
+                {
+                    self.load_snapshot();
+                }
+                InputEvent::KeyPressed(Scancode::S)
+                    if self.editor_mode
+                        && (input.is_key_held(Scancode::LCtrl)
+                            || input.is_key_held(Scancode::RCtrl)) =>
+                {
+                    self.save_scene();
+                }
+                InputEvent::MouseButtonPressed(MouseButton::Left) if self.cursor_mode() => {
+                    let cursor = Vec2::new(input.mouse_x as f32, input.mouse_y as f32);
+                    self.pick_inspected(cursor);
+                }
+                InputEvent::MouseButtonReleased(MouseButton::Left) => {
+                    if let Some(drag) = self.drag.take() {
+                        let snap = is_shift_held(input).then_some(&self.grid_snap);
+                        drag.finish(&mut self.world, snap);
+                    }
+                }
+                InputEvent::KeyPressed(Scancode::G) => self.cycle_gravity(),
+                InputEvent::KeyPressed(Scancode::T) if self.camera.mode == CameraMode::Player => {
+                    self.teleport_to_look_point();
+                }
                 InputEvent::KeyPressed(Scancode::Z) => {
                     self.camera.toggle_perspective();
-                    let mut to_toggle = vec![self.player_entity];
-                    if let Ok(children) = self.world.get::<&Children>(self.player_entity) {
-                        to_toggle.extend(children.0.iter().copied());
-                    }
-                    let is_third_person = self.camera.is_third_person();
-                    for entity in to_toggle {
-                        if self.world.get::<&Held>(entity).is_ok() {
-                            continue;
-                        }
-                        if self.world.get::<&SwordState>(entity).is_ok() {
-                            continue;
-                        }
-                        if is_third_person {
-                            let _ = self.world.remove_one::<Hidden>(entity);
-                        } else {
-                            let _ = self.world.insert_one(entity, Hidden);
-                        }
+                    if self.camera.is_third_person() {
+                        self.show_player_body();
+                    } else {
+                        self.hide_player_body();
                     }
                 }
                 InputEvent::KeyPressed(Scancode::F) => {
@@ -171,6 +462,12 @@ impl GameApp {
                         }
                     }
                 }
+                InputEvent::KeyPressed(key) => {
+                    if let Some(kind) = toggle_key_system(*key) {
+                        let on = self.toggles.toggle(kind);
+                        println!("[systems] {} {}", kind.name(), if on { "on" } else { "off" });
+                    }
+                }
                 _ => {}
             }
         }
@@ -178,36 +475,453 @@ impl GameApp {
         // Free-look (hold C): camera pans without rotating the character.
         self.camera.free_look = input.is_key_held(Scancode::C);
 
-        // Scroll wheel zoom.
-        if input.scroll_dy != 0.0 {
+        // Scroll wheel zoom (turns the dragged entity instead while editing).
+        if input.scroll_dy != 0.0 && self.drag.is_none() {
             self.camera.apply_zoom(input.scroll_dy);
         }
 
-        self.camera.look(input.mouse_dx, input.mouse_dy);
+        if !self.cursor_mode() {
+            self.camera.look(input.mouse_dx, input.mouse_dy);
+        }
+
+        let view_proj = self.view_proj();
+        if let Some(drag) = &mut self.drag {
+            let cursor = Vec2::new(input.mouse_x as f32, input.mouse_y as f32);
+            let (origin, dir) = screen_ray(&view_proj, cursor, self.window_size);
+            let constrain =
+                input.is_key_held(Scancode::LCtrl) || input.is_key_held(Scancode::RCtrl);
+            let snap = is_shift_held(input).then_some(&self.grid_snap);
+            drag.update(&mut self.world, origin, dir, constrain, snap);
+            if input.scroll_dy != 0.0 {
+                drag.rotate(&mut self.world, input.scroll_dy * EDITOR_ROTATE_STEP, snap);
+            }
+            if let Some(snap) = snap {
+                let center = snap.position(drag.position());
+                let gizmos = self.renderer.gizmos();
+                gizmos.grid(center, SNAP_GRID_CELLS, snap.step, SNAP_GRID_COLOR);
+            }
+        }
+    }
+
+    /// Light editor keys (see `light_editor`). Returns whether `key` was used.
+    fn handle_light_edit_key(&mut self, input: &InputState, key: Scancode) -> bool {
+        if key == self.light_edit_key {
+            self.light_editor.cycle(&self.world, is_shift_held(input));
+            match self.light_editor.selected() {
+                Some(light) => println!("[lights] editing {light:?}"),
+                None => println!("[lights] done"),
+            }
+            return true;
+        }
+        if self.light_editor.selected().is_none() {
+            return false;
+        }
+        let ctrl = input.is_key_held(Scancode::LCtrl) || input.is_key_held(Scancode::RCtrl);
+        let up = self.camera.up.vector();
+        let front = self.camera.front();
+        let forward = (front - up * front.dot(up)).normalize_or(Vec3::NEG_Z);
+        let right = forward.cross(up);
+        let editor = &self.light_editor;
+        let world = &mut self.world;
+        match key {
+            Scancode::LeftBracket => editor.scale_intensity(world, 1.0 / LIGHT_EDIT_SCALE),
+            Scancode::RightBracket => editor.scale_intensity(world, LIGHT_EDIT_SCALE),
+            Scancode::Minus => editor.scale_radius(world, 1.0 / LIGHT_EDIT_SCALE),
+            Scancode::Equals => editor.scale_radius(world, LIGHT_EDIT_SCALE),
+            Scancode::Comma => editor.rotate_hue(world, -LIGHT_EDIT_HUE_STEP),
+            Scancode::Period => editor.rotate_hue(world, LIGHT_EDIT_HUE_STEP),
+            Scancode::Up if ctrl => editor.nudge(world, up * LIGHT_EDIT_NUDGE),
+            Scancode::Down if ctrl => editor.nudge(world, -up * LIGHT_EDIT_NUDGE),
+            Scancode::Up => editor.nudge(world, forward * LIGHT_EDIT_NUDGE),
+            Scancode::Down => editor.nudge(world, -forward * LIGHT_EDIT_NUDGE),
+            Scancode::Left => editor.nudge(world, -right * LIGHT_EDIT_NUDGE),
+            Scancode::Right => editor.nudge(world, right * LIGHT_EDIT_NUDGE),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Inspect or editor mode: the cursor is free and the mouse picks instead of looking.
+    fn cursor_mode(&self) -> bool {
+        self.inspect_mode || self.editor_mode
+    }
+
+    fn view_proj(&self) -> Mat4 {
+        self.camera.projection_matrix(self.aspect_ratio) * self.camera.view_matrix()
+    }
+
+    /// Inspect whatever collider is under `cursor`, and in editor mode start dragging it.
+    /// Clicking empty space closes the panel.
+    fn pick_inspected(&mut self, cursor: Vec2) {
+        let hit = raycast_screen(
+            &self.world,
+            &self.view_proj(),
+            cursor,
+            self.window_size,
+            INSPECT_MAX_DISTANCE,
+        );
+        self.inspector.select(hit.as_ref().map(|h| h.entity));
+        if self.editor_mode {
+            self.drag = hit.and_then(|h| EntityDrag::begin(&self.world, &h, self.camera.front()));
+        }
+    }
+
+    /// Cycle gravity: down → up (ceiling) → tilted → down, relative to the world up axis.
+    /// Same magnitude each time.
+    fn cycle_gravity(&mut self) {
+        let g = self.physics.gravity.length();
+        let up = self.physics.world_up.vector();
+        let down = -up;
+        let tilted = self.physics.world_up.align() * Vec3::new(0.5, -1.0, 0.0).normalize();
+        let current = self.physics.gravity.normalize_or_zero();
+        let next = if current.abs_diff_eq(down, 1e-3) {
+            up
+        } else if current.abs_diff_eq(up, 1e-3) {
+            tilted
+        } else {
+            down
+        };
+        self.physics.gravity = next * g;
+        println!("[physics] gravity → {:?}", self.physics.gravity);
+    }
+
+    /// Cycle the velocity-solver passes per tick: 1 → 2 → 4 → 8 → 1.
+    fn cycle_solver_iterations(&mut self) {
+        let next = match self.physics.solver_iterations {
+            0..=1 => 2,
+            2..=3 => 4,
+            4..=7 => 8,
+            _ => 1,
+        };
+        self.physics.solver_iterations = next;
+        println!("[physics] solver iterations → {next}");
+    }
+
+    /// Debug: move the player to where the camera is looking. Raycasts static geometry
+    /// along the view direction (or goes a fixed distance on a miss), drops anything held,
+    /// and restarts the player from rest in `Falling` so it settles onto the surface.
+    fn teleport_to_look_point(&mut self) {
+        let dir = self.camera.front();
+        let origin = self.camera.position;
+        let point = match raycast_static(&self.world, origin, dir, TELEPORT_MAX_DISTANCE) {
+            Some(dist) => origin + dir * (dist - TELEPORT_BACKOFF).max(0.0),
+            None => origin + dir * TELEPORT_MISS_DISTANCE,
+        };
+        let pos = point + self.physics.up() * TELEPORT_LIFT;
+
+        drop_held(&mut self.world, self.player_entity);
+        let player = self.player_entity;
+        if let Ok(mut local) = self.world.get::<&mut LocalTransform>(player) {
+            local.position = pos;
+        }
+        if let Ok(mut vel) = self.world.get::<&mut Velocity>(player) {
+            vel.0 = Vec3::ZERO;
+        }
+        if let Ok(mut fsm) = self.world.get::<&mut PlayerFsm>(player) {
+            *fsm = PlayerFsm::new(PlayerState::Falling);
+        }
+        let _ = self.world.remove_one::<Grounded>(player);
+        // Reset the interpolation baseline so the body doesn't streak across the map.
+        let _ = self.world.insert_one(player, PreviousPosition(pos));
+    }
+
+    /// Hide the player's whole body subtree for first person. Held objects and the sword
+    /// (and anything attached below them) stay visible. Only entities that weren't already
+    /// hidden are recorded, so `show_player_body` restores exactly this set.
+    fn hide_player_body(&mut self) {
+        let player = self.player_entity;
+        let mut body = vec![player];
+        body.extend(descendants(&self.world, player));
+
+        let keep_visible = |world: &World, entity: Entity| {
+            let exempt =
+                |e: Entity| world.get::<&Held>(e).is_ok() || world.get::<&SwordState>(e).is_ok();
+            exempt(entity)
+                || ancestors(world, entity)
+                    .into_iter()
+                    .take_while(|&a| a != player)
+                    .any(exempt)
+        };
+
+        for entity in body {
+            if keep_visible(&self.world, entity) || self.world.get::<&Hidden>(entity).is_ok() {
+                continue;
+            }
+            if self.world.insert_one(entity, Hidden).is_ok() {
+                self.hidden_body.push(entity);
+            }
+        }
+    }
+
+    /// Undo `hide_player_body`.
+    fn show_player_body(&mut self) {
+        for entity in self.hidden_body.drain(..) {
+            let _ = self.world.remove_one::<Hidden>(entity);
+        }
+    }
+
+    fn is_noclip(&self) -> bool {
+        self.world.get::<&Noclip>(self.player_entity).is_ok()
+    }
+
+    /// Eye level above the player root: the player's rig decides it, so the camera and its
+    /// occlusion ray start at the head whatever the character's height.
+    fn eye_height(&self) -> f32 {
+        self.world
+            .get::<&CharacterBody>(self.player_entity)
+            .map_or(DEFAULT_EYE_HEIGHT, |body| body.eye_height)
+    }
+
+    /// Enter or leave noclip: the player body stops colliding and follows the fly camera.
+    /// Leaving noclip hands the body back to physics with a short recovery window so that
+    /// exiting inside geometry resolves gradually.
+    fn set_noclip(&mut self, on: bool) {
+        if on {
+            let player_pos = self
+                .world
+                .get::<&LocalTransform>(self.player_entity)
+                .map(|t| t.position)
+                .unwrap_or(self.camera.position);
+            let _ = self.world.insert_one(self.player_entity, Noclip);
+            self.camera.position = self.camera.eye_pos(player_pos, self.eye_height());
+            self.camera.mode = CameraMode::Fly;
+        } else {
+            let _ = self.world.remove_one::<Noclip>(self.player_entity);
+            let _ = self
+                .world
+                .insert_one(self.player_entity, NoclipRecovery(NOCLIP_RECOVERY_TIME));
+            if let Ok(mut vel) = self.world.get::<&mut Velocity>(self.player_entity) {
+                vel.0 = Vec3::ZERO;
+            }
+            self.camera.mode = CameraMode::Player;
+        }
+    }
+
+    /// Detach the camera into spectator mode (the player keeps walking a scripted circle)
+    /// or return to following the player, easing the camera back in behind it.
+    fn set_spectator(&mut self, on: bool) {
+        if on {
+            if self.is_noclip() {
+                self.set_noclip(false);
+            }
+            self.spectator_yaw = self.camera.body_yaw;
+            self.camera.mode = CameraMode::Spectator;
+            // The point is to watch the body, so show it even from first person.
+            self.show_player_body();
+        } else {
+            // Face the camera the way the player is walking so it lands behind them.
+            self.camera.yaw = self.spectator_yaw;
+            self.camera.body_yaw = self.spectator_yaw;
+            self.camera.pitch = 0.0;
+            self.camera.mode = CameraMode::Player;
+            self.camera.begin_transition();
+            if !self.camera.is_third_person() {
+                self.hide_player_body();
+            }
+        }
+    }
+
+    /// Throw the world away and rebuild the scene with its loader. The camera starts over
+    /// (keeping its clip planes and recenter/facing options) and per-run simulation state is reset;
+    /// physics settings and system toggles carry over.
+    fn restart_scene(&mut self) {
+        self.rebuild_world();
+        let settings = CameraSettings {
+            auto_recenter: self.camera.auto_recenter,
+            face_movement: self.camera.face_movement,
+            near: self.camera.near(),
+            far: self.camera.far(),
+        };
+        self.camera = Camera::with_settings(settings);
+        self.camera.up = self.physics.world_up;
+        self.spectator_yaw = 0.0;
+        println!("[scene] restarted");
+    }
+
+    /// Replace the world with a fresh one from the scene loader and reset per-run
+    /// simulation state. The camera is left alone.
+    fn rebuild_world(&mut self) {
+        let mut world = World::new();
+        let (meshes, player_entity) = (self.scene_loader)(&mut world);
+        self.replace_world(world, meshes, player_entity);
+    }
+
+    /// Swap in an already built world and reset per-run simulation state.
+    fn replace_world(&mut self, world: World, meshes: MeshStore, player_entity: Entity) {
+        // Old meshes are dropped (and their GL buffers freed) only after the new scene loaded.
+        self.world = world;
+        self.meshes = meshes;
+        self.player_entity = player_entity;
+        self.hidden_body.clear();
+        self.input_buffer = InputBuffer::new();
+        self.contacts = ContactTracker::new();
+        self.transform_guard = TransformGuard::new();
+        self.physics_accum = 0.0;
+        // Entity ids from the old world mean nothing in the new one.
+        self.drag = None;
+        self.inspector.select(None);
+    }
+
+    /// Remember the scene file the world came from, so the editor can save back to it.
+    pub fn set_scene_path(&mut self, path: PathBuf) {
+        self.scene_path = Some(path);
+    }
+
+    /// Write the current layout of the scene's `Persistent` entities back to its file. The
+    /// saved layout also becomes what Restart rebuilds.
+    fn save_scene(&mut self) {
+        let Some(path) = &self.scene_path else {
+            println!("[editor] nothing to save: scene was not loaded from a file");
+            return;
+        };
+        let file = SceneFile::from_world(&self.world, self.player_entity);
+        match file.save(path) {
+            Ok(()) => {
+                println!("[editor] saved {}", path.display());
+                let up = self.physics.world_up;
+                self.scene_loader = Box::new(move |world: &mut World| file.build(world, up));
+            }
+            Err(e) => eprintln!("[editor] save failed: {e}"),
+        }
+    }
+
+    /// Write the whole world, not just the scene file's prefabs, to `SNAPSHOT_PATH`.
+    fn save_snapshot(&self) {
+        match save_scene(&self.world, SNAPSHOT_PATH) {
+            Ok(()) => println!("[editor] saved snapshot {SNAPSHOT_PATH}"),
+            Err(e) => eprintln!("[editor] snapshot save failed: {e}"),
+        }
+    }
+
+    /// Replace the world with the one in `SNAPSHOT_PATH`. The camera carries over, like a hot
+    /// reload; Restart still rebuilds the original scene.
+    fn load_snapshot(&mut self) {
+        let mut meshes = MeshStore::new();
+        let (world, player_entity) = match load_scene(SNAPSHOT_PATH, &mut meshes) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("[editor] snapshot load failed, keeping current world: {e}");
+                return;
+            }
+        };
+        let body_hidden = !self.hidden_body.is_empty();
+        self.replace_world(world, meshes, player_entity);
+        if body_hidden {
+            self.hide_player_body();
+        }
+        println!("[editor] loaded snapshot {SNAPSHOT_PATH}");
+    }
+
+    /// Hot-reload `path` whenever it changes on disk. With `keep_player` the player keeps its
+    /// current position and facing across reloads; otherwise it respawns where the file says.
+    pub fn watch_scene(&mut self, path: PathBuf, keep_player: bool) {
+        self.scene_watch = Some(SceneWatch::new(path));
+        self.reload_keep_player = keep_player;
+    }
+
+    /// Reload the watched scene file if it changed. A file that fails to parse leaves the
+    /// current world running and logs the error.
+    fn tick_scene_watch(&mut self, dt: f32) {
+        let Some(watch) = self.scene_watch.as_mut() else {
+            return;
+        };
+        let file = match watch.poll(dt) {
+            None => return,
+            Some(Ok(file)) => file,
+            Some(Err(e)) => {
+                eprintln!("[scene] reload failed, keeping current scene: {e}");
+                return;
+            }
+        };
+
+        let kept = if self.reload_keep_player {
+            self.world
+                .get::<&LocalTransform>(self.player_entity)
+                .map(|lt| (lt.position, lt.rotation))
+                .ok()
+        } else {
+            None
+        };
+        let body_hidden = !self.hidden_body.is_empty();
+        let up = self.physics.world_up;
+        self.scene_loader = Box::new(move |world: &mut World| file.build(world, up));
+        self.rebuild_world();
+
+        if let Some((position, rotation)) = kept {
+            if let Ok(mut lt) = self.world.get::<&mut LocalTransform>(self.player_entity) {
+                lt.position = position;
+                lt.rotation = rotation;
+            }
+            let _ = self.world.insert_one(self.player_entity, PreviousPosition(position));
+        }
+        // The camera survives the reload, so the new body keeps the old one's visibility.
+        if body_hidden {
+            self.hide_player_body();
+        }
+        println!("[scene] reloaded");
     }
 
     fn handle_paused_input(&mut self, input: &InputState) -> PauseAction {
-        self.pause_menu.handle_input(&input.events)
+        let before = (self.pause_menu.selected(), self.pause_menu.exposure());
+        let volumes_before = self.pause_menu.volumes();
+        let action = self.pause_menu.handle_input(&input.events);
+        self.renderer.set_exposure(self.pause_menu.exposure());
+        let volumes = self.pause_menu.volumes();
+        if action != PauseAction::None {
+            self.audio.play_ui(MENU_SELECT_SOUND);
+        } else if before != (self.pause_menu.selected(), self.pause_menu.exposure())
+            || volumes != volumes_before
+        {
+            self.audio.play_ui(MENU_MOVE_SOUND);
+        }
+        self.audio.set_volumes(volumes);
+        if action != PauseAction::None && volumes != self.config.volumes {
+            self.config.volumes = volumes;
+            self.config.save(Path::new(CONFIG_PATH));
+        }
+        action
     }
 
     fn update_systems(&mut self, input: &InputState, dt: f32) -> f32 {
+        self.tick_scene_watch(dt);
         self.handle_running_input(input);
+        // Inspect/editor clicks pick entities; don't let them queue attacks too.
+        if !self.cursor_mode() {
+            let (x, y) = input.right_stick();
+            self.camera.look(x * STICK_LOOK_RATE * dt, -y * STICK_LOOK_RATE * dt);
+            self.input_buffer.update(input, &self.input_map, dt);
+        }
 
         // Lerp body_yaw toward camera.yaw — handles both normal turning and
         // returning from free-look with a single continuous lerp (~200 ms).
         if !self.camera.free_look {
             self.camera.tick_body_yaw(dt);
         }
+        if self.camera.mode == CameraMode::Player {
+            let moving_forward = self.input_map.is_action_held(input, GameAction::MoveForward)
+                || input.left_stick().1 > 0.0;
+            let mouse_moved = input.mouse_dx != 0.0
+                || input.mouse_dy != 0.0
+                || input.right_stick() != (0.0, 0.0);
+            self.camera.tick_auto_recenter(moving_forward, mouse_moved, dt);
+        }
 
         // Grab/throw must run before player movement to produce speed multiplier, yaw lock,
         // and movement block direction.
-        let (speed_mult, yaw_clamp, move_block) = if self.camera.mode == CameraMode::Player {
+        let grab_on =
+            self.camera.mode == CameraMode::Player && self.toggles.is_enabled(SystemKind::Grab);
+        let (speed_mult, yaw_clamp, move_block) = if grab_on {
             let camera = &self.camera;
             grab_throw_system(&mut self.world, input, camera, dt)
         } else {
             (1.0, None, None)
         };
 
+        locomotion_system(&mut self.world, dt);
+        if self.camera.mode == CameraMode::Player && self.toggles.is_enabled(SystemKind::ArmAim) {
+            arm_aim_system(&mut self.world, &self.camera, dt);
+        }
+
         // Apply yaw lock from grab system: prevent camera and body yaw from advancing past the
         // clamp point in the blocked direction. Clamping body_yaw here (after tick_body_yaw but
         // before player_movement_system) stops the visual rotation on the same frame.
@@ -227,26 +941,113 @@ impl GameApp {
 
         match self.camera.mode {
             CameraMode::Player => {
-                player_state_system(&mut self.world, input, dt);
-                let camera = &self.camera;
-                player_movement_system(&mut self.world, input, camera, speed_mult, move_block, dt);
+                if self.toggles.is_enabled(SystemKind::Player) {
+                    let physics = &self.physics;
+                    let map = &self.input_map;
+                    let buffer = &mut self.input_buffer;
+                    player_state_system(&mut self.world, input, map, buffer, physics, dt);
+                    let camera = &self.camera;
+                    player_movement_system(
+                        &mut self.world,
+                        input,
+                        map,
+                        camera,
+                        speed_mult,
+                        move_block,
+                        physics,
+                        dt,
+                    );
+                }
+                if self.toggles.is_enabled(SystemKind::Combat) {
+                    let hits = sword_attack_system(&mut self.world);
+                    apply_hit_impulses(&mut self.world, &hits, self.physics.world_up.vector());
+                    spawn_damage_numbers(&mut self.world, &hits);
+                    if let Some(knockback) = hits.iter().map(|h| h.knockback).reduce(f32::max) {
+                        self.haptics.rumble(knockback / HIT_RUMBLE_KNOCKBACK, 0.12);
+                        self.audio.play_sfx(HIT_SOUND);
+                        self.combat_music = COMBAT_MUSIC_HOLD;
+                        self.audio.play_music(COMBAT_MUSIC, MUSIC_CROSSFADE);
+                    }
+                }
             }
             CameraMode::Fly => {
                 self.camera.move_wasd(input, dt);
+                if self.is_noclip() {
+                    let pos = self.camera.position - self.camera.up.vector() * self.eye_height();
+                    if let Ok(mut local) = self.world.get::<&mut LocalTransform>(self.player_entity)
+                    {
+                        local.position = pos;
+                    }
+                    let _ = self.world.insert_one(self.player_entity, PreviousPosition(pos));
+                }
+            }
+            CameraMode::Spectator => {
+                self.camera.move_wasd(input, dt);
+                if self.toggles.is_enabled(SystemKind::Player) {
+                    self.drive_spectator_player(dt);
+                }
             }
         }
 
         let mut collision_events = Vec::new();
+        let mut contact_changes = Vec::new();
+        let mut resolved_contacts = 0;
         let mut physics_ticks = 0usize;
+        let was_grounded = self.world.get::<&Grounded>(self.player_entity).is_ok();
         self.physics_accum += dt;
+        // Disabled physics/collision still consume the accumulator, so re-enabling them
+        // doesn't replay the skipped time in one burst.
         while self.physics_accum >= PHYSICS_DT {
             physics_ticks += 1;
-            physics_step(&mut self.world);
-            collision_events.extend(collision_system(&mut self.world));
+            // Editor mode pauses the simulation so dragged entities stay where they're put.
+            if self.toggles.is_enabled(SystemKind::Physics) && !self.editor_mode {
+                physics_step(&mut self.world, &self.physics);
+            }
+            let events = if self.toggles.is_enabled(SystemKind::Collision) && !self.editor_mode {
+                let (events, resolved) = collision_system(&mut self.world, &self.physics);
+                resolved_contacts += resolved;
+                events
+            } else {
+                Vec::new()
+            };
+            contact_changes.extend(self.contacts.update(&events));
+            self.physics_debug.record(&self.world, &events);
+            collision_events.extend(events);
             self.physics_accum -= PHYSICS_DT;
         }
         let alpha = self.physics_accum / PHYSICS_DT;
-        grounded_system(&mut self.world, &collision_events, physics_ticks);
+        flicker_system(&mut self.world, dt);
+        grounded_system(&mut self.world, &collision_events, physics_ticks, &self.physics);
+        footprint_system(&mut self.world, self.physics.up(), dt);
+        damage_number_system(&mut self.world, dt);
+        // Let go of anything that timed out from under the inspector or the editor drag.
+        for despawned in lifetime_system(&mut self.world, dt) {
+            if self.inspector.selected() == Some(despawned.entity) {
+                self.inspector.select(None);
+            }
+            if self.drag.as_ref().is_some_and(|drag| drag.entity() == despawned.entity) {
+                self.drag = None;
+            }
+        }
+        let count = |status| contact_changes.iter().filter(|c| c.status == status).count();
+        self.debug_hud.set_contacts(
+            self.contacts.active_count(),
+            count(ContactStatus::Began),
+            count(ContactStatus::Ended),
+        );
+        self.debug_hud.set_solver(self.physics.solver_iterations, resolved_contacts);
+
+        // Landing strength: the hardest impact the player body took this frame.
+        let landed = !was_grounded && self.world.get::<&Grounded>(self.player_entity).is_ok();
+        let impact = collision_events
+            .iter()
+            .filter(|e| e.entity_a == self.player_entity || e.entity_b == self.player_entity)
+            .map(|e| e.relative_normal_velocity)
+            .fold(0.0_f32, f32::max);
+        if landed && impact > HARD_LANDING_SPEED {
+            let strength = (impact - HARD_LANDING_SPEED) / HARD_LANDING_RANGE;
+            self.haptics.rumble(0.3 + 0.7 * strength, 0.2);
+        }
 
         if self.camera.mode == CameraMode::Player {
             // Use interpolated player position so the camera follows
@@ -260,11 +1061,13 @@ impl GameApp {
                 _ => Vec3::ZERO,
             };
             // Compute desired camera position, raycast for wall occlusion, apply.
-            let (eye, desired) = self.camera.desired_follow_pos(player_pos, 0.7, 0.3);
-            let ray_to_desired = desired - eye;
-            let max_dist = ray_to_desired.length();
+            let (eye, desired) =
+                self.camera.desired_follow_pos(player_pos, self.eye_height(), 0.3);
+            let to_desired = desired - eye;
+            let max_dist = to_desired.length();
             let hit_dist = if max_dist > 1e-6 && self.camera.is_third_person() {
-                raycast_static(&self.world, eye, ray_to_desired / max_dist, max_dist)
+                let radius = self.camera.near_plane_radius(self.aspect_ratio);
+                camera_sweep(&self.world, eye, to_desired, radius)
             } else {
                 None
             };
@@ -274,13 +1077,151 @@ impl GameApp {
         alpha
     }
 
+    /// Spectator mode: drive the player from a script, holding W while the facing slowly
+    /// turns. The puppet camera only supplies the body yaw to the movement system.
+    fn drive_spectator_player(&mut self, dt: f32) {
+        self.spectator_yaw += SPECTATOR_TURN_RATE * dt;
+        let mut puppet = Camera::new();
+        puppet.yaw = self.spectator_yaw;
+        puppet.body_yaw = self.spectator_yaw;
+        // The script holds W, so it plays against the default bindings, not the player's.
+        let script = InputState::with_held_keys(&[Scancode::W]);
+        let map = InputMap::new();
+        let mut buffer = InputBuffer::new();
+        let physics = &self.physics;
+        player_state_system(&mut self.world, &script, &map, &mut buffer, physics, dt);
+        player_movement_system(&mut self.world, &script, &map, &puppet, 1.0, None, physics, dt);
+    }
+
+    /// Process entities in a fixed order wherever hecs query order would otherwise leak into
+    /// results (collision pairing, primary light selection).
+    pub fn set_deterministic(&mut self, on: bool) {
+        self.physics.deterministic = on;
+        self.renderer.set_deterministic(on);
+    }
+
+    /// Which world axis is up, for gravity, movement, the camera and shadows.
+    pub fn set_world_up(&mut self, up: WorldUp) {
+        self.physics.set_world_up(up);
+        self.camera.up = up;
+        self.renderer.set_world_up(up);
+    }
+
+    /// Velocity-solver passes per tick (at least one).
+    pub fn set_solver_iterations(&mut self, iterations: usize) {
+        self.physics.solver_iterations = iterations.max(1);
+    }
+
+    /// Closing speed (m/s) below which contacts stop bouncing, and the speed band above it
+    /// over which restitution fades in.
+    pub fn set_rest_threshold(&mut self, threshold: f32, falloff: f32) {
+        self.physics.rest_threshold = threshold.max(0.0);
+        self.physics.restitution_falloff = falloff.max(0.0);
+    }
+
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.pause_menu.set_exposure(exposure);
+        self.renderer.set_exposure(exposure);
+    }
+
+    /// Editor grid increment, and whether snapping follows the drag live or only applies
+    /// to the final placement.
+    pub fn set_grid_snap(&mut self, step: f32, live: bool) {
+        self.grid_snap = GridSnap { step: step.max(1e-3), live };
+    }
+
+    pub fn set_auto_exposure(&mut self, settings: AutoExposure) {
+        self.renderer.set_auto_exposure(Some(settings));
+    }
+
+    pub fn set_motion_blur(&mut self, strength: f32) {
+        self.renderer.set_motion_blur(strength);
+    }
+
+    /// Focus blur with `aperture`, sharp at view depth `focus_distance`, or following the
+    /// player when `None`.
+    pub fn set_depth_of_field(&mut self, aperture: f32, focus_distance: Option<f32>) {
+        self.dof_focus_player = focus_distance.is_none();
+        self.renderer.set_depth_of_field(Some(DepthOfField {
+            focus_distance: focus_distance.unwrap_or(1.0),
+            aperture,
+        }));
+    }
+
+    pub fn set_color_grading(&mut self, grading: ColorGrading) {
+        self.renderer.set_color_grading(grading);
+    }
+
+    pub fn set_vignette(&mut self, strength: f32, radius: f32) {
+        self.renderer.set_vignette(Some(Vignette { strength, radius }));
+    }
+
+    /// Bind `action` to `key`, replacing its default keyboard and mouse bindings.
+    pub fn rebind(&mut self, action: GameAction, key: Scancode) {
+        self.input_map.rebind(action, key);
+    }
+
+    /// Fraction of full stick tilt ignored around centre.
+    pub fn set_stick_deadzone(&mut self, deadzone: f32) {
+        self.stick_deadzone = deadzone.clamp(0.0, 0.95);
+    }
+
+    /// Key that cycles the light editor's selection.
+    pub fn set_light_edit_key(&mut self, key: Scancode) {
+        self.light_edit_key = key;
+    }
+
+    /// Key that toggles the physics debug overlay, and its velocity arrow length per unit
+    /// of speed (seconds).
+    pub fn set_physics_debug(&mut self, key: Scancode, velocity_scale: f32) {
+        self.physics_debug_key = key;
+        self.physics_debug.set_velocity_scale(velocity_scale);
+    }
+
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.renderer.set_render_scale(scale);
+    }
+
     fn render(&mut self, window: &GameWindow) {
         let view = self.camera.view_matrix();
-        let proj = self.camera.projection_matrix(window.aspect_ratio());
+        self.aspect_ratio = window.aspect_ratio();
+        let (w, h) = window.size();
+        self.window_size = Vec2::new(w as f32, h as f32);
+        let proj = self.camera.projection_matrix(self.aspect_ratio);
+
+        self.light_editor.validate(&self.world);
+        self.light_editor.draw_gizmo(&self.world, self.renderer.gizmos());
+        self.physics_debug.draw(&self.world, self.renderer.gizmos());
+        if self.show_hierarchy {
+            draw_hierarchy(&self.world, self.renderer.gizmos());
+        }
+
+        if self.dof_focus_player {
+            let player = self.world.get::<&GlobalTransform>(self.player_entity).map(|gt| gt.0);
+            // In first person the player is at (or behind) the eye; keep the last focus then.
+            if let Ok(depth) = player.map(|m| -(view * m.w_axis).z) {
+                if depth > self.eye_height() {
+                    self.renderer.set_focus_distance(depth);
+                }
+            }
+        }
 
         self.renderer
             .draw_scene(&self.world, &self.meshes, &view, &proj, self.camera.position);
 
+        // World-space labels over the scene, under the 2D UI.
+        let labels = LabelView::new(proj * view, self.camera.position, self.window_size);
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+        }
+        draw_damage_numbers(&mut self.text_renderer, &self.world, &labels);
+        unsafe {
+            gl::Disable(gl::BLEND);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+
         // UI pass — render on top of the scene
         if self.game_state == GameState::Paused {
             let (w, h) = window.size();
@@ -330,6 +1271,49 @@ impl GameApp {
                 gl::Enable(gl::DEPTH_TEST);
             }
         }
+
+        if self.inspector.selected().is_some() {
+            let (w, h) = window.size();
+            let ui_proj = Mat4::orthographic_rh_gl(0.0, w as f32, h as f32, 0.0, -1.0, 1.0);
+
+            unsafe {
+                gl::Disable(gl::DEPTH_TEST);
+                gl::Enable(gl::BLEND);
+                gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            }
+
+            self.inspector.draw(&mut self.text_renderer, &self.world, &ui_proj);
+
+            unsafe {
+                gl::Disable(gl::BLEND);
+                gl::Enable(gl::DEPTH_TEST);
+            }
+        }
+
+        if self.entity_browser.is_visible() {
+            let (w, h) = window.size();
+            let ui_proj = Mat4::orthographic_rh_gl(0.0, w as f32, h as f32, 0.0, -1.0, 1.0);
+
+            unsafe {
+                gl::Disable(gl::DEPTH_TEST);
+                gl::Enable(gl::BLEND);
+                gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            }
+
+            self.entity_browser.draw(
+                &mut self.text_renderer,
+                &self.world,
+                w as f32,
+                h as f32,
+                &ui_proj,
+            );
+            self.entity_browser.draw_labels(&mut self.text_renderer, &self.world, &labels);
+
+            unsafe {
+                gl::Disable(gl::BLEND);
+                gl::Enable(gl::DEPTH_TEST);
+            }
+        }
     }
 
     fn tick_recorder(&mut self, dt: f32) -> bool {
@@ -352,3 +1336,32 @@ impl GameApp {
         true
     }
 }
+
+fn is_shift_held(input: &InputState) -> bool {
+    input.is_key_held(Scancode::LShift) || input.is_key_held(Scancode::RShift)
+}
+
+fn is_ctrl_held(input: &InputState) -> bool {
+    input.is_key_held(Scancode::LCtrl) || input.is_key_held(Scancode::RCtrl)
+}
+
+/// Debug keys F5–F10 toggle the systems in `SystemKind::ALL` order.
+fn toggle_key_system(key: Scancode) -> Option<SystemKind> {
+    const KEYS: [Scancode; 6] =
+        [Scancode::F5, Scancode::F6, Scancode::F7, Scancode::F8, Scancode::F9, Scancode::F10];
+    KEYS.iter().position(|&k| k == key).map(|i| SystemKind::ALL[i])
+}
+
+/// Camera occlusion: sweep a sphere of `radius` from `eye` along `to_desired` and return the
+/// distance travelled before it touches static geometry, or `None` if the path is clear.
+/// Infinite ground planes are skipped, as with the old ray test.
+fn camera_sweep(world: &World, eye: Vec3, to_desired: Vec3, radius: f32) -> Option<f32> {
+    let planes: Vec<Entity> = world
+        .query::<&Collider>()
+        .iter()
+        .filter(|(_, c)| matches!(c, Collider::Plane { .. }))
+        .map(|(e, _)| e)
+        .collect();
+    let t = sweep_sphere_static(world, radius, eye, to_desired, &planes);
+    (t < 1.0).then(|| t * to_desired.length())
+}
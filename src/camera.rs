@@ -2,11 +2,14 @@ use glam::{Mat4, Vec3};
 use sdl2::keyboard::Scancode;
 
 use crate::engine::input::InputState;
+use crate::engine::world_up::WorldUp;
 
 #[derive(PartialEq, Eq)]
 pub enum CameraMode {
     Player,
     Fly,
+    /// Free-flying camera while the player keeps simulating on scripted input.
+    Spectator,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -37,6 +40,19 @@ const MIN_ARM: f32 = 0.3;
 /// Speed at which the camera arm recovers toward full length after a wall clip (units/s).
 const ARM_RECOVERY_SPEED: f32 = 4.0;
 
+/// Seconds to ease the camera from its old position into the follow position after a
+/// mode change (see `begin_transition`).
+const TRANSITION_TIME: f32 = 0.4;
+
+/// Default clip planes.
+pub const DEFAULT_NEAR: f32 = 0.1;
+pub const DEFAULT_FAR: f32 = 1000.0;
+
+/// Seconds the mouse must be idle before auto-recenter engages.
+const RECENTER_DELAY: f32 = 1.0;
+/// Auto-recenter ease rate (fraction of the remaining angle per second).
+const RECENTER_RATE: f32 = 2.0;
+
 pub struct Camera {
     pub position: Vec3,
     pub yaw: f32,
@@ -44,6 +60,8 @@ pub struct Camera {
     pub speed: f32,
     pub sensitivity: f32,
     pub fov: f32,
+    /// World up axis; yaw turns about it and pitch tilts toward it.
+    pub up: WorldUp,
     pub mode: CameraMode,
     pub perspective: Perspective,
     /// Whether the player is holding free-look (C): camera pans without rotating the character.
@@ -61,6 +79,35 @@ pub struct Camera {
     effective_arm_back: f32,
     /// Current effective front arm length, reduced by wall collision and smoothly recovered.
     effective_arm_front: f32,
+    /// Third-person back: ease `yaw` behind `body_yaw` while running forward with the
+    /// mouse idle.
+    pub auto_recenter: bool,
+    /// Third person: the body turns toward the direction it's moving in instead of facing
+    /// `body_yaw`. Movement itself stays relative to `body_yaw`.
+    pub face_movement: bool,
+    /// Seconds since the mouse last moved (drives the auto-recenter dead time).
+    mouse_idle: f32,
+    /// Near clip plane distance. Set through `set_clip_planes` so near < far holds.
+    near: f32,
+    /// Far clip plane distance. Also caps the shadow cascade range (see `Renderer`).
+    far: f32,
+    /// Follow-position blend after a mode change: (start position, progress 0..1).
+    transition: Option<(Vec3, f32)>,
+}
+
+/// Startup camera options, from the command line.
+#[derive(Clone, Copy)]
+pub struct CameraSettings {
+    pub auto_recenter: bool,
+    pub face_movement: bool,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self { auto_recenter: false, face_movement: false, near: DEFAULT_NEAR, far: DEFAULT_FAR }
+    }
 }
 
 impl Camera {
@@ -72,6 +119,7 @@ impl Camera {
             speed: 5.0,
             sensitivity: 0.1,
             fov: 45.0,
+            up: WorldUp::Y,
             mode: CameraMode::Player,
             perspective: Perspective::ThirdPersonBack,
             free_look: false,
@@ -80,16 +128,53 @@ impl Camera {
             arm_length_front: DEFAULT_ARM_FRONT,
             effective_arm_back: DEFAULT_ARM_BACK,
             effective_arm_front: DEFAULT_ARM_FRONT,
+            auto_recenter: false,
+            face_movement: false,
+            mouse_idle: 0.0,
+            near: DEFAULT_NEAR,
+            far: DEFAULT_FAR,
+            transition: None,
         }
     }
 
+    /// Camera with startup settings applied.
+    pub fn with_settings(settings: CameraSettings) -> Self {
+        let mut camera = Self::new();
+        camera.auto_recenter = settings.auto_recenter;
+        camera.face_movement = settings.face_movement;
+        camera.set_clip_planes(settings.near, settings.far);
+        camera
+    }
+
+    pub fn near(&self) -> f32 {
+        self.near
+    }
+
+    pub fn far(&self) -> f32 {
+        self.far
+    }
+
+    /// Set the clip planes. `near` is kept positive and `far` strictly beyond it; a
+    /// smaller near reduces feet/hand clipping, a larger far shows distant geometry at
+    /// the cost of depth precision.
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) {
+        self.near = near.max(1e-3);
+        self.far = far.max(self.near * 2.0);
+    }
+
     pub fn toggle_mode(&mut self) {
         self.mode = match self.mode {
             CameraMode::Player => CameraMode::Fly,
-            CameraMode::Fly => CameraMode::Player,
+            CameraMode::Fly | CameraMode::Spectator => CameraMode::Player,
         };
     }
 
+    /// Ease from the current position into the follow position over `TRANSITION_TIME`
+    /// instead of snapping. Call when returning to `Player` from a free camera.
+    pub fn begin_transition(&mut self) {
+        self.transition = Some((self.position, 0.0));
+    }
+
     /// Cycle perspective and reset zoom state to defaults.
     pub fn toggle_perspective(&mut self) {
         self.perspective = match self.perspective {
@@ -126,22 +211,23 @@ impl Camera {
     }
 
     /// Compute the world-space eye position (base of camera raycast).
-    pub fn eye_pos(player_pos: Vec3, eye_height: f32) -> Vec3 {
-        player_pos + Vec3::Y * eye_height
+    pub fn eye_pos(&self, player_pos: Vec3, eye_height: f32) -> Vec3 {
+        player_pos + self.up.vector() * eye_height
     }
 
     /// Compute the desired (unoccluded) camera position and the ray from eye to it.
     /// Returns `(eye, desired_pos)`.
     pub fn desired_follow_pos(&self, player_pos: Vec3, eye_height: f32, capsule_radius: f32) -> (Vec3, Vec3) {
-        let eye = Self::eye_pos(player_pos, eye_height);
+        let eye = self.eye_pos(player_pos, eye_height);
+        let up = self.up.vector();
         let desired = match self.perspective {
             Perspective::ThirdPersonBack => {
                 let back = -self.front();
-                eye + back * self.arm_length_back + Vec3::Y * 0.5
+                eye + back * self.arm_length_back + up * 0.5
             }
             Perspective::ThirdPersonFront => {
                 let front = self.front();
-                eye + front * self.arm_length_front + Vec3::Y * 0.25
+                eye + front * self.arm_length_front + up * 0.25
             }
             Perspective::FirstPerson => {
                 eye + self.front() * capsule_radius
@@ -154,7 +240,8 @@ impl Camera {
     ///
     /// `eye`        — world-space eye position (origin of the camera ray)
     /// `desired`    — unclamped desired camera world position
-    /// `hit_dist`   — ray distance to the nearest static geometry hit, if any
+    /// `hit_dist`   — distance from `eye` toward `desired` at which the camera first touches
+    ///                static geometry, if any
     /// `dt`         — frame delta time for smooth arm recovery
     pub fn apply_occlusion(&mut self, eye: Vec3, desired: Vec3, hit_dist: Option<f32>, dt: f32) {
         match self.perspective {
@@ -190,6 +277,13 @@ impl Camera {
                 self.position = eye + ray_dir * *eff;
             }
         }
+
+        if let Some((from, t)) = self.transition {
+            let t = (t + dt / TRANSITION_TIME).min(1.0);
+            let ease = t * t * (3.0 - 2.0 * t);
+            self.position = from.lerp(self.position, ease);
+            self.transition = (t < 1.0).then_some((from, t));
+        }
     }
 
     /// Lerp `body_yaw` toward `camera.yaw`. Call every frame when `!free_look`.
@@ -215,6 +309,30 @@ impl Camera {
         }
     }
 
+    /// Auto-recenter: while moving forward in third-person back, ease `yaw` toward
+    /// `body_yaw` so the camera trails behind the direction of travel.
+    ///
+    /// Never fights the player: any mouse motion resets a `RECENTER_DELAY` dead time, and
+    /// free-look (where body and camera are deliberately apart) disables it.
+    pub fn tick_auto_recenter(&mut self, moving_forward: bool, mouse_moved: bool, dt: f32) {
+        self.mouse_idle = if mouse_moved { 0.0 } else { self.mouse_idle + dt };
+
+        if !self.auto_recenter
+            || !moving_forward
+            || self.free_look
+            || self.perspective != Perspective::ThirdPersonBack
+            || self.mouse_idle < RECENTER_DELAY
+        {
+            return;
+        }
+
+        let diff = {
+            let d = self.body_yaw - self.yaw;
+            d - 360.0 * (d / 360.0).round() // shortest path in [-180, 180]
+        };
+        self.yaw += diff * (RECENTER_RATE * dt).min(1.0);
+    }
+
     pub fn look(&mut self, mouse_dx: f32, mouse_dy: f32) {
         self.yaw += mouse_dx * self.sensitivity;
         self.pitch -= mouse_dy * self.sensitivity;
@@ -223,7 +341,7 @@ impl Camera {
 
     pub fn move_wasd(&mut self, input: &InputState, dt: f32) {
         let front = self.front();
-        let right = front.cross(Vec3::Y).normalize();
+        let right = front.cross(self.up.vector()).normalize();
         let velocity = self.speed * dt;
 
         if input.is_key_held(Scancode::W) {
@@ -243,26 +361,36 @@ impl Camera {
     pub fn front(&self) -> Vec3 {
         let yaw_rad = self.yaw.to_radians();
         let pitch_rad = self.pitch.to_radians();
-        Vec3::new(
+        let front = Vec3::new(
             yaw_rad.cos() * pitch_rad.cos(),
             pitch_rad.sin(),
             yaw_rad.sin() * pitch_rad.cos(),
         )
-        .normalize()
+        .normalize();
+        self.up.align() * front
     }
 
     pub fn view_matrix(&self) -> Mat4 {
         if self.perspective == Perspective::ThirdPersonFront {
             // Look back toward the player (opposite of front direction)
             let back = -self.front();
-            Mat4::look_at_rh(self.position, self.position + back, Vec3::Y)
+            Mat4::look_at_rh(self.position, self.position + back, self.up.vector())
         } else {
             let front = self.front();
-            Mat4::look_at_rh(self.position, self.position + front, Vec3::Y)
+            Mat4::look_at_rh(self.position, self.position + front, self.up.vector())
         }
     }
 
     pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
-        Mat4::perspective_rh_gl(self.fov.to_radians(), aspect, 0.1, 1000.0)
+        Mat4::perspective_rh_gl(self.fov.to_radians(), aspect, self.near, self.far)
+    }
+
+    /// Distance from the eye to the near plane's corners. A sphere this size around the
+    /// camera contains the whole near plane, so keeping it out of walls keeps the near plane
+    /// from clipping into them.
+    pub fn near_plane_radius(&self, aspect: f32) -> f32 {
+        let half_height = self.near * (self.fov.to_radians() * 0.5).tan();
+        let half_width = half_height * aspect;
+        Vec3::new(half_width, half_height, self.near).length()
     }
 }
@@ -20,6 +20,9 @@ pub struct Held;
 /// State for the grab/throw system, attached to the player entity.
 pub struct GrabState {
     pub held_entity: Option<Entity>,
+    /// Grabbable the player is aimed at and could pick up right now (occluders ignored).
+    /// Refreshed every frame while nothing is held; the renderer x-rays it.
+    pub target: Option<Entity>,
     pub wind_up_time: f32,
     pub is_winding: bool,
     /// Player-local rotation of the held entity (rotates with player via parenting).
@@ -38,6 +41,7 @@ impl GrabState {
     pub fn new() -> Self {
         Self {
             held_entity: None,
+            target: None,
             wind_up_time: 0.0,
             is_winding: false,
             held_rotation: Quat::IDENTITY,
@@ -46,6 +50,57 @@ impl GrabState {
             yaw_lock: None,
         }
     }
+
+    /// Forget the held entity and everything tracked while holding it. Only the state:
+    /// detaching the entity itself is up to the caller.
+    pub fn release(&mut self) {
+        self.held_entity = None;
+        self.wind_up_time = 0.0;
+        self.is_winding = false;
+        self.held_velocity = Vec3::ZERO;
+        self.yaw_lock = None;
+    }
+}
+
+/// Arm-aim overlay state, attached to the player. `arm_aim_system` rotates the upper arms
+/// from their rest pose toward an aim target, blended by `weight`.
+pub struct ArmAim {
+    /// Current blend weight in [0, 1]; eases toward 1 while there is a target.
+    pub weight: f32,
+    /// Upper-arm rest rotations (player-local), restored as the weight fades out.
+    pub left_rest: Quat,
+    pub right_rest: Quat,
+}
+
+/// Walk-cycle state, attached to the player. `locomotion_system` swings the legs and arms
+/// from it according to which way the body is moving relative to where it faces.
+pub struct Locomotion {
+    /// Stride cycle position (radians); advances with the distance covered.
+    pub phase: f32,
+    /// Smoothed horizontal velocity in body space (+X = left, +Z = forward).
+    pub velocity: Vec3,
+    /// Upper-leg rest positions (left, right), player-local.
+    pub hips: [Vec3; 2],
+    /// Height of the hip joint above an upper leg's origin; the leg swings about it.
+    pub hip_pivot: f32,
+}
+
+/// Light-combo bookkeeping for sword attacks, attached to the player.
+pub struct SwordCombo {
+    /// Combo swing the next click starts, if it lands inside `window`.
+    pub next_index: u8,
+    /// Time left to chain into `next_index`; once it runs out the combo resets.
+    pub window: f32,
+    /// Lockout after the final swing; no attack can start until it reaches zero.
+    pub recovery: f32,
+    /// A click arrived mid-swing; chain straight into the next swing when it ends.
+    pub queued: bool,
+}
+
+impl SwordCombo {
+    pub fn new() -> Self {
+        Self { next_index: 0, window: 0.0, recovery: 0.0, queued: false }
+    }
 }
 
 /// Whether the sword is sheathed at the hip or wielded in hand.
@@ -99,6 +154,10 @@ pub enum PlayerState {
     Sheathing { timer: f32 },
     /// Sword transition: unsheathing. Timer counts up.
     Unsheathing { timer: f32 },
+    /// Sword swing. Timer counts up; `combo_index` picks the swing in the light combo
+    /// (0-based). `hits` lists targets already struck this swing so each one is only
+    /// hit once.
+    Attacking { timer: f32, combo_index: u8, hits: Vec<Entity> },
 }
 
 /// FSM component attached to the player entity.
@@ -119,4 +178,7 @@ pub struct CharacterBody {
     pub right_upper_leg: Entity,
     pub right_lower_leg: Entity,
     pub sword: Entity,
+    /// Eye level above the body's origin, from the rig's proportions. The camera follows
+    /// (and casts its occlusion ray from) this point.
+    pub eye_height: f32,
 }
@@ -0,0 +1,44 @@
+use glam::Vec3;
+use hecs::Entity;
+
+/// A melee hit, emitted once per target per swing by `sword_attack_system`.
+#[allow(dead_code)]
+pub struct HitEvent {
+    pub attacker: Entity,
+    /// Physics root of the entity that was struck.
+    pub target: Entity,
+    /// World-space point on the blade where the overlap was found.
+    pub point: Vec3,
+    /// Unit direction from the blade into the target (geometric contact normal).
+    pub normal: Vec3,
+    /// Attacker's horizontal facing at the time of the hit. Knockback pushes along this
+    /// when present and falls back to `normal` otherwise.
+    pub attacker_forward: Option<Vec3>,
+    pub damage: f32,
+    /// Horizontal knockback speed; grows through the combo.
+    pub knockback: f32,
+}
+
+/// How long a damage number stays up (seconds).
+pub const DAMAGE_NUMBER_LIFETIME: f32 = 1.0;
+
+/// A floating damage popup: a transient entity that rises from where a hit landed and
+/// fades out, then is despawned by its `Lifetime`.
+pub struct DamageNumber {
+    pub value: f32,
+    pub elapsed: f32,
+    /// Current world position of the label's anchor.
+    pub world_pos: Vec3,
+}
+
+impl DamageNumber {
+    pub fn new(value: f32, world_pos: Vec3) -> Self {
+        Self { value, elapsed: 0.0, world_pos }
+    }
+
+    /// Fully opaque for the first half of its life, then fading linearly to zero.
+    pub fn opacity(&self) -> f32 {
+        let t = self.elapsed / DAMAGE_NUMBER_LIFETIME;
+        ((1.0 - t) * 2.0).clamp(0.0, 1.0)
+    }
+}
@@ -1,15 +1,38 @@
 use glam::Vec3;
+use serde::{Deserialize, Serialize};
 
-/// Directional light component (sun-like). Casts shadows via cascaded shadow mapping.
+/// Directional light component (sun-like). The first one with `casts_shadows` set gets
+/// cascaded shadow mapping; the rest light the scene without shadows.
+#[derive(Clone, Copy, Deserialize, Serialize)]
 pub struct DirectionalLight {
     pub direction: Vec3,
     pub color: Vec3,
     pub intensity: f32,
     /// Per-cascade shadow map resolution (width = height). Default 2048.
     pub shadow_resolution: u32,
+    pub casts_shadows: bool,
+    /// Camera distance covered by this light's shadow cascades (the last cascade's far
+    /// split). Smaller is sharper up close with no distant shadows. `None` uses the
+    /// renderer default (`--shadow-distance`).
+    pub shadow_distance: Option<f32>,
+}
+
+/// Distance falloff curve for a `PointLight`. Discriminants match the `ATTEN_*` constants in
+/// `cel.frag`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[allow(dead_code)]
+pub enum Attenuation {
+    /// `1 / (constant + linear·d + quadratic·d²)` with coefficients derived from `radius`.
+    Preset = 0,
+    /// Physically based `1 / d²` (clamped very close to the light). Ignores `radius`.
+    InverseSquare = 1,
+    /// Inverse square multiplied by a window that eases to exactly zero at `radius` with zero
+    /// slope, so the light's edge has no visible boundary.
+    Windowed = 2,
 }
 
 /// Point light component with distance attenuation.
+#[derive(Clone, Copy, Deserialize, Serialize)]
 pub struct PointLight {
     pub color: Vec3,
     pub intensity: f32,
@@ -17,6 +40,7 @@ pub struct PointLight {
     pub constant: f32,
     pub linear: f32,
     pub quadratic: f32,
+    pub attenuation: Attenuation,
 }
 
 impl PointLight {
@@ -28,11 +52,20 @@ impl PointLight {
             constant: 1.0,
             linear: 4.5 / radius,
             quadratic: 75.0 / (radius * radius),
+            attenuation: Attenuation::Preset,
         }
     }
+
+    /// Change the reach, re-deriving the preset falloff coefficients to match.
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+        self.linear = 4.5 / radius;
+        self.quadratic = 75.0 / (radius * radius);
+    }
 }
 
 /// Spot light component with cone angle and falloff.
+#[derive(Clone, Copy, Deserialize, Serialize)]
 pub struct SpotLight {
     pub direction: Vec3,
     pub color: Vec3,
@@ -59,4 +92,30 @@ impl SpotLight {
             quadratic: 75.0 / (radius * radius),
         }
     }
+
+    /// Change the reach, re-deriving the falloff coefficients to match.
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+        self.linear = 4.5 / radius;
+        self.quadratic = 75.0 / (radius * radius);
+    }
+}
+
+/// Smoothly varying intensity for firelight. `flicker_system` overwrites the intensity of
+/// the `PointLight` / `SpotLight` on the same entity each frame with `base_intensity` plus a
+/// sum of sines scaled by `amplitude`, so the wobble is continuous rather than per-frame noise.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct Flicker {
+    pub base_intensity: f32,
+    pub amplitude: f32,
+    /// Rate of the slowest wobble (Hz); faster layers run at fixed multiples of it.
+    pub frequency: f32,
+    /// Elapsed time driving the wave (seconds).
+    pub time: f32,
+}
+
+impl Flicker {
+    pub fn new(base_intensity: f32, amplitude: f32, frequency: f32) -> Self {
+        Self { base_intensity, amplitude, frequency, time: 0.0 }
+    }
 }
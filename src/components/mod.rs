@@ -1,11 +1,15 @@
 mod character;
+mod combat;
 mod lighting;
 mod physics;
 mod render;
+mod scene;
 mod transform;
 
 pub use character::*;
+pub use combat::*;
 pub use lighting::*;
 pub use physics::*;
 pub use render::*;
+pub use scene::*;
 pub use transform::*;
@@ -1,5 +1,6 @@
 use glam::Vec3;
 use hecs::Entity;
+use serde::{Deserialize, Serialize};
 
 /// Linear velocity in world space.
 pub struct Velocity(pub Vec3);
@@ -7,8 +8,8 @@ pub struct Velocity(pub Vec3);
 /// Per-entity acceleration (accumulated forces / mass).
 pub struct Acceleration(pub Vec3);
 
-/// Entity mass in kilograms.
-#[allow(dead_code)]
+/// Entity mass in kilograms. Collisions between dynamic bodies share their push and impulse
+/// by inverse mass; bodies without one weigh 1 kg.
 pub struct Mass(pub f32);
 
 /// Marker: entity is affected by gravity.
@@ -16,6 +17,7 @@ pub struct GravityAffected;
 
 /// Collision shape attached to an entity.
 #[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 pub enum Collider {
     Sphere { radius: f32 },
     Capsule { radius: f32, height: f32 },
@@ -37,12 +39,41 @@ pub struct Friction(pub f32);
 /// 0.0 = no drag, higher values = faster deceleration.
 pub struct Drag(pub f32);
 
+/// Air resistance growing with speed squared: a deceleration of `k * speed²` opposing the
+/// velocity, so fast bodies slow sharply, slow ones barely, and a falling body settles at a
+/// terminal speed of `sqrt(g / k)`.
+pub struct QuadraticDrag(pub f32);
+
 /// Collision contact produced by the detection phase.
 pub struct CollisionEvent {
     pub entity_a: Entity,
     pub entity_b: Entity,
     pub contact_normal: Vec3,
     pub penetration_depth: f32,
+    /// Closing speed along `contact_normal` at detection time (m/s): positive when the
+    /// bodies approach each other, near zero for resting contacts. Filled in by
+    /// `collision_system`; zero for events from `query_collisions_at`.
+    pub relative_normal_velocity: f32,
+}
+
+/// Lifecycle of a contact between two colliders across physics ticks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContactStatus {
+    /// Touching this tick but not the previous one.
+    Began,
+    /// Touching this tick and the previous one.
+    Persisted,
+    /// Touched the previous tick but not this one (or one of them was despawned).
+    Ended,
+}
+
+/// One collider pair's contact status for a tick, from `ContactTracker::update`.
+/// The pair is unordered; `entity_a` is simply the one with the lower id.
+#[allow(dead_code)]
+pub struct ContactChange {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+    pub status: ContactStatus,
 }
 
 /// Marker: entity is touching the ground (set each physics frame).
@@ -52,3 +83,11 @@ pub struct Grounded;
 /// Updated at the start of each physics step; used by transform propagation
 /// to lerp between prev and current position by the accumulator alpha.
 pub struct PreviousPosition(pub Vec3);
+
+/// Marker: entity ignores gravity, integration and collision (noclip fly mode).
+/// Its position is driven directly by the fly camera.
+pub struct Noclip;
+
+/// Seconds left after leaving noclip during which collision pushes on this entity are
+/// rate-limited, so exiting inside geometry eases out instead of launching the body.
+pub struct NoclipRecovery(pub f32);
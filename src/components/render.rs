@@ -1,14 +1,125 @@
-use glam::Vec3;
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
 
 /// Index into the MeshStore resource.
 #[derive(Clone, Copy)]
 pub struct MeshHandle(pub usize);
 
+/// The generated primitive an entity's `MeshHandle` was built from, kept beside the handle
+/// so a saved scene can describe the mesh and rebuild it on load.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum MeshShape {
+    Sphere { radius: f32, stacks: u32, sectors: u32 },
+    TaperedBox { top_w: f32, top_d: f32, bot_w: f32, bot_d: f32, height: f32 },
+    Capsule { radius: f32, height: f32, sectors: u32, stacks: u32 },
+    /// The fixed sword model from `sword_data`.
+    Sword,
+}
+
 /// RGB color applied to an entity for rendering.
+#[derive(Clone, Copy)]
 pub struct Color(pub Vec3);
 
 /// Checkerboard pattern using primary Color and this secondary color.
+#[derive(Clone, Copy)]
 pub struct Checkerboard(pub Vec3);
 
 /// Marker: entity is hidden from rendering but still participates in physics/collision.
 pub struct Hidden;
+
+/// Mirror the scene in this surface with screen-space reflections, blended over its own
+/// shading at `strength` (0–1). Only what is on screen can be reflected; reflections of
+/// anything else fade to the fog colour. Needs the renderer's offscreen path, which any
+/// reflective entity switches on.
+#[derive(Clone, Copy)]
+pub struct Reflective {
+    pub strength: f32,
+}
+
+/// Reflect the scene in this entity's flat top like a mirror or still water, blended over
+/// its own shading at `strength` (0–1). The scene is rendered a second time, flipped about
+/// the horizontal plane at the top of the entity's collider (a ground plane or a box), so
+/// unlike `Reflective` it shows what is off screen too. Only the first mirror is rendered.
+#[derive(Clone, Copy)]
+pub struct PlanarMirror {
+    pub strength: f32,
+}
+
+/// Keep the entity turned toward the camera (sprites, world-space health bars): its local +Z
+/// points at the camera and +Y stays as close to world up as the view allows.
+/// `Cylindrical` only turns about the vertical axis, so the entity stays upright.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Billboard {
+    Spherical,
+    Cylindrical,
+}
+
+/// Marker: entity is left out of the shadow map pass, so it casts no shadow. It is still
+/// drawn (and still receives shadows unless it also has `NoShadowReceive`).
+#[allow(dead_code)]
+pub struct NoShadowCast;
+
+/// Marker: entity is drawn without shadowing from the directional light. It still casts a
+/// shadow unless it also has `NoShadowCast`.
+#[allow(dead_code)]
+pub struct NoShadowReceive;
+
+/// Draw this entity's silhouette through anything in front of it, in `color` (sRGB), so it
+/// stays findable behind walls (quest items, points of interest). Only the hidden parts are
+/// drawn; where the entity is in plain view it renders normally.
+#[allow(dead_code)]
+pub struct XRay {
+    pub color: Vec3,
+}
+
+/// Largest decal edge length (metres). Decals are flat quads, so on anything but a flat
+/// surface a big one visibly floats off or cuts in.
+pub const MAX_DECAL_SIZE: f32 = 3.0;
+
+/// A flat mark (scorch, footprint, hit mark) lying on a surface, drawn blended over the
+/// opaque scene. Give it a `Lifetime` to have it despawn; it fades out over its last
+/// moments.
+pub struct Decal {
+    pub position: Vec3,
+    /// Unit surface normal the decal faces along.
+    pub normal: Vec3,
+    /// Unit in-surface direction of the decal's length (`size.y`).
+    pub tangent: Vec3,
+    /// Width and length (metres), each clamped to `MAX_DECAL_SIZE`.
+    pub size: Vec2,
+    /// sRGB colour, alpha taken from the decal mask.
+    pub color: Vec3,
+}
+
+impl Decal {
+    /// `tangent` only picks the in-plane orientation; it is projected onto the surface, with
+    /// an arbitrary direction used if it is (nearly) parallel to `normal`.
+    pub fn new(position: Vec3, normal: Vec3, tangent: Vec3, size: Vec2, color: Vec3) -> Self {
+        let normal = normal.normalize();
+        let tangent = (tangent - normal * tangent.dot(normal))
+            .try_normalize()
+            .unwrap_or_else(|| normal.any_orthonormal_vector());
+        Self {
+            position,
+            normal,
+            tangent,
+            size: size.clamp(Vec2::ZERO, Vec2::splat(MAX_DECAL_SIZE)),
+            color,
+        }
+    }
+}
+
+/// Leaves footprint decals behind an entity while it walks on the ground.
+pub struct FootprintTrail {
+    /// Ground distance covered since the last print.
+    pub distance: f32,
+    /// Which foot lands next.
+    pub left: bool,
+}
+
+impl FootprintTrail {
+    pub fn new() -> Self {
+        Self { distance: 0.0, left: true }
+    }
+}
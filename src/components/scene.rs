@@ -0,0 +1,18 @@
+use hecs::Entity;
+
+/// Marker: entity belongs to the authored scene and is written back when the editor saves
+/// it. Scene files tag what they spawn; anything spawned at runtime (thrown objects,
+/// particles, decals) lacks it and is left out of saves.
+pub struct Persistent;
+
+/// Seconds until the entity despawns itself, with everything parented under it. Ticked
+/// down by `lifetime_system`; for transient objects that would otherwise pile up.
+pub struct Lifetime {
+    pub remaining: f32,
+}
+
+/// Reported by `lifetime_system` for each entity it despawned (children included), so
+/// anything holding on to the entity can let go.
+pub struct Despawned {
+    pub entity: Entity,
+}
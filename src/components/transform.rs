@@ -1,7 +1,11 @@
+use std::collections::{HashSet, VecDeque};
+
 use glam::{Mat4, Quat, Vec3};
 use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
 
 /// Spatial transform with position, rotation, and scale (local space).
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 pub struct LocalTransform {
     pub position: Vec3,
     pub rotation: Quat,
@@ -32,6 +36,12 @@ pub struct Parent(pub Entity);
 /// Lists child entities in the transform hierarchy.
 pub struct Children(pub Vec<Entity>);
 
+/// Marker: this entity ignores its parent's scale. It still follows the parent's position
+/// and rotation, and its own `LocalTransform::scale` applies as usual. Useful under
+/// non-uniformly scaled parents, where inherited scale would shear a rotated child.
+#[allow(dead_code)]
+pub struct NoScaleInherit;
+
 /// Attach `child` under `parent` in the transform hierarchy.
 pub fn add_child(world: &mut World, parent: Entity, child: Entity) {
     let has_children = world.get::<&Children>(parent).is_ok();
@@ -48,10 +58,91 @@ pub fn add_child(world: &mut World, parent: Entity, child: Entity) {
 }
 
 /// Detach `child` from `parent` in the transform hierarchy.
-#[allow(dead_code)]
 pub fn remove_child(world: &mut World, parent: Entity, child: Entity) {
     if let Ok(mut children) = world.get::<&mut Children>(parent) {
         children.0.retain(|&e| e != child);
     }
     let _ = world.remove_one::<Parent>(child);
 }
+
+/// Collect every entity below `root` in the transform hierarchy (BFS order, `root` excluded).
+/// Entities already visited are skipped, so a malformed `Children` cycle terminates.
+pub fn descendants(world: &World, root: Entity) -> Vec<Entity> {
+    let mut result = Vec::new();
+    let mut visited = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(entity) = queue.pop_front() {
+        if let Ok(children) = world.get::<&Children>(entity) {
+            for &child in &children.0 {
+                if visited.insert(child) {
+                    result.push(child);
+                    queue.push_back(child);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Despawn `entity` and every entity below it in the transform hierarchy, detaching it
+/// from its parent first. Returns the despawned entities, `entity` first.
+pub fn despawn_recursive(world: &mut World, entity: Entity) -> Vec<Entity> {
+    if let Some(parent) = world.get::<&Parent>(entity).ok().map(|p| p.0) {
+        remove_child(world, parent, entity);
+    }
+    let mut removed = vec![entity];
+    removed.extend(descendants(world, entity));
+    removed.retain(|&e| world.despawn(e).is_ok());
+    removed
+}
+
+/// Walk `Parent` links upward from `entity`, nearest parent first (`entity` excluded).
+/// Stops if a cycle is detected.
+pub fn ancestors(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut result = Vec::new();
+    let mut current = entity;
+    while let Ok(parent) = world.get::<&Parent>(current) {
+        let parent = parent.0;
+        if parent == entity || result.contains(&parent) {
+            break;
+        }
+        result.push(parent);
+        current = parent;
+    }
+    result
+}
+
+/// World matrix of `entity` composed from the `LocalTransform` chain up to its root.
+/// Unlike `GlobalTransform` this is current as of the last simulation write (no render
+/// interpolation, no wait for the next propagation pass).
+pub fn world_matrix(world: &World, entity: Entity) -> Mat4 {
+    let local = |e: Entity| {
+        world.get::<&LocalTransform>(e).map(|lt| lt.matrix()).unwrap_or(Mat4::IDENTITY)
+    };
+    ancestors(world, entity)
+        .into_iter()
+        .fold(local(entity), |acc, parent| local(parent) * acc)
+}
+
+/// Move `child` under `new_parent` (or to the world root for `None`) while keeping its
+/// world position, rotation and scale unchanged: the child's `LocalTransform` is rewritten
+/// relative to the new parent.
+pub fn reparent_keep_world(world: &mut World, child: Entity, new_parent: Option<Entity>) {
+    let child_world = world_matrix(world, child);
+    let parent_world = new_parent.map(|p| world_matrix(world, p)).unwrap_or(Mat4::IDENTITY);
+
+    if let Some(old_parent) = world.get::<&Parent>(child).ok().map(|p| p.0) {
+        remove_child(world, old_parent, child);
+    }
+    if let Some(parent) = new_parent {
+        add_child(world, parent, child);
+    }
+
+    let (scale, rotation, position) =
+        (parent_world.inverse() * child_world).to_scale_rotation_translation();
+    if let Ok(mut lt) = world.get::<&mut LocalTransform>(child) {
+        lt.position = position;
+        lt.rotation = rotation;
+        lt.scale = scale;
+    }
+}
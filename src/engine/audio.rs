@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use sdl2::audio::{
+    AudioCVT, AudioCallback, AudioDevice, AudioFormat, AudioSpecDesired, AudioSpecWAV,
+};
+use sdl2::Sdl;
+use serde::{Deserialize, Serialize};
+
+/// Sounds are looked up by name as `<AUDIO_DIR>/<name>.wav`.
+const AUDIO_DIR: &str = "assets/audio";
+const SAMPLE_RATE: i32 = 44_100;
+const CHANNELS: u8 = 2;
+/// Most sound effects mixed at once; further ones are dropped until a slot frees up.
+const MAX_SFX_VOICES: usize = 32;
+/// Music gain while the game is paused, and how long the duck takes (seconds).
+const PAUSED_MUSIC_GAIN: f32 = 0.3;
+const DUCK_TIME: f32 = 0.25;
+
+/// Volume per sound category (0–1). What a channel plays at is its own volume × `master`.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Volumes {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Self { master: 1.0, sfx: 1.0, music: 1.0 }
+    }
+}
+
+impl Volumes {
+    fn clamped(self) -> Self {
+        Self {
+            master: self.master.clamp(0.0, 1.0),
+            sfx: self.sfx.clamp(0.0, 1.0),
+            music: self.music.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// A decoded sound: interleaved stereo f32 at `SAMPLE_RATE`.
+type Clip = Arc<[f32]>;
+
+struct Voice {
+    clip: Clip,
+    /// Next sample index into `clip`.
+    cursor: usize,
+    looping: bool,
+}
+
+impl Voice {
+    fn new(clip: Clip, looping: bool) -> Self {
+        Self { clip, cursor: 0, looping }
+    }
+
+    /// Next sample, or `None` once a one-shot has played out.
+    fn next(&mut self) -> Option<f32> {
+        if self.cursor >= self.clip.len() {
+            if !self.looping || self.clip.is_empty() {
+                return None;
+            }
+            self.cursor = 0;
+        }
+        let sample = self.clip[self.cursor];
+        self.cursor += 1;
+        Some(sample)
+    }
+}
+
+/// A music track fading towards `target` gain (1 = playing, 0 = fading out).
+struct MusicVoice {
+    voice: Voice,
+    gain: f32,
+    target: f32,
+    /// Gain change per stereo frame.
+    rate: f32,
+}
+
+/// Runs on SDL's audio thread: sums the music and effect voices into the output buffer.
+struct Mixer {
+    music: Vec<MusicVoice>,
+    /// World sound effects; held in place while paused.
+    sfx: Vec<Voice>,
+    /// Interface sounds; these keep playing while paused.
+    ui: Vec<Voice>,
+    paused: bool,
+    /// Current music duck gain, easing towards 1 or `PAUSED_MUSIC_GAIN`.
+    duck: f32,
+    /// Read per sample, so volume changes apply to sounds already playing.
+    volumes: Volumes,
+}
+
+impl AudioCallback for Mixer {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        let duck_step = 1.0 / (DUCK_TIME * SAMPLE_RATE as f32);
+        let duck_target = if self.paused { PAUSED_MUSIC_GAIN } else { 1.0 };
+        for frame in out.chunks_mut(CHANNELS as usize) {
+            self.duck += (duck_target - self.duck).clamp(-duck_step, duck_step);
+            for track in &mut self.music {
+                track.gain = if track.gain < track.target {
+                    (track.gain + track.rate).min(track.target)
+                } else {
+                    (track.gain - track.rate).max(track.target)
+                };
+            }
+            for sample in frame.iter_mut() {
+                let mut music = 0.0;
+                for track in &mut self.music {
+                    music += track.voice.next().unwrap_or(0.0) * track.gain;
+                }
+                let mut sfx = 0.0;
+                if !self.paused {
+                    for voice in &mut self.sfx {
+                        sfx += voice.next().unwrap_or(0.0);
+                    }
+                }
+                for voice in &mut self.ui {
+                    sfx += voice.next().unwrap_or(0.0);
+                }
+                let mix = music * self.duck * self.volumes.music + sfx * self.volumes.sfx;
+                *sample = (mix * self.volumes.master).clamp(-1.0, 1.0);
+            }
+        }
+        self.music.retain(|track| track.target > 0.0 || track.gain > 0.0);
+        self.sfx.retain(|voice| voice.cursor < voice.clip.len());
+        self.ui.retain(|voice| voice.cursor < voice.clip.len());
+    }
+}
+
+/// Sound output: looping background music on its own channel with crossfades, plus
+/// one-shot sound effects, each with an independent volume. Every call is a no-op when
+/// there is no audio device. Clips load on first use and stay cached; a missing or
+/// unreadable file is reported once and then plays as silence.
+pub struct Audio {
+    device: Option<AudioDevice<Mixer>>,
+    clips: HashMap<String, Option<Clip>>,
+    /// Name of the track music is playing or fading in.
+    music: Option<String>,
+}
+
+impl Audio {
+    /// Audio with no device; nothing plays.
+    pub fn disabled() -> Self {
+        Self { device: None, clips: HashMap::new(), music: None }
+    }
+
+    pub fn new(sdl: &Sdl) -> Self {
+        let desired = AudioSpecDesired {
+            freq: Some(SAMPLE_RATE),
+            channels: Some(CHANNELS),
+            samples: None,
+        };
+        let device = sdl.audio().and_then(|audio| {
+            audio.open_playback(None, &desired, |_| Mixer {
+                music: Vec::new(),
+                sfx: Vec::new(),
+                ui: Vec::new(),
+                paused: false,
+                duck: 1.0,
+                volumes: Volumes::default(),
+            })
+        });
+        let device = match device {
+            Ok(device) => {
+                device.resume();
+                Some(device)
+            }
+            Err(e) => {
+                eprintln!("Audio disabled: {e}");
+                None
+            }
+        };
+        Self { device, clips: HashMap::new(), music: None }
+    }
+
+    /// Switch the background music to `name`, looping, crossfading from the current track
+    /// over `fade` seconds (0 = cut). Asking for the track already playing does nothing.
+    pub fn play_music(&mut self, name: &str, fade: f32) {
+        if self.music.as_deref() == Some(name) || self.device.is_none() {
+            return;
+        }
+        self.music = Some(name.to_string());
+        let clip = self.clip(name);
+        let Some(device) = &mut self.device else {
+            return;
+        };
+        let rate = if fade > 0.0 { 1.0 / (fade * SAMPLE_RATE as f32) } else { 1.0 };
+        let mut mixer = device.lock();
+        for track in &mut mixer.music {
+            track.target = 0.0;
+            track.rate = rate;
+        }
+        if let Some(clip) = clip {
+            let gain = if fade > 0.0 { 0.0 } else { 1.0 };
+            mixer.music.push(MusicVoice { voice: Voice::new(clip, true), gain, target: 1.0, rate });
+        }
+    }
+
+    /// Fade the music out over `fade` seconds.
+    #[allow(dead_code)]
+    pub fn stop_music(&mut self, fade: f32) {
+        self.music = None;
+        let Some(device) = &mut self.device else {
+            return;
+        };
+        let rate = if fade > 0.0 { 1.0 / (fade * SAMPLE_RATE as f32) } else { 1.0 };
+        for track in &mut device.lock().music {
+            track.target = 0.0;
+            track.rate = rate;
+        }
+    }
+
+    /// Play `name` once as a world sound effect (held while paused).
+    pub fn play_sfx(&mut self, name: &str) {
+        self.play_one_shot(name, false);
+    }
+
+    /// Play `name` once as an interface sound, which plays even while paused. Uses the
+    /// sound effect volume.
+    pub fn play_ui(&mut self, name: &str) {
+        self.play_one_shot(name, true);
+    }
+
+    fn play_one_shot(&mut self, name: &str, ui: bool) {
+        if self.device.is_none() {
+            return;
+        }
+        let Some(clip) = self.clip(name) else {
+            return;
+        };
+        let Some(device) = &mut self.device else {
+            return;
+        };
+        let mut mixer = device.lock();
+        let voices = if ui { &mut mixer.ui } else { &mut mixer.sfx };
+        if voices.len() < MAX_SFX_VOICES {
+            voices.push(Voice::new(clip, false));
+        }
+    }
+
+    /// Pause hook: hold world sound effects where they are and duck the music; `false`
+    /// resumes both. Interface sounds are unaffected.
+    pub fn set_paused(&mut self, paused: bool) {
+        if let Some(device) = &mut self.device {
+            device.lock().paused = paused;
+        }
+    }
+
+    /// Set the category volumes; takes effect immediately, including on playing sounds.
+    pub fn set_volumes(&mut self, volumes: Volumes) {
+        if let Some(device) = &mut self.device {
+            device.lock().volumes = volumes.clamped();
+        }
+    }
+
+    fn clip(&mut self, name: &str) -> Option<Clip> {
+        self.clips
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let path = PathBuf::from(AUDIO_DIR).join(format!("{name}.wav"));
+                load_wav(&path)
+                    .map_err(|e| eprintln!("Failed to load sound {}: {e}", path.display()))
+                    .ok()
+            })
+            .clone()
+    }
+}
+
+/// Decode a WAV file and convert it to the mixer's format.
+fn load_wav(path: &Path) -> Result<Clip, String> {
+    let wav = AudioSpecWAV::load_wav(path)?;
+    let cvt = AudioCVT::new(
+        wav.format,
+        wav.channels,
+        wav.freq,
+        AudioFormat::f32_sys(),
+        CHANNELS,
+        SAMPLE_RATE,
+    )?;
+    let bytes = cvt.convert(wav.buffer().to_vec());
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
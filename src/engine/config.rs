@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::audio::Volumes;
+
+/// User settings kept between runs, as a RON file:
+///
+/// ```ron
+/// Config(
+///     volumes: (master: 0.8, sfx: 1.0, music: 0.5),
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename = "Config", default)]
+pub struct Config {
+    pub volumes: Volumes,
+}
+
+impl Config {
+    /// Read `path`, falling back to defaults when it doesn't exist yet or can't be parsed
+    /// (reported, not fatal: a broken settings file shouldn't stop the game starting).
+    pub fn load(path: &Path) -> Self {
+        let Ok(source) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        ron::from_str(&source).unwrap_or_else(|e| {
+            eprintln!("Ignoring settings in {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) {
+        let config = ron::ser::PrettyConfig::new().depth_limit(2);
+        let result = ron::ser::to_string_pretty(self, config)
+            .map_err(|e| e.to_string())
+            .and_then(|source| std::fs::write(path, source + "\n").map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Failed to save settings to {}: {e}", path.display());
+        }
+    }
+}
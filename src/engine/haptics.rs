@@ -0,0 +1,55 @@
+use sdl2::controller::GameController;
+use sdl2::{GameControllerSubsystem, Sdl};
+
+/// Gamepad rumble on the controller `InputState` reads, as passed to `follow`. Every call is
+/// a no-op when there is no controller, no controller subsystem, or the pad can't rumble.
+pub struct Haptics {
+    subsystem: Option<GameControllerSubsystem>,
+    controller: Option<GameController>,
+}
+
+impl Haptics {
+    /// Haptics with no backend; `rumble` does nothing.
+    pub fn disabled() -> Self {
+        Self { subsystem: None, controller: None }
+    }
+
+    pub fn new(sdl: &Sdl) -> Self {
+        Self { subsystem: sdl.game_controller().ok(), controller: None }
+    }
+
+    /// Rumble the controller with SDL instance id `instance_id` (`InputState::controller_id`),
+    /// or none. Call every frame; the pad is only reopened when the id changes.
+    pub fn follow(&mut self, instance_id: Option<u32>) {
+        if self.controller.as_ref().map(|c| c.instance_id()) == instance_id {
+            return;
+        }
+        self.controller = None;
+        let (Some(subsystem), Some(id)) = (&self.subsystem, instance_id) else {
+            return;
+        };
+        // SDL hands back another reference to a pad that is already open.
+        let count = subsystem.num_joysticks().unwrap_or(0);
+        self.controller = (0..count)
+            .filter(|&i| subsystem.is_game_controller(i))
+            .filter_map(|i| subsystem.open(i).ok())
+            .find(|c| c.instance_id() == id);
+    }
+
+    /// Rumble for `duration` seconds at `strength` in [0, 1] (clamped). The low-frequency
+    /// motor carries the weight of the impact; the high-frequency one adds a lighter buzz.
+    pub fn rumble(&mut self, strength: f32, duration: f32) {
+        let Some(controller) = &mut self.controller else {
+            return;
+        };
+        let strength = strength.clamp(0.0, 1.0);
+        if strength <= 0.0 {
+            return;
+        }
+        let low = (strength * u16::MAX as f32) as u16;
+        let high = (strength * 0.5 * u16::MAX as f32) as u16;
+        let duration_ms = (duration.max(0.0) * 1000.0) as u32;
+        // Pads without rumble support report an error here; ignore it.
+        let _ = controller.set_rumble(low, high, duration_ms);
+    }
+}
@@ -1,8 +1,11 @@
+use sdl2::controller::{Axis, Button, GameController};
 use sdl2::event::Event;
 use sdl2::keyboard::Scancode;
 use sdl2::mouse::MouseButton;
-use sdl2::EventPump;
-use std::collections::HashSet;
+use sdl2::{EventPump, GameControllerSubsystem, Sdl};
+use std::collections::{HashSet, VecDeque};
+
+use crate::engine::input_map::{GameAction, InputMap};
 
 #[allow(dead_code)]
 pub enum InputEvent {
@@ -13,17 +16,38 @@ pub enum InputEvent {
     MouseMotion { dx: f32, dy: f32 },
     /// Scroll wheel delta: positive = scroll up (zoom in), negative = scroll down (zoom out).
     MouseWheel { dy: f32 },
+    ControllerButtonPressed(Button),
+    ControllerButtonReleased(Button),
     Quit,
 }
 
+/// Stick deflection (0..1 of full tilt) below which a stick reads as centred.
+pub const DEFAULT_STICK_DEADZONE: f32 = 0.15;
+
 pub struct InputState {
     pub keys: HashSet<Scancode>,
     pub mouse_buttons: HashSet<MouseButton>,
     pub mouse_dx: f32,
     pub mouse_dy: f32,
+    /// Cursor position in window pixels (top-left origin). Only meaningful while relative
+    /// mouse mode is off.
+    pub mouse_x: i32,
+    pub mouse_y: i32,
     /// Accumulated scroll wheel delta this frame (positive = up).
     pub scroll_dy: f32,
     pub events: Vec<InputEvent>,
+    /// Gamepad buttons currently down.
+    pub controller_buttons: HashSet<Button>,
+    /// Raw stick positions in -1..1, y up-positive, as polled this frame (no deadzone).
+    pub left_stick_raw: (f32, f32),
+    pub right_stick_raw: (f32, f32),
+    /// Radial stick deadzone, as a fraction of full tilt.
+    pub stick_deadzone: f32,
+    /// Controller subsystem, once `open_controllers` has run; `None` keeps input
+    /// keyboard-and-mouse only.
+    controller_subsystem: Option<GameControllerSubsystem>,
+    /// The game controller being read: the first one connected.
+    controller: Option<GameController>,
 }
 
 impl InputState {
@@ -33,18 +57,60 @@ impl InputState {
             mouse_buttons: HashSet::new(),
             mouse_dx: 0.0,
             mouse_dy: 0.0,
+            mouse_x: 0,
+            mouse_y: 0,
             scroll_dy: 0.0,
             events: Vec::new(),
+            controller_buttons: HashSet::new(),
+            left_stick_raw: (0.0, 0.0),
+            right_stick_raw: (0.0, 0.0),
+            stick_deadzone: DEFAULT_STICK_DEADZONE,
+            controller_subsystem: None,
+            controller: None,
+        }
+    }
+
+    /// Start reading gamepads: open the first connected game controller now, and pick up
+    /// controllers plugged in later. Without this, input is keyboard and mouse only.
+    pub fn open_controllers(&mut self, sdl: &Sdl) {
+        self.controller_subsystem = sdl.game_controller().ok();
+        self.open_first_controller();
+    }
+
+    fn open_first_controller(&mut self) {
+        let Some(subsystem) = &self.controller_subsystem else {
+            return;
+        };
+        let count = subsystem.num_joysticks().unwrap_or(0);
+        self.controller = (0..count)
+            .filter(|&i| subsystem.is_game_controller(i))
+            .find_map(|i| subsystem.open(i).ok());
+        if let Some(controller) = &self.controller {
+            println!("[input] using controller: {}", controller.name());
         }
     }
 
+    /// Synthetic input with `keys` held and no events, for driving gameplay systems from a
+    /// script instead of the keyboard.
+    pub fn with_held_keys(keys: &[Scancode]) -> Self {
+        let mut input = Self::new();
+        input.keys.extend(keys.iter().copied());
+        input
+    }
+
     pub fn update(&mut self, event_pump: &mut EventPump) {
+        self.apply_events(event_pump.poll_iter());
+    }
+
+    /// One frame's update from `events`: per-frame deltas start from zero, then the events
+    /// are applied in order and the sticks polled.
+    fn apply_events(&mut self, events: impl IntoIterator<Item = Event>) {
         self.mouse_dx = 0.0;
         self.mouse_dy = 0.0;
         self.scroll_dy = 0.0;
         self.events.clear();
 
-        for event in event_pump.poll_iter() {
+        for event in events {
             match event {
                 Event::Quit { .. } => {
                     self.events.push(InputEvent::Quit);
@@ -62,7 +128,9 @@ impl InputState {
                     self.keys.remove(&sc);
                     self.events.push(InputEvent::KeyReleased(sc));
                 }
-                Event::MouseButtonDown { mouse_btn, .. } => {
+                Event::MouseButtonDown { mouse_btn, x, y, .. } => {
+                    self.mouse_x = x;
+                    self.mouse_y = y;
                     if self.mouse_buttons.insert(mouse_btn) {
                         self.events.push(InputEvent::MouseButtonPressed(mouse_btn));
                     }
@@ -71,7 +139,9 @@ impl InputState {
                     self.mouse_buttons.remove(&mouse_btn);
                     self.events.push(InputEvent::MouseButtonReleased(mouse_btn));
                 }
-                Event::MouseMotion { xrel, yrel, .. } => {
+                Event::MouseMotion { x, y, xrel, yrel, .. } => {
+                    self.mouse_x = x;
+                    self.mouse_y = y;
                     let dx = xrel as f32;
                     let dy = yrel as f32;
                     self.mouse_dx += dx;
@@ -83,9 +153,65 @@ impl InputState {
                     self.scroll_dy += dy;
                     self.events.push(InputEvent::MouseWheel { dy });
                 }
+                Event::ControllerButtonDown { which, button, .. } if self.is_active(which) => {
+                    if self.controller_buttons.insert(button) {
+                        self.events.push(InputEvent::ControllerButtonPressed(button));
+                    }
+                }
+                Event::ControllerButtonUp { which, button, .. } if self.is_active(which) => {
+                    self.controller_buttons.remove(&button);
+                    self.events.push(InputEvent::ControllerButtonReleased(button));
+                }
+                // `which` is a device index here, not an instance id.
+                Event::ControllerDeviceAdded { which, .. } if self.controller.is_none() => {
+                    if let Some(subsystem) = &self.controller_subsystem {
+                        self.controller = subsystem.open(which).ok();
+                    }
+                    if let Some(controller) = &self.controller {
+                        println!("[input] controller connected: {}", controller.name());
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } if self.is_active(which) => {
+                    println!("[input] controller disconnected");
+                    self.controller = None;
+                    self.controller_buttons.clear();
+                    self.open_first_controller();
+                }
                 _ => {}
             }
         }
+
+        (self.left_stick_raw, self.right_stick_raw) = match &self.controller {
+            Some(pad) => (
+                (axis_value(pad, Axis::LeftX), -axis_value(pad, Axis::LeftY)),
+                (axis_value(pad, Axis::RightX), -axis_value(pad, Axis::RightY)),
+            ),
+            None => ((0.0, 0.0), (0.0, 0.0)),
+        };
+    }
+
+    /// SDL instance id of the controller being read, for `Haptics::follow`.
+    pub fn controller_id(&self) -> Option<u32> {
+        self.controller.as_ref().map(|c| c.instance_id())
+    }
+
+    /// Whether `instance_id` is the controller being read.
+    fn is_active(&self, instance_id: u32) -> bool {
+        self.controller.as_ref().is_some_and(|c| c.instance_id() == instance_id)
+    }
+
+    /// Left stick (x right, y up), zero inside the deadzone.
+    pub fn left_stick(&self) -> (f32, f32) {
+        apply_deadzone(self.left_stick_raw, self.stick_deadzone)
+    }
+
+    /// Right stick (x right, y up), zero inside the deadzone.
+    pub fn right_stick(&self) -> (f32, f32) {
+        apply_deadzone(self.right_stick_raw, self.stick_deadzone)
+    }
+
+    pub fn is_controller_button_held(&self, button: Button) -> bool {
+        self.controller_buttons.contains(&button)
     }
 
     pub fn is_key_held(&self, sc: Scancode) -> bool {
@@ -102,3 +228,120 @@ impl InputState {
             .any(|e| matches!(e, InputEvent::Quit))
     }
 }
+
+fn axis_value(controller: &GameController, axis: Axis) -> f32 {
+    (controller.axis(axis) as f32 / i16::MAX as f32).clamp(-1.0, 1.0)
+}
+
+/// Radial deadzone: a stick within `deadzone` of centre reads as centred, and the rest of
+/// its travel is rescaled so output still ramps smoothly from 0 to 1 at full tilt.
+fn apply_deadzone((x, y): (f32, f32), deadzone: f32) -> (f32, f32) {
+    let length = x.hypot(y);
+    if length <= deadzone {
+        return (0.0, 0.0);
+    }
+    let scale = ((length - deadzone) / (1.0 - deadzone).max(1e-6)).min(1.0) / length;
+    (x * scale, y * scale)
+}
+
+/// Gameplay actions that can be pressed slightly early and still honored.
+const BUFFERED_ACTIONS: [GameAction; 3] = [GameAction::Jump, GameAction::Dash, GameAction::Attack];
+
+/// Presses older than this are dropped regardless of the window a consumer asks for.
+const BUFFER_MAX_AGE: f32 = 0.5;
+/// Upper bound on buffered presses (oldest dropped first).
+const BUFFER_CAPACITY: usize = 16;
+
+/// Recent action presses with timestamps, so a press that arrives a few frames before
+/// it is valid (attack during recovery, jump just before landing) is still honored.
+///
+/// Timestamps use accumulated game time from `update`, so time spent paused does not
+/// age the buffer.
+pub struct InputBuffer {
+    entries: VecDeque<(GameAction, f32)>,
+    time: f32,
+}
+
+impl InputBuffer {
+    pub fn new() -> Self {
+        Self { entries: VecDeque::new(), time: 0.0 }
+    }
+
+    /// Advance the clock and record this frame's presses of the buffered actions, as bound
+    /// in `map`. Call once per running frame.
+    pub fn update(&mut self, input: &InputState, map: &InputMap, dt: f32) {
+        self.time += dt;
+        let pressed = input.events.iter().flat_map(|event| {
+            BUFFERED_ACTIONS.into_iter().filter(move |&action| map.is_press_of(event, action))
+        });
+        for action in pressed {
+            if self.entries.len() == BUFFER_CAPACITY {
+                self.entries.pop_front();
+            }
+            self.entries.push_back((action, self.time));
+        }
+        let cutoff = self.time - BUFFER_MAX_AGE;
+        self.entries.retain(|&(_, t)| t >= cutoff);
+    }
+
+    /// Whether `action` was pressed within the last `window` seconds. If so the oldest
+    /// such press is removed, so each press is consumed at most once.
+    pub fn consume_buffered(&mut self, action: GameAction, window: f32) -> bool {
+        let cutoff = self.time - window;
+        let found = self.entries.iter().position(|&(a, t)| a == action && t >= cutoff);
+        if let Some(i) = found {
+            self.entries.remove(i);
+        }
+        found.is_some()
+    }
+
+    /// Drop every buffered press of `action`.
+    pub fn clear(&mut self, action: GameAction) {
+        self.entries.retain(|&(a, _)| a != action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sdl2::mouse::MouseWheelDirection;
+
+    use super::*;
+
+    fn wheel(y: i32) -> Event {
+        Event::MouseWheel {
+            timestamp: 0,
+            window_id: 0,
+            which: 0,
+            x: 0,
+            y,
+            direction: MouseWheelDirection::Normal,
+            precise_x: 0.0,
+            precise_y: y as f32,
+            mouse_x: 0,
+            mouse_y: 0,
+        }
+    }
+
+    #[test]
+    fn stick_inside_deadzone_reads_centred() {
+        assert_eq!(apply_deadzone((0.1, -0.08), 0.15), (0.0, 0.0));
+
+        let mut input = InputState::new();
+        input.left_stick_raw = (0.1, -0.08);
+        assert_eq!(input.left_stick(), (0.0, 0.0));
+        input.left_stick_raw = (0.6, 0.8);
+        assert!(input.left_stick().0 > 0.5);
+    }
+
+    #[test]
+    fn scroll_accumulates_then_clears() {
+        let mut input = InputState::new();
+        input.apply_events([wheel(1), wheel(2)]);
+        assert_eq!(input.scroll_dy, 3.0);
+        assert_eq!(input.events.len(), 2);
+
+        input.apply_events([]);
+        assert_eq!(input.scroll_dy, 0.0);
+        assert!(input.events.is_empty());
+    }
+}
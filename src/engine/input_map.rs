@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use sdl2::controller::Button;
+use sdl2::keyboard::Scancode;
+use sdl2::mouse::MouseButton;
+
+use crate::engine::input::{InputEvent, InputState};
+
+/// Gameplay actions the player triggers, independent of the keys they are bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameAction {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Sprint,
+    Dash,
+    Attack,
+    ToggleCamera,
+}
+
+impl GameAction {
+    pub const ALL: [GameAction; 9] = [
+        Self::MoveForward,
+        Self::MoveBack,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Jump,
+        Self::Sprint,
+        Self::Dash,
+        Self::Attack,
+        Self::ToggleCamera,
+    ];
+
+    /// Name used to refer to the action on the command line (`--bind jump=K`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MoveForward => "move-forward",
+            Self::MoveBack => "move-back",
+            Self::MoveLeft => "move-left",
+            Self::MoveRight => "move-right",
+            Self::Jump => "jump",
+            Self::Sprint => "sprint",
+            Self::Dash => "dash",
+            Self::Attack => "attack",
+            Self::ToggleCamera => "toggle-camera",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// A physical input an action can be bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Binding {
+    Key(Scancode),
+    Mouse(MouseButton),
+    Controller(Button),
+}
+
+/// Which keys and buttons trigger each `GameAction`. An action may have several bindings;
+/// any one of them held counts as the action held.
+pub struct InputMap {
+    bindings: HashMap<GameAction, Vec<Binding>>,
+}
+
+impl InputMap {
+    /// The default controls.
+    pub fn new() -> Self {
+        use Binding::{Controller, Key, Mouse};
+        let defaults = [
+            (GameAction::MoveForward, Key(Scancode::W)),
+            (GameAction::MoveBack, Key(Scancode::S)),
+            (GameAction::MoveLeft, Key(Scancode::A)),
+            (GameAction::MoveRight, Key(Scancode::D)),
+            (GameAction::Jump, Key(Scancode::Space)),
+            (GameAction::Sprint, Key(Scancode::LShift)),
+            (GameAction::Dash, Key(Scancode::LCtrl)),
+            (GameAction::Attack, Mouse(MouseButton::Left)),
+            (GameAction::ToggleCamera, Key(Scancode::F1)),
+            (GameAction::Jump, Controller(Button::A)),
+            (GameAction::Sprint, Controller(Button::LeftStick)),
+            (GameAction::Dash, Controller(Button::B)),
+            (GameAction::Attack, Controller(Button::X)),
+            (GameAction::ToggleCamera, Controller(Button::Back)),
+        ];
+        let mut bindings: HashMap<GameAction, Vec<Binding>> = HashMap::new();
+        for (action, binding) in defaults {
+            bindings.entry(action).or_default().push(binding);
+        }
+        Self { bindings }
+    }
+
+    pub fn bindings(&self, action: GameAction) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Bind `action` to `key` instead of its previous keyboard and mouse bindings. Its
+    /// controller buttons are kept.
+    pub fn rebind(&mut self, action: GameAction, key: Scancode) {
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|binding| matches!(binding, Binding::Controller(_)));
+        bindings.insert(0, Binding::Key(key));
+    }
+
+    /// Add `binding` to the ones already triggering `action`.
+    #[allow(dead_code)]
+    pub fn bind(&mut self, action: GameAction, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn is_action_held(&self, input: &InputState, action: GameAction) -> bool {
+        self.bindings(action).iter().any(|binding| match *binding {
+            Binding::Key(key) => input.is_key_held(key),
+            Binding::Mouse(button) => input.is_mouse_button_held(button),
+            Binding::Controller(button) => input.is_controller_button_held(button),
+        })
+    }
+
+    /// Whether `action` was pressed this frame.
+    #[allow(dead_code)]
+    pub fn action_just_pressed(&self, input: &InputState, action: GameAction) -> bool {
+        input.events.iter().any(|event| self.is_press_of(event, action))
+    }
+
+    /// Whether `event` is a press of one of `action`'s bindings.
+    pub fn is_press_of(&self, event: &InputEvent, action: GameAction) -> bool {
+        let pressed = match *event {
+            InputEvent::KeyPressed(key) => Binding::Key(key),
+            InputEvent::MouseButtonPressed(button) => Binding::Mouse(button),
+            InputEvent::ControllerButtonPressed(button) => Binding::Controller(button),
+            _ => return false,
+        };
+        self.bindings(action).contains(&pressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressing(key: Scancode) -> InputState {
+        let mut input = InputState::new();
+        input.events.push(InputEvent::KeyPressed(key));
+        input
+    }
+
+    #[test]
+    fn rebound_key_triggers_action() {
+        let mut map = InputMap::new();
+        map.rebind(GameAction::Jump, Scancode::K);
+
+        assert!(map.action_just_pressed(&pressing(Scancode::K), GameAction::Jump));
+        assert!(!map.action_just_pressed(&pressing(Scancode::Space), GameAction::Jump));
+        assert!(!map.action_just_pressed(&InputState::new(), GameAction::Jump));
+    }
+}
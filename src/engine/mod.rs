@@ -1,3 +1,8 @@
+pub mod audio;
+pub mod config;
+pub mod haptics;
 pub mod input;
+pub mod input_map;
 pub mod time;
 pub mod window;
+pub mod world_up;
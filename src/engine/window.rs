@@ -4,6 +4,8 @@ use sdl2::Sdl;
 pub struct GameWindow {
     _gl_context: GLContext,
     window: Window,
+    /// Title the window was created with; live status text is appended to it.
+    base_title: String,
 }
 
 impl GameWindow {
@@ -13,6 +15,8 @@ impl GameWindow {
         let gl_attr = video.gl_attr();
         gl_attr.set_context_profile(GLProfile::Core);
         gl_attr.set_context_version(3, 3);
+        // sRGB-capable default framebuffer; the renderer enables GL_FRAMEBUFFER_SRGB.
+        gl_attr.set_framebuffer_srgb_compatible(true);
 
         let window = video
             .window(title, width, height)
@@ -30,9 +34,19 @@ impl GameWindow {
         Self {
             _gl_context: gl_context,
             window,
+            base_title: title.to_string(),
         }
     }
 
+    pub fn base_title(&self) -> &str {
+        &self.base_title
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        // Only fails on interior NUL bytes; keep the old title then.
+        let _ = self.window.set_title(title);
+    }
+
     pub fn swap(&self) {
         self.window.gl_swap_window();
     }
@@ -0,0 +1,41 @@
+use std::str::FromStr;
+
+use glam::{Quat, Vec3};
+
+/// The world axis that points up. The default gravity, grounded checks (when gravity is
+/// zero), the plane the player moves in, the camera and the shadow frustum all orient
+/// themselves by it. Scene content is placed as authored, so a Z-up world wants Z-up
+/// scenes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WorldUp {
+    #[default]
+    Y,
+    Z,
+}
+
+impl WorldUp {
+    pub fn vector(self) -> Vec3 {
+        match self {
+            Self::Y => Vec3::Y,
+            Self::Z => Vec3::Z,
+        }
+    }
+
+    /// Rotation taking the Y-up frame that yaw/pitch and movement input are worked out in
+    /// to the world. Identity for `Y`.
+    pub fn align(self) -> Quat {
+        Quat::from_rotation_arc(Vec3::Y, self.vector())
+    }
+}
+
+impl FromStr for WorldUp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "y" | "Y" => Ok(Self::Y),
+            "z" | "Z" => Ok(Self::Z),
+            _ => Err("expected y or z".to_string()),
+        }
+    }
+}
@@ -9,11 +9,83 @@ mod scene;
 mod systems;
 mod ui;
 
-use app::GameApp;
+use app::{GameApp, SceneLoader};
+use camera::CameraSettings;
 use clap::Parser;
+use engine::input_map::GameAction;
 use engine::window::GameWindow;
+use engine::world_up::WorldUp;
 use hecs::World;
+use renderer::{AutoExposure, ColorGrading, ShadowSettings};
+use scene::bench_scene::load_bench_scene;
+use scene::loader::SceneFile;
 use scene::test_scene::load_test_scene;
+use sdl2::keyboard::Scancode;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Clone)]
+enum SceneKind {
+    /// Hand-built test level
+    Test,
+    /// Grid of dynamic spheres for stress testing (size set by --count)
+    Bench,
+    /// Scene description file (RON), see `scene::loader::SceneFile`
+    File(PathBuf),
+}
+
+impl FromStr for SceneKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "test" => Ok(SceneKind::Test),
+            "bench" => Ok(SceneKind::Bench),
+            "" => Err("expected test, bench, or a scene file path".to_string()),
+            path => Ok(SceneKind::File(PathBuf::from(path))),
+        }
+    }
+}
+
+/// Colour grading presets for `--grade`.
+#[derive(Clone, Copy)]
+enum GradeKind {
+    /// No grading
+    Neutral,
+    /// Warm, high-contrast
+    Sunset,
+    /// Cold, desaturated
+    Night,
+}
+
+impl FromStr for GradeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "neutral" => Ok(GradeKind::Neutral),
+            "sunset" => Ok(GradeKind::Sunset),
+            "night" => Ok(GradeKind::Night),
+            _ => Err("expected neutral, sunset, or night".to_string()),
+        }
+    }
+}
+
+fn parse_key(name: &str) -> Result<Scancode, String> {
+    Scancode::from_name(name).ok_or_else(|| format!("unknown key name: {name}"))
+}
+
+/// `action=key`, e.g. `jump=K`.
+fn parse_binding(binding: &str) -> Result<(GameAction, Scancode), String> {
+    let (action, key) = binding
+        .split_once('=')
+        .ok_or_else(|| format!("expected action=key, got: {binding}"))?;
+    let action = GameAction::from_name(action).ok_or_else(|| {
+        let names: Vec<_> = GameAction::ALL.iter().map(|a| a.name()).collect();
+        format!("unknown action {action}; expected one of {}", names.join(", "))
+    })?;
+    Ok((action, parse_key(key)?))
+}
 
 #[derive(Parser)]
 #[command(name = "lance", about = "Lance Engine")]
@@ -21,16 +93,231 @@ struct Args {
     /// Record 5 seconds of video to demos/demo.mp4
     #[arg(long)]
     record: bool,
+
+    /// Scene to load: test, bench, or a path to a .ron scene file
+    #[arg(long, default_value = "test")]
+    scene: SceneKind,
+
+    /// Bench scene grid size: spawns count×count spheres
+    #[arg(long, default_value_t = 10)]
+    count: usize,
+
+    /// Number of directional shadow cascades (2–4)
+    #[arg(long, default_value_t = renderer::DEFAULT_CASCADES)]
+    shadow_cascades: usize,
+
+    /// Camera distance in metres covered by shadow cascades
+    #[arg(long, default_value_t = renderer::DEFAULT_SHADOW_DISTANCE)]
+    shadow_distance: f32,
+
+    /// Cascade split blend: 0 = uniform, 1 = logarithmic
+    #[arg(long, default_value_t = renderer::DEFAULT_SPLIT_LAMBDA)]
+    cascade_lambda: f32,
+
+    /// Third-person camera swings back behind the player when running with the mouse idle
+    #[arg(long)]
+    camera_recenter: bool,
+
+    /// Third-person body turns to face the way it's moving rather than the camera's way
+    #[arg(long)]
+    face_movement: bool,
+
+    /// Camera near clip plane distance
+    #[arg(long, default_value_t = camera::DEFAULT_NEAR)]
+    near: f32,
+
+    /// Camera far clip plane distance (also caps the shadow cascade range)
+    #[arg(long, default_value_t = camera::DEFAULT_FAR)]
+    far: f32,
+
+    /// Render the 3D scene at this fraction of the window resolution (0.25–1), then upscale;
+    /// the UI always draws at full resolution
+    #[arg(long, default_value_t = 1.0)]
+    render_scale: f32,
+
+    /// Initial scene brightness multiplier (0.1–4); also adjustable from the pause menu
+    #[arg(long, default_value_t = renderer::DEFAULT_EXPOSURE)]
+    exposure: f32,
+
+    /// Adapt exposure to the average scene brightness over time (eye adaptation); the
+    /// manual exposure then acts as compensation on top
+    #[arg(long)]
+    auto_exposure: bool,
+
+    /// Eye adaptation rate (1/s); higher adjusts faster
+    #[arg(long, default_value_t = renderer::DEFAULT_ADAPT_SPEED)]
+    adapt_speed: f32,
+
+    /// Lowest exposure eye adaptation may reach
+    #[arg(long, default_value_t = renderer::DEFAULT_AUTO_EXPOSURE_MIN)]
+    auto_exposure_min: f32,
+
+    /// Highest exposure eye adaptation may reach
+    #[arg(long, default_value_t = renderer::DEFAULT_AUTO_EXPOSURE_MAX)]
+    auto_exposure_max: f32,
+
+    /// Blur along screen motion (camera and objects); optionally the strength (0–1), the
+    /// fraction of a frame's movement to smear over
+    #[arg(long, num_args = 0..=1, default_missing_value_t = renderer::DEFAULT_MOTION_BLUR)]
+    motion_blur: Option<f32>,
+
+    /// Depth of field focused at this view distance (world units)
+    #[arg(long, conflicts_with = "dof_focus_player")]
+    dof_focus: Option<f32>,
+
+    /// Depth of field that keeps the player in focus
+    #[arg(long)]
+    dof_focus_player: bool,
+
+    /// Depth-of-field blur strength (0–1)
+    #[arg(long, default_value_t = renderer::DEFAULT_DOF_APERTURE)]
+    dof_aperture: f32,
+
+    /// Darken the screen edges (never the HUD); optionally the strength (0–1)
+    #[arg(long, num_args = 0..=1, default_missing_value_t = renderer::DEFAULT_VIGNETTE_STRENGTH)]
+    vignette: Option<f32>,
+
+    /// Where the vignette starts, as a fraction of the centre-to-corner distance (0–1)
+    #[arg(long, default_value_t = renderer::DEFAULT_VIGNETTE_RADIUS)]
+    vignette_radius: f32,
+
+    /// Colour grade applied to the final image: neutral, sunset, or night
+    #[arg(long, default_value = "neutral")]
+    grade: GradeKind,
+
+    /// Key that cycles the light editor through the point and spot lights (SDL key name)
+    #[arg(long, default_value = "L", value_parser = parse_key)]
+    light_edit_key: Scancode,
+
+    /// Rebind a control, as action=key (e.g. jump=K); repeat for several
+    #[arg(long = "bind", value_parser = parse_binding)]
+    bindings: Vec<(GameAction, Scancode)>,
+
+    /// Fraction of gamepad stick tilt ignored around centre
+    #[arg(long, default_value_t = engine::input::DEFAULT_STICK_DEADZONE)]
+    stick_deadzone: f32,
+
+    /// Key that toggles drawing body velocities and collision contacts (SDL key name)
+    #[arg(long, default_value = "F11", value_parser = parse_key)]
+    physics_debug_key: Scancode,
+
+    /// Velocity arrow length per unit of speed in the physics debug overlay (seconds)
+    #[arg(long, default_value_t = systems::DEFAULT_VELOCITY_ARROW_SCALE)]
+    velocity_arrow_scale: f32,
+
+    /// Process entities in a fixed order so repeated runs simulate identically
+    #[arg(long)]
+    deterministic: bool,
+
+    /// Closing speed (m/s) below which contacts come to rest instead of bouncing
+    #[arg(long, default_value_t = systems::DEFAULT_REST_THRESHOLD)]
+    rest_threshold: f32,
+
+    /// Speed band (m/s) above the rest threshold over which bounces fade in; 0 is a hard cutoff
+    #[arg(long, default_value_t = systems::DEFAULT_RESTITUTION_FALLOFF)]
+    restitution_falloff: f32,
+
+    /// Collision velocity-solver passes per tick; more keep stacks still at a CPU cost
+    #[arg(long, default_value_t = systems::DEFAULT_SOLVER_ITERATIONS)]
+    solver_iterations: usize,
+
+    /// World up axis, y or z; gravity, movement, the camera and shadows follow it
+    #[arg(long, default_value = "y")]
+    up: WorldUp,
+
+    /// Reload a .ron --scene whenever the file changes on disk
+    #[arg(long)]
+    watch_scene: bool,
+
+    /// Keep the player where it is across hot reloads instead of respawning it
+    #[arg(long)]
+    reload_keep_player: bool,
+
+    /// Editor grid increment (world units) used while Shift is held
+    #[arg(long, default_value_t = systems::DEFAULT_SNAP_STEP)]
+    snap_step: f32,
+
+    /// Editor snapping only moves the final placement, not the entity during the drag
+    #[arg(long)]
+    snap_on_release: bool,
 }
 
 fn main() {
     let args = Args::parse();
     let sdl = sdl2::init().expect("Failed to init SDL2");
-    let window = GameWindow::new(&sdl, "Lance Engine", 1280, 720);
+    let mut window = GameWindow::new(&sdl, "Lance Engine", 1280, 720);
 
-    let mut world = World::new();
-    let (meshes, player_entity) = load_test_scene(&mut world);
+    let count = args.count;
+    let scene: SceneLoader = match &args.scene {
+        SceneKind::Test => Box::new(load_test_scene),
+        SceneKind::Bench => Box::new(move |world: &mut World| load_bench_scene(world, count)),
+        SceneKind::File(path) => {
+            // Parse up front so a bad file fails at launch, not on restart.
+            let file = SceneFile::load(path).unwrap_or_else(|e| {
+                eprintln!("Failed to load scene: {e}");
+                std::process::exit(1);
+            });
+            let up = args.up;
+            Box::new(move |world: &mut World| file.build(world, up))
+        }
+    };
 
-    let mut app = GameApp::new(world, meshes, player_entity, args.record, &window);
-    app.run(&sdl, &window);
+    let mut app = GameApp::new(
+        scene,
+        args.record,
+        ShadowSettings {
+            cascades: args.shadow_cascades,
+            distance: args.shadow_distance,
+            split_lambda: args.cascade_lambda,
+        },
+        CameraSettings {
+            auto_recenter: args.camera_recenter,
+            face_movement: args.face_movement,
+            near: args.near,
+            far: args.far,
+        },
+        &window,
+    );
+    app.set_deterministic(args.deterministic);
+    app.set_rest_threshold(args.rest_threshold, args.restitution_falloff);
+    app.set_solver_iterations(args.solver_iterations);
+    app.set_world_up(args.up);
+    app.set_render_scale(args.render_scale);
+    app.set_exposure(args.exposure);
+    if args.auto_exposure {
+        app.set_auto_exposure(AutoExposure {
+            speed: args.adapt_speed,
+            min: args.auto_exposure_min,
+            max: args.auto_exposure_max,
+            ..AutoExposure::default()
+        });
+    }
+    if let Some(strength) = args.motion_blur {
+        app.set_motion_blur(strength);
+    }
+    if args.dof_focus.is_some() || args.dof_focus_player {
+        app.set_depth_of_field(args.dof_aperture, args.dof_focus);
+    }
+    if let Some(strength) = args.vignette {
+        app.set_vignette(strength, args.vignette_radius);
+    }
+    app.set_color_grading(match args.grade {
+        GradeKind::Neutral => ColorGrading::default(),
+        GradeKind::Sunset => ColorGrading::sunset(),
+        GradeKind::Night => ColorGrading::night(),
+    });
+    app.set_light_edit_key(args.light_edit_key);
+    app.set_stick_deadzone(args.stick_deadzone);
+    for &(action, key) in &args.bindings {
+        app.rebind(action, key);
+    }
+    app.set_physics_debug(args.physics_debug_key, args.velocity_arrow_scale);
+    app.set_grid_snap(args.snap_step, !args.snap_on_release);
+    if let SceneKind::File(path) = &args.scene {
+        app.set_scene_path(path.clone());
+        if args.watch_scene {
+            app.watch_scene(path.clone(), args.reload_keep_player);
+        }
+    }
+    app.run(&sdl, &mut window);
 }
@@ -0,0 +1,175 @@
+use std::mem;
+
+use gl::types::*;
+use glam::{Mat4, Vec2};
+use hecs::World;
+
+use super::shader::ShaderProgram;
+use super::{srgb_to_linear, with_frame_block, FRAME_BINDING};
+use crate::components::{Decal, Lifetime};
+
+const DECAL_VERT_SRC: &str = include_str!("../../shaders/decal.vert");
+const DECAL_FRAG_SRC: &str = include_str!("../../shaders/decal.frag");
+
+/// Edge length of the generated decal mask texture.
+const MASK_SIZE: usize = 64;
+/// Seconds over which a decal fades out before it expires.
+const FADE_TIME: f32 = 1.5;
+/// Depth bias pulling decals toward the camera so they don't z-fight the surface under them.
+const DEPTH_OFFSET_FACTOR: f32 = -1.0;
+const DEPTH_OFFSET_UNITS: f32 = -4.0;
+
+/// Draws `Decal` entities as alpha-blended quads over the opaque scene.
+///
+/// Every decal shares one procedurally generated splat mask (a soft disc with an uneven
+/// rim), stretched to the decal's size and tinted by its colour.
+pub struct DecalRenderer {
+    shader: ShaderProgram,
+    vao: GLuint,
+    vbo: GLuint,
+    mask: GLuint,
+    /// Texture unit the mask is bound to while drawing.
+    unit: u32,
+}
+
+impl DecalRenderer {
+    pub fn new(unit: u32) -> Self {
+        let shader = ShaderProgram::from_sources(
+            &with_frame_block(DECAL_VERT_SRC),
+            &with_frame_block(DECAL_FRAG_SRC),
+        )
+        .expect("Failed to compile decal shaders");
+        shader.bind_uniform_block("Frame", FRAME_BINDING);
+
+        #[rustfmt::skip]
+        let corners: [f32; 12] = [
+            -0.5, -0.5,
+             0.5, -0.5,
+             0.5,  0.5,
+            -0.5, -0.5,
+             0.5,  0.5,
+            -0.5,  0.5,
+        ];
+
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+        let mut mask: GLuint = 0;
+        let pixels = splat_mask();
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                mem::size_of_val(&corners) as GLsizeiptr,
+                corners.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            let stride = (2 * mem::size_of::<f32>()) as GLsizei;
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::BindVertexArray(0);
+
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::GenTextures(1, &mut mask);
+            gl::BindTexture(gl::TEXTURE_2D, mask);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::R8 as i32,
+                MASK_SIZE as i32,
+                MASK_SIZE as i32,
+                0,
+                gl::RED,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const _,
+            );
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+        }
+
+        Self { shader, vao, vbo, mask, unit }
+    }
+
+    /// Blend every decal in `world` onto the depth-tested scene. Must run after the opaque
+    /// pass with the scene framebuffer still bound; depth writes stay off so overlapping
+    /// decals don't clip each other.
+    pub fn draw(&mut self, world: &World) {
+        let mut query = world.query::<(&Decal, Option<&Lifetime>)>();
+        let mut decals = query.iter().peekable();
+        if decals.peek().is_none() {
+            return;
+        }
+
+        self.shader.bind();
+        self.shader.set_int("u_decal_mask", self.unit as i32);
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + self.unit);
+            gl::BindTexture(gl::TEXTURE_2D, self.mask);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(gl::FALSE);
+            gl::Enable(gl::POLYGON_OFFSET_FILL);
+            gl::PolygonOffset(DEPTH_OFFSET_FACTOR, DEPTH_OFFSET_UNITS);
+            gl::BindVertexArray(self.vao);
+        }
+
+        for (_e, (decal, lifetime)) in decals {
+            // Quad x → across (width), y → normal, z → along the tangent (length).
+            let across = decal.normal.cross(decal.tangent);
+            let model = Mat4::from_cols(
+                (across * decal.size.x).extend(0.0),
+                decal.normal.extend(0.0),
+                (decal.tangent * decal.size.y).extend(0.0),
+                decal.position.extend(1.0),
+            );
+            let alpha = lifetime.map_or(1.0, |l| (l.remaining / FADE_TIME).clamp(0.0, 1.0));
+            self.shader.set_mat4("u_model", &model);
+            self.shader.set_vec3("u_decal_color", srgb_to_linear(decal.color));
+            self.shader.set_float("u_decal_alpha", alpha);
+            unsafe {
+                gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            }
+        }
+
+        unsafe {
+            gl::BindVertexArray(0);
+            gl::Disable(gl::POLYGON_OFFSET_FILL);
+            gl::DepthMask(gl::TRUE);
+            gl::Disable(gl::BLEND);
+        }
+    }
+}
+
+impl Drop for DecalRenderer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteTextures(1, &self.mask);
+        }
+    }
+}
+
+/// Soft-edged disc whose rim radius wobbles with angle, so stretched and tinted copies read
+/// as footprints, scorches or splats rather than perfect ellipses.
+fn splat_mask() -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(MASK_SIZE * MASK_SIZE);
+    for y in 0..MASK_SIZE {
+        for x in 0..MASK_SIZE {
+            let p = (Vec2::new(x as f32, y as f32) + 0.5) / MASK_SIZE as f32 * 2.0 - 1.0;
+            let angle = p.y.atan2(p.x);
+            let rim = 0.8 + 0.08 * (angle * 5.0).sin() + 0.05 * (angle * 11.0 + 1.3).sin();
+            let alpha = 1.0 - ((p.length() - (rim - 0.25)) / 0.25).clamp(0.0, 1.0);
+            pixels.push((alpha * alpha * (3.0 - 2.0 * alpha) * 255.0) as u8);
+        }
+    }
+    pixels
+}
@@ -0,0 +1,149 @@
+use std::mem;
+
+use gl::types::*;
+use glam::Vec3;
+
+use super::shader::ShaderProgram;
+use super::{srgb_to_linear, with_frame_block, FRAME_BINDING};
+
+const GIZMO_VERT_SRC: &str = include_str!("../../shaders/gizmo.vert");
+const GIZMO_FRAG_SRC: &str = include_str!("../../shaders/gizmo.frag");
+
+/// Floats per vertex: world position, then linear colour.
+const VERTEX_FLOATS: usize = 6;
+/// Line segments per `circle`.
+const CIRCLE_SEGMENTS: u32 = 32;
+
+/// Immediate-mode debug lines. Anything may queue segments during a frame; `draw` renders
+/// them all as one `GL_LINES` batch, depth-tested against the scene, and clears the queue.
+/// Overlay lines go in a second batch drawn on top of everything.
+pub struct GizmoRenderer {
+    shader: ShaderProgram,
+    vao: GLuint,
+    vbo: GLuint,
+    vertices: Vec<f32>,
+    overlay: Vec<f32>,
+}
+
+impl GizmoRenderer {
+    pub fn new() -> Self {
+        let shader = ShaderProgram::from_sources(
+            &with_frame_block(GIZMO_VERT_SRC),
+            &with_frame_block(GIZMO_FRAG_SRC),
+        )
+        .expect("Failed to compile gizmo shaders");
+        shader.bind_uniform_block("Frame", FRAME_BINDING);
+
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            let stride = (VERTEX_FLOATS * mem::size_of::<f32>()) as GLsizei;
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::EnableVertexAttribArray(1);
+            gl::VertexAttribPointer(
+                1,
+                3,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (3 * mem::size_of::<f32>()) as *const _,
+            );
+            gl::BindVertexArray(0);
+        }
+
+        Self { shader, vao, vbo, vertices: Vec::new(), overlay: Vec::new() }
+    }
+
+    /// Queue a segment from `a` to `b` in `color` (sRGB).
+    pub fn line(&mut self, a: Vec3, b: Vec3, color: Vec3) {
+        let color = srgb_to_linear(color);
+        for p in [a, b] {
+            self.vertices.extend_from_slice(&[p.x, p.y, p.z, color.x, color.y, color.z]);
+        }
+    }
+
+    /// Queue a segment that shows through the scene, for things usually hidden inside meshes.
+    pub fn overlay_line(&mut self, a: Vec3, b: Vec3, color: Vec3) {
+        let color = srgb_to_linear(color);
+        for p in [a, b] {
+            self.overlay.extend_from_slice(&[p.x, p.y, p.z, color.x, color.y, color.z]);
+        }
+    }
+
+    /// Queue a grid in the horizontal plane through `center`, `cells` cells of size `step`
+    /// out from the centre in each direction.
+    pub fn grid(&mut self, center: Vec3, cells: u32, step: f32, color: Vec3) {
+        let half = cells as f32 * step;
+        for i in 0..=cells * 2 {
+            let t = -half + i as f32 * step;
+            self.line(center + Vec3::new(-half, 0.0, t), center + Vec3::new(half, 0.0, t), color);
+            self.line(center + Vec3::new(t, 0.0, -half), center + Vec3::new(t, 0.0, half), color);
+        }
+    }
+
+    /// Queue a circle of `radius` around `center` in the plane perpendicular to `axis`.
+    pub fn circle(&mut self, center: Vec3, axis: Vec3, radius: f32, color: Vec3) {
+        let (u, v) = axis.normalize().any_orthonormal_pair();
+        let point = |i: u32| {
+            let angle = i as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+            center + (u * angle.cos() + v * angle.sin()) * radius
+        };
+        for i in 0..CIRCLE_SEGMENTS {
+            self.line(point(i), point(i + 1), color);
+        }
+    }
+
+    /// Draw and clear the queued lines. Must run with the scene framebuffer bound.
+    pub fn draw(&mut self) {
+        if self.vertices.is_empty() && self.overlay.is_empty() {
+            return;
+        }
+
+        self.shader.bind();
+        self.draw_batch(&self.vertices);
+        if !self.overlay.is_empty() {
+            unsafe {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+            self.draw_batch(&self.overlay);
+            unsafe {
+                gl::Enable(gl::DEPTH_TEST);
+            }
+        }
+        self.vertices.clear();
+        self.overlay.clear();
+    }
+
+    fn draw_batch(&self, vertices: &[f32]) {
+        if vertices.is_empty() {
+            return;
+        }
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                mem::size_of_val(vertices) as GLsizeiptr,
+                vertices.as_ptr() as *const _,
+                gl::STREAM_DRAW,
+            );
+            gl::DrawArrays(gl::LINES, 0, (vertices.len() / VERTEX_FLOATS) as GLsizei);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for GizmoRenderer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+        }
+    }
+}
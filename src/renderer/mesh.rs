@@ -1,20 +1,63 @@
 use gl::types::*;
+use glam::{Mat3, Mat4, Vec3};
+use std::collections::HashMap;
 use std::f32::consts::PI;
 use std::mem;
 use std::ptr;
 
+use crate::components::MeshShape;
+
+/// Primitive topology used by `Mesh::draw`.
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    Triangles,
+    Lines,
+}
+
+impl Primitive {
+    fn gl_mode(self) -> GLenum {
+        match self {
+            Primitive::Triangles => gl::TRIANGLES,
+            Primitive::Lines => gl::LINES,
+        }
+    }
+}
+
 pub struct Mesh {
     vao: GLuint,
     vbo: GLuint,
+    /// Element buffer, or 0 for non-indexed meshes drawn with `glDrawArrays`.
     ebo: GLuint,
+    primitive: Primitive,
     pub index_count: i32,
+    vertex_count: usize,
+    /// Local-space AABB (min, max) of the vertex positions.
+    bounds: (Vec3, Vec3),
 }
 
 impl Mesh {
+    #[allow(dead_code)]
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Local-space axis-aligned bounds as `(min, max)`. Empty meshes report a
+    /// zero-size box at the origin.
+    #[allow(dead_code)]
+    pub fn local_bounds(&self) -> (Vec3, Vec3) {
+        self.bounds
+    }
+
     pub fn draw(&self) {
+        let mode = self.primitive.gl_mode();
         unsafe {
             gl::BindVertexArray(self.vao);
-            gl::DrawElements(gl::TRIANGLES, self.index_count, gl::UNSIGNED_INT, ptr::null());
+            if self.ebo != 0 {
+                gl::DrawElements(mode, self.index_count, gl::UNSIGNED_INT, ptr::null());
+            } else {
+                gl::DrawArrays(mode, 0, self.vertex_count as GLsizei);
+            }
             gl::BindVertexArray(0);
         }
     }
@@ -22,14 +65,187 @@ impl Mesh {
 
 impl Drop for Mesh {
     fn drop(&mut self) {
+        // Meshes built in unit tests never reached a GL context (see `upload_mesh`).
+        if cfg!(test) {
+            return;
+        }
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
             gl::DeleteBuffers(1, &self.vbo);
-            gl::DeleteBuffers(1, &self.ebo);
+            if self.ebo != 0 {
+                gl::DeleteBuffers(1, &self.ebo);
+            }
+        }
+    }
+}
+
+/// CPU-side mesh geometry: interleaved `[px, py, pz, nx, ny, nz]` vertices plus triangle
+/// indices. The `*_data` factories return this so it can be post-processed before upload.
+pub struct MeshData {
+    pub vertices: Vec<f32>,
+    pub indices: Vec<u32>,
+}
+
+/// How vertex normals are generated when recomputing them from geometry.
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NormalMode {
+    /// Vertices sharing a position get the area-weighted average of their faces' normals.
+    Smooth,
+    /// Every triangle gets its own three vertices carrying the face normal (faceted look).
+    Flat,
+}
+
+impl MeshData {
+    pub fn upload(&self) -> Mesh {
+        upload_mesh(&self.vertices, &self.indices)
+    }
+
+    /// Recompute normals in the given mode. `Flat` un-shares vertices, so the returned
+    /// buffers grow to three vertices per triangle.
+    #[allow(dead_code)]
+    pub fn with_normals(self, mode: NormalMode) -> MeshData {
+        match mode {
+            NormalMode::Flat => self.flat_normals(),
+            NormalMode::Smooth => self.smooth_normals(),
+        }
+    }
+
+    fn position(&self, index: u32) -> [f32; 3] {
+        let o = index as usize * 6;
+        [self.vertices[o], self.vertices[o + 1], self.vertices[o + 2]]
+    }
+
+    fn normal(&self, index: u32) -> [f32; 3] {
+        let o = index as usize * 6;
+        [self.vertices[o + 3], self.vertices[o + 4], self.vertices[o + 5]]
+    }
+
+    /// Unnormalised face normal of a triangle (length = twice its area). Factories don't
+    /// share one winding convention, so the result is flipped to agree with the triangle's
+    /// existing vertex normals.
+    fn face_normal(&self, tri: &[u32]) -> [f32; 3] {
+        let p = [self.position(tri[0]), self.position(tri[1]), self.position(tri[2])];
+        let e1 = [p[1][0] - p[0][0], p[1][1] - p[0][1], p[1][2] - p[0][2]];
+        let e2 = [p[2][0] - p[0][0], p[2][1] - p[0][1], p[2][2] - p[0][2]];
+        let n = [
+            e1[1] * e2[2] - e1[2] * e2[1],
+            e1[2] * e2[0] - e1[0] * e2[2],
+            e1[0] * e2[1] - e1[1] * e2[0],
+        ];
+        let hint = tri.iter().map(|&i| self.normal(i)).fold([0.0; 3], |a, b| {
+            [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+        });
+        if n[0] * hint[0] + n[1] * hint[1] + n[2] * hint[2] < 0.0 {
+            [-n[0], -n[1], -n[2]]
+        } else {
+            n
+        }
+    }
+
+    fn flat_normals(self) -> MeshData {
+        let mut vertices = Vec::with_capacity(self.indices.len() * 6);
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for tri in self.indices.chunks_exact(3) {
+            let n = self.face_normal(tri);
+            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt().max(1e-12);
+            let n = [n[0] / len, n[1] / len, n[2] / len];
+            for &i in tri {
+                let v = self.position(i);
+                indices.push(indices.len() as u32);
+                vertices.extend_from_slice(&[v[0], v[1], v[2], n[0], n[1], n[2]]);
+            }
+        }
+        MeshData { vertices, indices }
+    }
+
+    fn smooth_normals(mut self) -> MeshData {
+        // Weld by exact position so split seams (box corners, UV seams) smooth together.
+        // Adding 0.0 folds -0.0 into +0.0 so mirrored seams share a key.
+        let key = |p: [f32; 3]| {
+            ((p[0] + 0.0).to_bits(), (p[1] + 0.0).to_bits(), (p[2] + 0.0).to_bits())
+        };
+        let mut sums: HashMap<(u32, u32, u32), [f32; 3]> = HashMap::new();
+        for tri in self.indices.chunks_exact(3) {
+            // Area-weighted: larger faces pull the shared normal further.
+            let n = self.face_normal(tri);
+            for &i in tri {
+                let sum = sums.entry(key(self.position(i))).or_insert([0.0; 3]);
+                sum[0] += n[0];
+                sum[1] += n[1];
+                sum[2] += n[2];
+            }
+        }
+        for v in 0..self.vertices.len() / 6 {
+            let Some(n) = sums.get(&key(self.position(v as u32))) else { continue };
+            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            if len < 1e-12 {
+                continue;
+            }
+            let o = v * 6;
+            self.vertices[o + 3] = n[0] / len;
+            self.vertices[o + 4] = n[1] / len;
+            self.vertices[o + 5] = n[2] / len;
+        }
+        self
+    }
+}
+
+/// Accumulates transformed sub-meshes into a single buffer, e.g. to merge static scenery
+/// into one draw call or to compose a multi-part prop like the sword.
+pub struct MeshBuilder {
+    data: MeshData,
+}
+
+impl MeshBuilder {
+    pub fn new() -> Self {
+        Self { data: MeshData { vertices: Vec::new(), indices: Vec::new() } }
+    }
+
+    /// Append `mesh` with `transform` baked in. Normals go through the inverse-transpose
+    /// so non-uniform scale keeps them perpendicular; mirroring transforms flip the
+    /// triangle winding so faces keep their orientation.
+    pub fn add(&mut self, mesh: &MeshData, transform: Mat4) -> &mut Self {
+        let base = (self.data.vertices.len() / 6) as u32;
+        let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+
+        for v in mesh.vertices.chunks_exact(6) {
+            let p = transform.transform_point3(Vec3::new(v[0], v[1], v[2]));
+            let n = (normal_matrix * Vec3::new(v[3], v[4], v[5])).normalize_or_zero();
+            self.data.vertices.extend_from_slice(&[p.x, p.y, p.z, n.x, n.y, n.z]);
+        }
+
+        let mirrored = transform.determinant() < 0.0;
+        for tri in mesh.indices.chunks_exact(3) {
+            if mirrored {
+                self.data.indices.extend_from_slice(&[base + tri[0], base + tri[2], base + tri[1]]);
+            } else {
+                self.data.indices.extend_from_slice(&[base + tri[0], base + tri[1], base + tri[2]]);
+            }
         }
+        self
+    }
+
+    pub fn finish(self) -> MeshData {
+        self.data
+    }
+
+    #[allow(dead_code)]
+    pub fn build(self) -> Mesh {
+        self.data.upload()
     }
 }
 
+/// AABB of the positions in an interleaved vertex buffer with `stride` floats per vertex.
+fn position_bounds(vertices: &[f32], stride: usize) -> (Vec3, Vec3) {
+    let mut positions = vertices.chunks_exact(stride).map(|v| Vec3::new(v[0], v[1], v[2]));
+    let Some(first) = positions.next() else {
+        return (Vec3::ZERO, Vec3::ZERO);
+    };
+    positions.fold((first, first), |(min, max), p| (min.min(p), max.max(p)))
+}
+
+#[cfg(not(test))]
 fn upload_mesh(vertices: &[f32], indices: &[u32]) -> Mesh {
     let mut vao = 0;
     let mut vbo = 0;
@@ -82,11 +298,88 @@ fn upload_mesh(vertices: &[f32], indices: &[u32]) -> Mesh {
         vao,
         vbo,
         ebo,
+        primitive: Primitive::Triangles,
         index_count: indices.len() as i32,
+        vertex_count: vertices.len() / 6,
+        bounds: position_bounds(vertices, 6),
     }
 }
 
-pub fn create_sphere(radius: f32, stacks: u32, sectors: u32) -> Mesh {
+/// Unit tests run without a GL context, so their meshes keep only counts and bounds and are
+/// never uploaded (or deleted).
+#[cfg(test)]
+fn upload_mesh(vertices: &[f32], indices: &[u32]) -> Mesh {
+    Mesh {
+        vao: 0,
+        vbo: 0,
+        ebo: 0,
+        primitive: Primitive::Triangles,
+        index_count: indices.len() as i32,
+        vertex_count: vertices.len() / 6,
+        bounds: position_bounds(vertices, 6),
+    }
+}
+
+/// Upload a non-indexed, position-only (stride 3) line list: every consecutive pair of
+/// points is one segment. Used for gizmos, grids and wireframe overlays; shaders drawing
+/// it must not read a normal attribute.
+#[allow(dead_code)]
+pub fn upload_lines(positions: &[f32]) -> Mesh {
+    let mut vao = 0;
+    let mut vbo = 0;
+
+    unsafe {
+        gl::GenVertexArrays(1, &mut vao);
+        gl::GenBuffers(1, &mut vbo);
+
+        gl::BindVertexArray(vao);
+
+        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+        gl::BufferData(
+            gl::ARRAY_BUFFER,
+            (positions.len() * mem::size_of::<f32>()) as GLsizeiptr,
+            positions.as_ptr() as *const _,
+            gl::STATIC_DRAW,
+        );
+
+        let stride = 3 * mem::size_of::<f32>() as GLsizei;
+
+        // position attribute (location 0)
+        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, ptr::null());
+        gl::EnableVertexAttribArray(0);
+
+        gl::BindVertexArray(0);
+    }
+
+    Mesh {
+        vao,
+        vbo,
+        ebo: 0,
+        primitive: Primitive::Lines,
+        index_count: 0,
+        vertex_count: positions.len() / 3,
+        bounds: position_bounds(positions, 3),
+    }
+}
+
+/// Create a `size × size` line grid in the XZ plane at y = 0 with `divisions` cells per
+/// side, as a single `GL_LINES` mesh.
+#[allow(dead_code)]
+pub fn create_line_grid(size: f32, divisions: u32) -> Mesh {
+    let n = divisions.max(1);
+    let half = size * 0.5;
+    let step = size / n as f32;
+    let mut positions = Vec::with_capacity(((n + 1) * 12) as usize);
+    for i in 0..=n {
+        let t = -half + i as f32 * step;
+        // Line parallel to X, then line parallel to Z.
+        positions.extend_from_slice(&[-half, 0.0, t, half, 0.0, t]);
+        positions.extend_from_slice(&[t, 0.0, -half, t, 0.0, half]);
+    }
+    upload_lines(&positions)
+}
+
+pub fn sphere_data(radius: f32, stacks: u32, sectors: u32) -> MeshData {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
@@ -130,10 +423,15 @@ pub fn create_sphere(radius: f32, stacks: u32, sectors: u32) -> Mesh {
         }
     }
 
-    upload_mesh(&vertices, &indices)
+    MeshData { vertices, indices }
 }
 
-pub fn create_capsule(radius: f32, height: f32, sectors: u32, stacks: u32) -> Mesh {
+/// Upload [`sphere_data`] to the GPU.
+pub fn create_sphere(radius: f32, stacks: u32, sectors: u32) -> Mesh {
+    sphere_data(radius, stacks, sectors).upload()
+}
+
+pub fn capsule_data(radius: f32, height: f32, sectors: u32, stacks: u32) -> MeshData {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
@@ -208,11 +506,16 @@ pub fn create_capsule(radius: f32, height: f32, sectors: u32, stacks: u32) -> Me
         }
     }
 
-    upload_mesh(&vertices, &indices)
+    MeshData { vertices, indices }
+}
+
+/// Upload [`capsule_data`] to the GPU.
+pub fn create_capsule(radius: f32, height: f32, sectors: u32, stacks: u32) -> Mesh {
+    capsule_data(radius, height, sectors, stacks).upload()
 }
 
 #[allow(dead_code)]
-pub fn create_box(width: f32, height: f32, depth: f32) -> Mesh {
+pub fn box_data(width: f32, height: f32, depth: f32) -> MeshData {
     let hw = width * 0.5;
     let hh = height * 0.5;
     let hd = depth * 0.5;
@@ -257,14 +560,20 @@ pub fn create_box(width: f32, height: f32, depth: f32) -> Mesh {
         indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
     }
 
-    upload_mesh(&vertices, &indices)
+    MeshData { vertices, indices }
+}
+
+/// Upload [`box_data`] to the GPU.
+#[allow(dead_code)]
+pub fn create_box(width: f32, height: f32, depth: f32) -> Mesh {
+    box_data(width, height, depth).upload()
 }
 
 /// Create a tapered box (rectangular prism where top and bottom can have different dimensions).
 /// Top face has dimensions `top_w × top_d`, bottom face has `bot_w × bot_d`.
 /// Side face normals are computed via cross products for correct trapezoid normals.
 #[allow(dead_code)]
-pub fn create_tapered_box(top_w: f32, top_d: f32, bot_w: f32, bot_d: f32, height: f32) -> Mesh {
+pub fn tapered_box_data(top_w: f32, top_d: f32, bot_w: f32, bot_d: f32, height: f32) -> MeshData {
     let hh = height * 0.5;
     let htw = top_w * 0.5;
     let htd = top_d * 0.5;
@@ -330,11 +639,17 @@ pub fn create_tapered_box(top_w: f32, top_d: f32, bot_w: f32, bot_d: f32, height
     let n = face_normal(corners[3], corners[7], corners[0]);
     add_quad(corners[3], corners[7], corners[4], corners[0], n[0], n[1], n[2]);
 
-    upload_mesh(&vertices, &indices)
+    MeshData { vertices, indices }
 }
 
+/// Upload [`tapered_box_data`] to the GPU.
 #[allow(dead_code)]
-pub fn create_cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
+pub fn create_tapered_box(top_w: f32, top_d: f32, bot_w: f32, bot_d: f32, height: f32) -> Mesh {
+    tapered_box_data(top_w, top_d, bot_w, bot_d, height).upload()
+}
+
+#[allow(dead_code)]
+pub fn cylinder_data(radius: f32, height: f32, segments: u32) -> MeshData {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
     let half_h = height * 0.5;
@@ -390,123 +705,460 @@ pub fn create_cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
         indices.extend_from_slice(&[bot_center, bot_ring_start + i + 1, bot_ring_start + i]);
     }
 
-    upload_mesh(&vertices, &indices)
+    MeshData { vertices, indices }
 }
 
-/// Create a sword mesh composed of blade (box), crossguard (box), and handle (cylinder).
-/// Origin is at the grip point (top of handle / base of blade).
-pub fn create_sword() -> Mesh {
+/// Upload [`cylinder_data`] to the GPU.
+#[allow(dead_code)]
+pub fn create_cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
+    cylinder_data(radius, height, segments).upload()
+}
+
+/// Create a flat `size × size` plane in XZ at y = 0, tessellated into
+/// `subdivisions × subdivisions` quads with upward normals. Unlike a single quad, this
+/// gives per-vertex lighting and future displacement something to work with.
+/// Subdivisions are clamped to 65 534, the most for which every vertex index, up to
+/// `(n + 1)² - 1`, still fits in a `u32`.
+#[allow(dead_code)]
+pub fn grid_plane_data(size: f32, subdivisions: u32) -> MeshData {
+    let n = subdivisions.clamp(1, 65_534);
+    let half = size * 0.5;
+    let step = size / n as f32;
+    // Counts in usize: (n + 1)² * 6 floats overflows u32 long before the index range does.
+    let side = n as usize;
+    let mut vertices = Vec::with_capacity((side + 1) * (side + 1) * 6);
+    let mut indices = Vec::with_capacity(side * side * 6);
+
+    // Row i runs along +Z, column j along +X.
+    for i in 0..=n {
+        let z = -half + i as f32 * step;
+        for j in 0..=n {
+            let x = -half + j as f32 * step;
+            vertices.extend_from_slice(&[x, 0.0, z, 0.0, 1.0, 0.0]);
+        }
+    }
+
+    // Counter-clockwise seen from above for every quad.
+    for i in 0..n {
+        for j in 0..n {
+            let a = i * (n + 1) + j;
+            let b = a + n + 1;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    MeshData { vertices, indices }
+}
+
+/// Upload [`grid_plane_data`] to the GPU.
+#[allow(dead_code)]
+pub fn create_grid_plane(size: f32, subdivisions: u32) -> Mesh {
+    grid_plane_data(size, subdivisions).upload()
+}
+
+/// Create a torus lying in the XZ plane, centred at the origin.
+/// `major_radius` is the distance from the centre to the middle of the tube; `minor_radius`
+/// is the tube radius. Seam vertices are duplicated (same positions) so UV-style rings close
+/// without gaps, and normals point outward from the tube centre line.
+#[allow(dead_code)]
+pub fn torus_data(
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: u32,
+    minor_segments: u32,
+) -> MeshData {
+    let major_segments = major_segments.max(3);
+    let minor_segments = minor_segments.max(3);
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
-    // Helper: append a box at an offset position, return vertex count added
-    let add_box = |verts: &mut Vec<f32>, idxs: &mut Vec<u32>,
-                       w: f32, h: f32, d: f32, offset_y: f32| {
-        let base = verts.len() as u32 / 6;
-        let hw = w * 0.5;
-        let hh = h * 0.5;
-        let hd = d * 0.5;
-        let oy = offset_y;
-
-        #[rustfmt::skip]
-        let box_verts: [f32; 144] = [
-            // Front (+Z)
-            -hw, -hh + oy,  hd,  0.0,  0.0,  1.0,
-             hw, -hh + oy,  hd,  0.0,  0.0,  1.0,
-             hw,  hh + oy,  hd,  0.0,  0.0,  1.0,
-            -hw,  hh + oy,  hd,  0.0,  0.0,  1.0,
-            // Back (-Z)
-             hw, -hh + oy, -hd,  0.0,  0.0, -1.0,
-            -hw, -hh + oy, -hd,  0.0,  0.0, -1.0,
-            -hw,  hh + oy, -hd,  0.0,  0.0, -1.0,
-             hw,  hh + oy, -hd,  0.0,  0.0, -1.0,
-            // Top (+Y)
-            -hw,  hh + oy,  hd,  0.0,  1.0,  0.0,
-             hw,  hh + oy,  hd,  0.0,  1.0,  0.0,
-             hw,  hh + oy, -hd,  0.0,  1.0,  0.0,
-            -hw,  hh + oy, -hd,  0.0,  1.0,  0.0,
-            // Bottom (-Y)
-            -hw, -hh + oy, -hd,  0.0, -1.0,  0.0,
-             hw, -hh + oy, -hd,  0.0, -1.0,  0.0,
-             hw, -hh + oy,  hd,  0.0, -1.0,  0.0,
-            -hw, -hh + oy,  hd,  0.0, -1.0,  0.0,
-            // Right (+X)
-             hw, -hh + oy,  hd,  1.0,  0.0,  0.0,
-             hw, -hh + oy, -hd,  1.0,  0.0,  0.0,
-             hw,  hh + oy, -hd,  1.0,  0.0,  0.0,
-             hw,  hh + oy,  hd,  1.0,  0.0,  0.0,
-            // Left (-X)
-            -hw, -hh + oy, -hd, -1.0,  0.0,  0.0,
-            -hw, -hh + oy,  hd, -1.0,  0.0,  0.0,
-            -hw,  hh + oy,  hd, -1.0,  0.0,  0.0,
-            -hw,  hh + oy, -hd, -1.0,  0.0,  0.0,
-        ];
+    for i in 0..=major_segments {
+        // Wrap the last ring back to exactly angle 0 so the seam is watertight.
+        let u = 2.0 * PI * ((i % major_segments) as f32) / (major_segments as f32);
+        let (su, cu) = u.sin_cos();
 
-        verts.extend_from_slice(&box_verts);
-        for face in 0..6u32 {
-            let b = base + face * 4;
-            idxs.extend_from_slice(&[b, b + 1, b + 2, b, b + 2, b + 3]);
-        }
-    };
+        for j in 0..=minor_segments {
+            let v = 2.0 * PI * ((j % minor_segments) as f32) / (minor_segments as f32);
+            let (sv, cv) = v.sin_cos();
 
-    // Helper: append a cylinder at an offset position
-    let add_cylinder = |verts: &mut Vec<f32>, idxs: &mut Vec<u32>,
-                        radius: f32, height: f32, segments: u32, offset_y: f32| {
-        let base = verts.len() as u32 / 6;
-        let half_h = height * 0.5;
-
-        // Side rings
-        for i in 0..=segments {
-            let angle = 2.0 * PI * (i as f32) / (segments as f32);
-            let nx = angle.cos();
-            let nz = angle.sin();
-            let x = radius * nx;
-            let z = radius * nz;
-            verts.extend_from_slice(&[x, -half_h + offset_y, z, nx, 0.0, nz]);
-            verts.extend_from_slice(&[x, half_h + offset_y, z, nx, 0.0, nz]);
-        }
-        for i in 0..segments {
-            let bot = base + i * 2;
-            let top = bot + 1;
-            let next_bot = bot + 2;
-            let next_top = bot + 3;
-            idxs.extend_from_slice(&[bot, next_bot, top, top, next_bot, next_top]);
-        }
+            let ring = major_radius + minor_radius * cv;
+            vertices.extend_from_slice(&[ring * cu, minor_radius * sv, ring * su]);
 
-        // Top cap
-        let tc = verts.len() as u32 / 6;
-        verts.extend_from_slice(&[0.0, half_h + offset_y, 0.0, 0.0, 1.0, 0.0]);
-        let tr = verts.len() as u32 / 6;
-        for i in 0..=segments {
-            let angle = 2.0 * PI * (i as f32) / (segments as f32);
-            verts.extend_from_slice(&[radius * angle.cos(), half_h + offset_y, radius * angle.sin(), 0.0, 1.0, 0.0]);
+            // Normal: direction from the tube centre line to the surface point.
+            vertices.extend_from_slice(&[cv * cu, sv, cv * su]);
         }
-        for i in 0..segments {
-            idxs.extend_from_slice(&[tc, tr + i, tr + i + 1]);
+    }
+
+    for i in 0..major_segments {
+        for j in 0..minor_segments {
+            let first = i * (minor_segments + 1) + j;
+            let second = first + minor_segments + 1;
+
+            indices.extend_from_slice(&[first, first + 1, second]);
+            indices.extend_from_slice(&[second, first + 1, second + 1]);
         }
+    }
+
+    MeshData { vertices, indices }
+}
+
+/// Upload [`torus_data`] to the GPU.
+#[allow(dead_code)]
+pub fn create_torus(
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: u32,
+    minor_segments: u32,
+) -> Mesh {
+    torus_data(major_radius, minor_radius, major_segments, minor_segments).upload()
+}
+
+/// Create a cone centred at the origin: circular base at `-height/2`, apex at `+height/2`.
+/// The apex is duplicated per side segment (with the segment's mid-angle slant normal) so
+/// side shading interpolates smoothly instead of collapsing at the singular tip.
+#[allow(dead_code)]
+pub fn cone_data(radius: f32, height: f32, segments: u32) -> MeshData {
+    let segments = segments.max(3);
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let half_h = height * 0.5;
+
+    // Slant normal at angle a: perpendicular to the side line, pointing outward.
+    let slant = (height * height + radius * radius).sqrt().max(1e-6);
+    let ny = radius / slant;
+    let nr = height / slant;
+
+    // Side: base ring vertices interleaved with one apex vertex per segment.
+    for i in 0..=segments {
+        let angle = 2.0 * PI * (i as f32) / (segments as f32);
+        let (sa, ca) = angle.sin_cos();
+        vertices.extend_from_slice(&[radius * ca, -half_h, radius * sa, nr * ca, ny, nr * sa]);
+
+        let mid = 2.0 * PI * (i as f32 + 0.5) / (segments as f32);
+        let (sm, cm) = mid.sin_cos();
+        vertices.extend_from_slice(&[0.0, half_h, 0.0, nr * cm, ny, nr * sm]);
+    }
+    for i in 0..segments {
+        let ring = i * 2;
+        let apex = ring + 1;
+        let next_ring = ring + 2;
+        indices.extend_from_slice(&[ring, apex, next_ring]);
+    }
+
+    // Base cap (-Y)
+    let center = vertices.len() as u32 / 6;
+    vertices.extend_from_slice(&[0.0, -half_h, 0.0, 0.0, -1.0, 0.0]);
+    let ring_start = vertices.len() as u32 / 6;
+    for i in 0..=segments {
+        let angle = 2.0 * PI * (i as f32) / (segments as f32);
+        vertices.extend_from_slice(&[
+            radius * angle.cos(),
+            -half_h,
+            radius * angle.sin(),
+            0.0,
+            -1.0,
+            0.0,
+        ]);
+    }
+    for i in 0..segments {
+        indices.extend_from_slice(&[center, ring_start + i, ring_start + i + 1]);
+    }
+
+    MeshData { vertices, indices }
+}
+
+/// Upload [`cone_data`] to the GPU.
+#[allow(dead_code)]
+pub fn create_cone(radius: f32, height: f32, segments: u32) -> Mesh {
+    cone_data(radius, height, segments).upload()
+}
+
+/// Create a square pyramid centred at the origin: `base × base` square at `-height/2`,
+/// apex at `+height/2`. Each face gets its own vertices and flat normal.
+#[allow(dead_code)]
+pub fn pyramid_data(base: f32, height: f32) -> MeshData {
+    let hb = base * 0.5;
+    let hh = height * 0.5;
+
+    let apex = [0.0, hh, 0.0];
+    // Base corners, counter-clockwise seen from above.
+    let corners: [[f32; 3]; 4] = [
+        [-hb, -hh,  hb], // 0: left-front
+        [ hb, -hh,  hb], // 1: right-front
+        [ hb, -hh, -hb], // 2: right-back
+        [-hb, -hh, -hb], // 3: left-back
+    ];
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
 
-        // Bottom cap
-        let bc = verts.len() as u32 / 6;
-        verts.extend_from_slice(&[0.0, -half_h + offset_y, 0.0, 0.0, -1.0, 0.0]);
-        let br = verts.len() as u32 / 6;
-        for i in 0..=segments {
-            let angle = 2.0 * PI * (i as f32) / (segments as f32);
-            verts.extend_from_slice(&[radius * angle.cos(), -half_h + offset_y, radius * angle.sin(), 0.0, -1.0, 0.0]);
+    // Side faces: each base edge plus the apex, wound counter-clockwise from outside.
+    for k in 0..4 {
+        let a = corners[k];
+        let b = corners[(k + 1) % 4];
+        let n = triangle_normal(a, b, apex);
+        let base_idx = vertices.len() as u32 / 6;
+        for p in &[a, b, apex] {
+            vertices.extend_from_slice(&[p[0], p[1], p[2], n[0], n[1], n[2]]);
         }
-        for i in 0..segments {
-            idxs.extend_from_slice(&[bc, br + i + 1, br + i]);
+        indices.extend_from_slice(&[base_idx, base_idx + 1, base_idx + 2]);
+    }
+
+    // Base (-Y): reverse order so it faces down.
+    let base_idx = vertices.len() as u32 / 6;
+    for c in corners.iter().rev() {
+        vertices.extend_from_slice(&[c[0], c[1], c[2], 0.0, -1.0, 0.0]);
+    }
+    indices.extend_from_slice(&[
+        base_idx,
+        base_idx + 1,
+        base_idx + 2,
+        base_idx,
+        base_idx + 2,
+        base_idx + 3,
+    ]);
+
+    MeshData { vertices, indices }
+}
+
+/// Upload [`pyramid_data`] to the GPU.
+#[allow(dead_code)]
+pub fn create_pyramid(base: f32, height: f32) -> Mesh {
+    pyramid_data(base, height).upload()
+}
+
+/// Unit normal of triangle (a, b, c) with counter-clockwise winding.
+fn triangle_normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    let e1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let e2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let nx = e1[1] * e2[2] - e1[2] * e2[1];
+    let ny = e1[2] * e2[0] - e1[0] * e2[2];
+    let nz = e1[0] * e2[1] - e1[1] * e2[0];
+    let len = (nx * nx + ny * ny + nz * nz).sqrt();
+    if len < 1e-12 { [0.0, 1.0, 0.0] } else { [nx / len, ny / len, nz / len] }
+}
+
+/// Parse Wavefront OBJ text into mesh data. Reads `v`, `vn` and `f` lines; everything
+/// else (texture coordinates, groups, materials) is skipped. Polygons are fan-triangulated.
+/// Corners sharing a position/normal pair share a vertex; faces without normals get their
+/// own vertices with the flat face normal. Negative (relative) indices are supported.
+#[allow(dead_code)]
+pub fn obj_data(source: &str) -> Result<MeshData, String> {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut shared: HashMap<(usize, usize), u32> = HashMap::new();
+
+    for (line_no, line) in source.lines().enumerate() {
+        let line_no = line_no + 1;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => positions.push(parse_obj_vec3(tokens, line_no)?),
+            Some("vn") => normals.push(parse_obj_vec3(tokens, line_no)?),
+            Some("f") => {
+                let mut corners = Vec::new();
+                for token in tokens {
+                    let mut parts = token.split('/');
+                    let v = parse_obj_index(parts.next(), positions.len(), line_no)?;
+                    // `v`, `v/vt`, `v//vn`, `v/vt/vn`; an empty trailing slot (`v/vt/`) is
+                    // no normal, like a missing one.
+                    let vn = match parts.nth(1).filter(|vn| !vn.is_empty()) {
+                        Some(vn) => Some(parse_obj_index(Some(vn), normals.len(), line_no)?),
+                        None => None,
+                    };
+                    corners.push((v, vn));
+                }
+                if corners.len() < 3 {
+                    return Err(format!("line {line_no}: face has fewer than 3 vertices"));
+                }
+                if corners.iter().any(|c| c.1.is_none()) {
+                    // Flat: one normal for the whole polygon, from its first three corners.
+                    let p = |i: usize| positions[corners[i].0];
+                    let n = triangle_normal(p(0), p(1), p(2));
+                    let base = (vertices.len() / 6) as u32;
+                    for &(v, _) in &corners {
+                        let p = positions[v];
+                        vertices.extend_from_slice(&[p[0], p[1], p[2], n[0], n[1], n[2]]);
+                    }
+                    for i in 1..corners.len() as u32 - 1 {
+                        indices.extend_from_slice(&[base, base + i, base + i + 1]);
+                    }
+                } else {
+                    let mut corner_index = |(v, vn): (usize, Option<usize>)| {
+                        let vn = vn.unwrap_or_default();
+                        *shared.entry((v, vn)).or_insert_with(|| {
+                            let (p, n) = (positions[v], normals[vn]);
+                            vertices.extend_from_slice(&[p[0], p[1], p[2], n[0], n[1], n[2]]);
+                            (vertices.len() / 6 - 1) as u32
+                        })
+                    };
+                    let fan: Vec<u32> = corners.iter().map(|&c| corner_index(c)).collect();
+                    for i in 1..fan.len() - 1 {
+                        indices.extend_from_slice(&[fan[0], fan[i], fan[i + 1]]);
+                    }
+                }
+            }
+            _ => {}
         }
-    };
+    }
+
+    Ok(MeshData { vertices, indices })
+}
+
+/// Read and parse an OBJ file (see [`obj_data`]). Errors are prefixed with `path`.
+#[allow(dead_code)]
+pub fn read_obj(path: &str) -> Result<MeshData, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    obj_data(&source).map_err(|e| format!("{path}: {e}"))
+}
+
+/// Read an OBJ file (see [`obj_data`]) and upload it to the GPU.
+#[allow(dead_code)]
+pub fn load_obj(path: &str) -> Result<Mesh, String> {
+    Ok(read_obj(path)?.upload())
+}
+
+fn parse_obj_vec3<'a>(
+    mut tokens: impl Iterator<Item = &'a str>,
+    line_no: usize,
+) -> Result<[f32; 3], String> {
+    let mut v = [0.0; 3];
+    for c in &mut v {
+        let token =
+            tokens.next().ok_or_else(|| format!("line {line_no}: expected 3 coordinates"))?;
+        *c = token.parse().map_err(|_| format!("line {line_no}: bad coordinate '{token}'"))?;
+    }
+    Ok(v)
+}
+
+/// Resolve a 1-based (or negative, counting back from the latest) OBJ index into `0..len`.
+fn parse_obj_index(token: Option<&str>, len: usize, line_no: usize) -> Result<usize, String> {
+    let token = token.unwrap_or_default();
+    let index: i64 = token.parse().map_err(|_| format!("line {line_no}: bad index '{token}'"))?;
+    let resolved = if index < 0 { len as i64 + index } else { index - 1 };
+    if index == 0 || resolved < 0 || resolved >= len as i64 {
+        return Err(format!("line {line_no}: index {index} out of range (have {len})"));
+    }
+    Ok(resolved as usize)
+}
+
+/// Create a sword mesh composed of blade (box), crossguard (box), and handle (cylinder).
+/// Origin is at the grip point (top of handle / base of blade).
+pub fn sword_data() -> MeshData {
+    let mut builder = MeshBuilder::new();
 
     // Handle: cylinder, radius 0.02, height 0.15, centered below origin
-    add_cylinder(&mut vertices, &mut indices, 0.02, 0.15, 8, -0.075);
+    builder.add(&cylinder_data(0.02, 0.15, 8), Mat4::from_translation(Vec3::new(0.0, -0.075, 0.0)));
 
     // Crossguard: wide short box at origin (grip point)
-    add_box(&mut vertices, &mut indices, 0.2, 0.03, 0.03, 0.0);
+    builder.add(&box_data(0.2, 0.03, 0.03), Mat4::IDENTITY);
 
     // Blade: tall thin box above crossguard
-    add_box(&mut vertices, &mut indices, 0.05, 0.8, 0.02, 0.415);
+    builder.add(&box_data(0.05, 0.8, 0.02), Mat4::from_translation(Vec3::new(0.0, 0.415, 0.0)));
 
-    upload_mesh(&vertices, &indices)
+    builder.finish()
 }
 
+/// Upload [`sword_data`] to the GPU.
+pub fn create_sword() -> Mesh {
+    sword_data().upload()
+}
+
+/// Build the mesh a `MeshShape` describes.
+pub fn create_shape(shape: &MeshShape) -> Mesh {
+    match *shape {
+        MeshShape::Sphere { radius, stacks, sectors } => create_sphere(radius, stacks, sectors),
+        MeshShape::TaperedBox { top_w, top_d, bot_w, bot_d, height } => {
+            create_tapered_box(top_w, top_d, bot_w, bot_d, height)
+        }
+        MeshShape::Capsule { radius, height, sectors, stacks } => {
+            create_capsule(radius, height, sectors, stacks)
+        }
+        MeshShape::Sword => create_sword(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every index points at a vertex and every normal is unit length.
+    fn assert_well_formed(data: &MeshData) {
+        let vertex_count = data.vertices.len() / 6;
+        assert!(data.indices.iter().all(|&i| (i as usize) < vertex_count));
+        for (i, v) in data.vertices.chunks_exact(6).enumerate() {
+            let length = Vec3::new(v[3], v[4], v[5]).length();
+            assert!((length - 1.0).abs() < 1e-4, "vertex {i} normal has length {length}");
+        }
+    }
+
+    #[test]
+    fn cone_index_count_and_unit_normals() {
+        let cone = cone_data(0.5, 1.0, 16);
+        // One side triangle and one base triangle per segment.
+        assert_eq!(cone.indices.len(), 16 * 3 * 2);
+        assert_well_formed(&cone);
+    }
+
+    #[test]
+    fn torus_index_count_and_unit_normals() {
+        let torus = torus_data(1.0, 0.25, 24, 12);
+        // Two triangles per quad of the major × minor grid.
+        assert_eq!(torus.indices.len(), 24 * 12 * 6);
+        assert_well_formed(&torus);
+    }
+
+    const CUBE_OBJ: &str = "\
+# unit cube, one normal per face
+v -0.5 -0.5  0.5
+v  0.5 -0.5  0.5
+v  0.5  0.5  0.5
+v -0.5  0.5  0.5
+v -0.5 -0.5 -0.5
+v  0.5 -0.5 -0.5
+v  0.5  0.5 -0.5
+v -0.5  0.5 -0.5
+vn 0 0 1
+vn 0 0 -1
+vn 1 0 0
+vn -1 0 0
+vn 0 1 0
+vn 0 -1 0
+f 1//1 2//1 3//1 4//1
+f 6//2 5//2 8//2 7//2
+f 2//3 6//3 7//3 3//3
+f 5//4 1//4 4//4 8//4
+f 4//5 3//5 7//5 8//5
+f 5//6 6//6 2//6 1//6
+";
+
+    #[test]
+    fn obj_cube_from_file() {
+        let path = std::env::temp_dir().join(format!("lance_cube_{}.obj", std::process::id()));
+        std::fs::write(&path, CUBE_OBJ).unwrap();
+        let cube = read_obj(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        let cube = cube.unwrap();
+        // Each face's corners pair a position with that face's normal: 6 × 4 vertices.
+        assert_eq!(cube.vertices.len() / 6, 24);
+        // Six quads, fanned into two triangles each.
+        assert_eq!(cube.indices.len(), 36);
+        assert_well_formed(&cube);
+    }
+
+    #[test]
+    fn obj_empty_normal_slot_is_flat() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nf 1/1/ 2/1/ 3/1/\n";
+        let triangle = obj_data(obj).unwrap();
+        assert_eq!(triangle.indices, vec![0, 1, 2]);
+        // Flat normal of a counter-clockwise triangle in the XY plane: +Z.
+        assert_eq!(&triangle.vertices[3..6], &[0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn obj_bad_index_is_an_error() {
+        assert!(obj_data("v 0 0 0\nf 1 2 3\n").is_err());
+    }
+}
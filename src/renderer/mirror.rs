@@ -0,0 +1,122 @@
+use gl::types::*;
+use glam::{Mat4, Vec3};
+use hecs::{Entity, World};
+
+use crate::components::{Collider, GlobalTransform, PlanarMirror};
+
+/// Reflection texture resolution as a fraction of the scene's.
+pub const MIRROR_SCALE: f32 = 0.5;
+
+/// The horizontal plane a `PlanarMirror` reflects in.
+pub struct MirrorPlane {
+    pub entity: Entity,
+    /// World height of the plane.
+    pub height: f32,
+    pub strength: f32,
+}
+
+impl MirrorPlane {
+    /// The first `PlanarMirror` in `world` whose collider gives it a flat top: a ground
+    /// plane's surface or a box's upper face. Only one mirror is rendered per frame.
+    pub fn find(world: &World) -> Option<Self> {
+        let mut query = world.query::<(&PlanarMirror, &Collider, &GlobalTransform)>();
+        let mut mirrors: Vec<_> = query.iter().collect();
+        mirrors.sort_by_key(|&(entity, _)| entity);
+        mirrors.into_iter().find_map(|(entity, (mirror, collider, gt))| {
+            let height = match *collider {
+                Collider::Plane { normal, offset } if normal.abs_diff_eq(Vec3::Y, 1e-3) => offset,
+                Collider::Box { half_extents } => gt.0.w_axis.y + half_extents.y,
+                _ => return None,
+            };
+            Some(Self { entity, height, strength: mirror.strength.clamp(0.0, 1.0) })
+        })
+    }
+
+    /// `view` looking at the world flipped upside down about the plane.
+    pub fn reflect_view(&self, view: &Mat4) -> Mat4 {
+        let up = Vec3::new(0.0, self.height, 0.0);
+        *view
+            * Mat4::from_translation(up)
+            * Mat4::from_scale(Vec3::new(1.0, -1.0, 1.0))
+            * Mat4::from_translation(-up)
+    }
+
+    pub fn reflect_point(&self, p: Vec3) -> Vec3 {
+        Vec3::new(p.x, 2.0 * self.height - p.y, p.z)
+    }
+
+    /// World-space clip plane keeping only what lies above the mirror, for
+    /// `gl_ClipDistance` (`dot(plane, vec4(world, 1)) >= 0`).
+    pub fn clip_plane(&self) -> [f32; 4] {
+        [0.0, 1.0, 0.0, -self.height]
+    }
+}
+
+/// Offscreen colour + depth target the mirrored scene is drawn into. Colour is sRGB like
+/// the scene target, so sampling it returns linear values.
+pub struct MirrorTarget {
+    pub fbo: GLuint,
+    pub color: GLuint,
+    depth: GLuint,
+    pub size: (i32, i32),
+}
+
+impl MirrorTarget {
+    pub fn new(size: (i32, i32)) -> Self {
+        let mut fbo: GLuint = 0;
+        let mut color: GLuint = 0;
+        let mut depth: GLuint = 0;
+        unsafe {
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::GenTextures(1, &mut color);
+            gl::GenRenderbuffers(1, &mut depth);
+
+            gl::BindTexture(gl::TEXTURE_2D, color);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::SRGB8_ALPHA8 as i32,
+                size.0,
+                size.1,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+
+            gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, size.0, size.1);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                color,
+                0,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::RENDERBUFFER,
+                depth,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+        Self { fbo, color, depth, size }
+    }
+}
+
+impl Drop for MirrorTarget {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteTextures(1, &self.color);
+            gl::DeleteRenderbuffers(1, &self.depth);
+        }
+    }
+}
@@ -1,33 +1,130 @@
+mod decals;
+mod gizmos;
 pub mod mesh;
+mod mirror;
+mod post;
 pub mod shader;
+mod tiled;
+mod xray;
+
+use std::collections::HashMap;
 
 use gl::types::*;
 use glam::{Mat4, Vec3, Vec4};
-use hecs::World;
+use hecs::{Entity, World};
 use mesh::Mesh;
-use shader::ShaderProgram;
+use shader::{ShaderProgram, Std140, UniformBuffer};
+use decals::DecalRenderer;
+pub use gizmos::GizmoRenderer;
+use mirror::{MirrorPlane, MirrorTarget, MIRROR_SCALE};
+use post::{PostInputs, PostProcess};
+pub use post::{
+    ColorGrading, DepthOfField, Vignette, DEFAULT_DOF_APERTURE, DEFAULT_MOTION_BLUR,
+    DEFAULT_VIGNETTE_RADIUS, DEFAULT_VIGNETTE_STRENGTH,
+};
+use tiled::{PackedPointLight, TiledLights, TILE_SIZE};
+use xray::XRayRenderer;
 
 use crate::components::{
-    Checkerboard, Color, DirectionalLight, GlobalTransform, Hidden, LocalTransform, MeshHandle,
-    PointLight, SpotLight,
+    Checkerboard, Collider, Color, DirectionalLight, GlobalTransform, Hidden, LocalTransform,
+    MeshHandle, NoShadowCast, NoShadowReceive, PointLight, Reflective, SpotLight, Static,
+    Velocity,
 };
+use crate::engine::world_up::WorldUp;
 
 const VERT_SRC: &str = include_str!("../../shaders/cel.vert");
 const FRAG_SRC: &str = include_str!("../../shaders/cel.frag");
 const SHADOW_VERT_SRC: &str = include_str!("../../shaders/shadow.vert");
 const SHADOW_FRAG_SRC: &str = include_str!("../../shaders/shadow.frag");
+const FRAME_BLOCK_SRC: &str = include_str!("../../shaders/frame.glsl");
+/// Uniform buffer binding point of the `Frame` block.
+const FRAME_BINDING: u32 = 0;
 
+/// Fog/sky colour, sRGB-encoded like every other authored colour.
 const FOG_COLOR: Vec3 = Vec3::new(0.1, 0.1, 0.15);
 
 const MAX_POINT_LIGHTS: usize = 8;
 const MAX_SPOT_LIGHTS: usize = 4;
+/// First texture unit of the tiled point-light textures (after the cascade shadow maps).
+const TILED_TEXTURE_UNIT: u32 = MAX_CASCADES as u32;
+/// Texture unit of the decal mask (after the three tiled-light textures).
+const DECAL_TEXTURE_UNIT: u32 = TILED_TEXTURE_UNIT + 3;
+/// Texture unit of the planar mirror's reflection.
+const MIRROR_TEXTURE_UNIT: u32 = DECAL_TEXTURE_UNIT + 1;
+/// Extra directional lights beyond the primary (shadowed) one; no shadows.
+const MAX_FILL_LIGHTS: usize = 3;
+/// Dynamic bodies uploaded as contact-occlusion spheres (nearest to camera first).
+const MAX_AO_SPHERES: usize = 8;
+/// How strongly a fully occluded fragment is darkened by contact AO.
+const AO_STRENGTH: f32 = 0.6;
+
+/// Shadow cascade count range. The shader declares arrays sized to `MAX_CASCADES`
+/// and reads `u_num_cascades` to know how many are live.
+pub const MIN_CASCADES: usize = 2;
+pub const MAX_CASCADES: usize = 4;
+pub const DEFAULT_CASCADES: usize = 3;
+
+/// Default camera distance (metres) covered by the shadow cascades.
+pub const DEFAULT_SHADOW_DISTANCE: f32 = 80.0;
+/// Default blend between uniform (0.0) and logarithmic (1.0) cascade splits.
+pub const DEFAULT_SPLIT_LAMBDA: f32 = 0.75;
 
-/// Number of shadow cascade slices.
-const NUM_CASCADES: usize = 3;
+/// Range of the exposure multiplier, and its default.
+pub const MIN_EXPOSURE: f32 = 0.1;
+pub const MAX_EXPOSURE: f32 = 4.0;
+pub const DEFAULT_EXPOSURE: f32 = 1.0;
 
-/// Camera-space depth split points (positive, metres).
-/// Cascade i covers [CASCADE_SPLITS[i], CASCADE_SPLITS[i+1]).
-const CASCADE_SPLITS: [f32; 4] = [0.1, 8.0, 25.0, 80.0];
+/// Range of the scene render scale (fraction of the window resolution).
+pub const MIN_RENDER_SCALE: f32 = 0.25;
+pub const MAX_RENDER_SCALE: f32 = 1.0;
+
+/// Renderer-init shadow configuration.
+#[derive(Clone, Copy)]
+pub struct ShadowSettings {
+    /// Number of cascades; clamped to [MIN_CASCADES, MAX_CASCADES].
+    pub cascades: usize,
+    /// Camera distance at which the last cascade ends.
+    pub distance: f32,
+    /// Practical split scheme blend factor in [0, 1].
+    pub split_lambda: f32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            cascades: DEFAULT_CASCADES,
+            distance: DEFAULT_SHADOW_DISTANCE,
+            split_lambda: DEFAULT_SPLIT_LAMBDA,
+        }
+    }
+}
+
+/// Eye adaptation: exposure drifts toward whatever brings the average scene luminance to
+/// `target`, at `speed` (1/s, exponential approach in log space), kept within [min, max].
+/// The manual exposure still applies on top, as compensation.
+#[derive(Clone, Copy)]
+pub struct AutoExposure {
+    /// Average linear luminance the adapted image aims for (mid grey by default).
+    pub target: f32,
+    pub speed: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+pub const DEFAULT_ADAPT_SPEED: f32 = 1.5;
+pub const DEFAULT_AUTO_EXPOSURE_MIN: f32 = 0.25;
+pub const DEFAULT_AUTO_EXPOSURE_MAX: f32 = 4.0;
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        Self {
+            target: 0.18,
+            speed: DEFAULT_ADAPT_SPEED,
+            min: DEFAULT_AUTO_EXPOSURE_MIN,
+            max: DEFAULT_AUTO_EXPOSURE_MAX,
+        }
+    }
+}
 
 /// How far behind each cascade to extend the light frustum to capture shadow casters.
 const SHADOW_CASTER_REACH: f32 = 150.0;
@@ -105,24 +202,167 @@ impl Drop for ShadowMap {
     }
 }
 
-/// Holds all loaded meshes. Entities reference meshes by MeshHandle index.
+/// Decode an sRGB-encoded colour (as authored in scenes and components) to linear, which is
+/// what lighting, fog and blending operate on.
+fn srgb_to_linear(c: Vec3) -> Vec3 {
+    let channel = |v: f32| {
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    Vec3::new(channel(c.x), channel(c.y), channel(c.z))
+}
+
+/// Offscreen target the scene is drawn into when it renders below window resolution or
+/// feeds a post-process. Every attachment is a texture so full-screen passes can sample it:
+/// - colour: sRGB like the window framebuffer, so the upscale blit copies encoded values,
+/// - velocity: per-pixel screen motion since last frame (UV units), written by the cel pass,
+/// - material: view-space normal (0–1 encoded) and reflectivity, also from the cel pass,
+/// - depth.
+struct SceneTarget {
+    fbo: GLuint,
+    color: GLuint,
+    velocity: GLuint,
+    material: GLuint,
+    depth: GLuint,
+    size: (i32, i32),
+}
+
+impl SceneTarget {
+    fn new(size: (i32, i32)) -> Self {
+        let mut fbo: GLuint = 0;
+        let mut textures = [0 as GLuint; 4];
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::GenTextures(4, textures.as_mut_ptr());
+            let [color, velocity, material, depth] = textures;
+
+            let formats = [
+                (color, gl::SRGB8_ALPHA8, gl::RGBA, gl::UNSIGNED_BYTE, gl::LINEAR),
+                (velocity, gl::RG16F, gl::RG, gl::FLOAT, gl::NEAREST),
+                (material, gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE, gl::NEAREST),
+                (depth, gl::DEPTH_COMPONENT24, gl::DEPTH_COMPONENT, gl::FLOAT, gl::NEAREST),
+            ];
+            for (texture, internal, format, ty, filter) in formats {
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                gl::TexImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    internal as i32,
+                    size.0,
+                    size.1,
+                    0,
+                    format,
+                    ty,
+                    std::ptr::null(),
+                );
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            }
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            let attachments = [
+                (gl::COLOR_ATTACHMENT0, color),
+                (gl::COLOR_ATTACHMENT1, velocity),
+                (gl::COLOR_ATTACHMENT2, material),
+                (gl::DEPTH_ATTACHMENT, depth),
+            ];
+            for (attachment, texture) in attachments {
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, gl::TEXTURE_2D, texture, 0);
+            }
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        let [color, velocity, material, depth] = textures;
+        Self { fbo, color, velocity, material, depth, size }
+    }
+}
+
+impl Drop for SceneTarget {
+    fn drop(&mut self) {
+        let textures = [self.color, self.velocity, self.material, self.depth];
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteTextures(4, textures.as_ptr());
+        }
+    }
+}
+
+/// Practical cascade split points between `near` and `far`: each split blends the
+/// logarithmic and uniform schemes by `lambda` (1.0 = fully logarithmic).
+/// Returns `count + 1` strictly increasing depths; cascade i covers [splits[i], splits[i+1]).
+fn cascade_splits(near: f32, far: f32, count: usize, lambda: f32) -> [f32; MAX_CASCADES + 1] {
+    let near = near.max(1e-3);
+    let far = far.max(near + 1.0);
+    let lambda = lambda.clamp(0.0, 1.0);
+
+    let mut splits = [far; MAX_CASCADES + 1];
+    splits[0] = near;
+    for i in 1..count {
+        let t = i as f32 / count as f32;
+        let log = near * (far / near).powf(t);
+        let uniform = near + (far - near) * t;
+        // Guard against degenerate (zero-width) cascades from float error.
+        splits[i] = (lambda * log + (1.0 - lambda) * uniform).max(splits[i - 1] + 1e-3);
+    }
+    splits[count] = far;
+    splits
+}
+
+/// Insert the shared `Frame` block declaration right after the `#version` line of `src`.
+fn with_frame_block(src: &str) -> String {
+    let (version, body) = src.split_once('\n').unwrap_or((src, ""));
+    format!("{version}\n{FRAME_BLOCK_SRC}{body}")
+}
+
+/// Holds all loaded meshes. Entities reference meshes by MeshHandle index. Removed meshes
+/// leave an empty slot that the next `add` fills, so handles stay small and stable.
 pub struct MeshStore {
-    meshes: Vec<Mesh>,
+    meshes: Vec<Option<Mesh>>,
+    /// Empty slots, reused most recently freed first.
+    free: Vec<usize>,
 }
 
 impl MeshStore {
     pub fn new() -> Self {
-        Self { meshes: Vec::new() }
+        Self { meshes: Vec::new(), free: Vec::new() }
     }
 
     pub fn add(&mut self, mesh: Mesh) -> MeshHandle {
-        let handle = MeshHandle(self.meshes.len());
-        self.meshes.push(mesh);
-        handle
+        if let Some(index) = self.free.pop() {
+            self.meshes[index] = Some(mesh);
+            return MeshHandle(index);
+        }
+        self.meshes.push(Some(mesh));
+        MeshHandle(self.meshes.len() - 1)
+    }
+
+    /// Drop the mesh, freeing its GPU buffers, and make its slot available to `add`. Any
+    /// entity still holding `handle` must be despawned or re-pointed first: after the next
+    /// `add` it would silently draw the new mesh. Removing an empty slot does nothing.
+    #[allow(dead_code)]
+    pub fn remove(&mut self, handle: MeshHandle) {
+        if let Some(slot) = self.meshes.get_mut(handle.0) {
+            if slot.take().is_some() {
+                self.free.push(handle.0);
+            }
+        }
     }
 
+    /// The mesh at `handle`. Panics if it was never added or has been removed; see
+    /// `try_get` for handles that may dangle.
     pub fn get(&self, handle: MeshHandle) -> &Mesh {
-        &self.meshes[handle.0]
+        debug_assert!(self.try_get(handle).is_some(), "mesh {} was removed", handle.0);
+        self.meshes[handle.0].as_ref().expect("mesh handle refers to a removed mesh")
+    }
+
+    pub fn try_get(&self, handle: MeshHandle) -> Option<&Mesh> {
+        self.meshes.get(handle.0).and_then(Option::as_ref)
     }
 }
 
@@ -131,25 +371,74 @@ pub struct Renderer {
     shadow_shader: ShaderProgram,
     /// One shadow map per cascade.
     shadow_maps: Vec<ShadowMap>,
+    /// Number of live cascades, in [MIN_CASCADES, MAX_CASCADES].
+    num_cascades: usize,
+    /// Default camera distance covered by the cascades, for lights that don't set
+    /// `DirectionalLight::shadow_distance`.
+    shadow_distance: f32,
+    /// Uniform/logarithmic split blend factor.
+    split_lambda: f32,
     /// Cached resolution to detect changes.
     shadow_resolution: u32,
     viewport_size: (i32, i32),
+    /// Per-tile point-light lists, used when more lights are visible than the uniform arrays hold.
+    tiled_lights: TiledLights,
+    /// Pick the primary/fill directional lights in `Entity` order rather than query order.
+    deterministic: bool,
+    /// World up axis, kept as the shadow cameras' up unless the light runs along it.
+    world_up: WorldUp,
+    /// Per-frame camera, fog and light data (`Frame` in shaders/frame.glsl), shared by the
+    /// cel and shadow programs.
+    frame_ubo: UniformBuffer,
+    /// Scene resolution as a fraction of the window, in [MIN_RENDER_SCALE, MAX_RENDER_SCALE].
+    render_scale: f32,
+    /// Offscreen scene target; only allocated while `render_scale` is below 1, eye
+    /// adaptation is on, or a post effect is.
+    scene_target: Option<SceneTarget>,
+    /// Overall brightness multiplier, in [MIN_EXPOSURE, MAX_EXPOSURE].
+    exposure: f32,
+    /// Eye adaptation settings; `None` leaves exposure fully manual.
+    auto_exposure: Option<AutoExposure>,
+    /// Exposure factor eye adaptation has reached (1 with it off), applied on top of `exposure`.
+    adapted_exposure: f32,
+    /// Average linear luminance of the last frame before exposure, if measured.
+    scene_luminance: Option<f32>,
+    post: PostProcess,
+    /// Last frame's camera view-projection and each drawn entity's model matrix, for the
+    /// velocity buffer.
+    prev_view_proj: Mat4,
+    prev_models: HashMap<Entity, Mat4>,
+    /// Reflection texture for the scene's `PlanarMirror`, while it has one.
+    mirror_target: Option<MirrorTarget>,
+    decals: DecalRenderer,
+    xray: XRayRenderer,
+    gizmos: GizmoRenderer,
 }
 
 impl Renderer {
-    pub fn init() -> Self {
+    pub fn init(shadow_settings: ShadowSettings) -> Self {
         unsafe {
             gl::Enable(gl::DEPTH_TEST);
-            gl::ClearColor(FOG_COLOR.x, FOG_COLOR.y, FOG_COLOR.z, 1.0);
+            // Shaders output linear colour; the sRGB framebuffer encodes on write (and
+            // blends in linear space). The clear colour goes through the same encoding.
+            gl::Enable(gl::FRAMEBUFFER_SRGB);
         }
 
         let shader =
-            ShaderProgram::from_sources(VERT_SRC, FRAG_SRC).expect("Failed to compile cel shaders");
-        let shadow_shader = ShaderProgram::from_sources(SHADOW_VERT_SRC, SHADOW_FRAG_SRC)
-            .expect("Failed to compile shadow shaders");
+            ShaderProgram::from_sources(&with_frame_block(VERT_SRC), &with_frame_block(FRAG_SRC))
+                .expect("Failed to compile cel shaders");
+        let shadow_shader =
+            ShaderProgram::from_sources(&with_frame_block(SHADOW_VERT_SRC), SHADOW_FRAG_SRC)
+                .expect("Failed to compile shadow shaders");
+        let frame_size = shader
+            .bind_uniform_block("Frame", FRAME_BINDING)
+            .expect("cel shaders have no Frame block");
+        shadow_shader.bind_uniform_block("Frame", FRAME_BINDING);
+        let frame_ubo = UniformBuffer::new(frame_size, FRAME_BINDING);
 
+        let num_cascades = shadow_settings.cascades.clamp(MIN_CASCADES, MAX_CASCADES);
         let shadow_resolution = 2048;
-        let shadow_maps = (0..NUM_CASCADES).map(|_| ShadowMap::new(shadow_resolution)).collect();
+        let shadow_maps = (0..num_cascades).map(|_| ShadowMap::new(shadow_resolution)).collect();
 
         let mut viewport = [0i32; 4];
         unsafe {
@@ -160,9 +449,313 @@ impl Renderer {
             shader,
             shadow_shader,
             shadow_maps,
+            num_cascades,
+            shadow_distance: shadow_settings.distance,
+            split_lambda: shadow_settings.split_lambda,
             shadow_resolution,
             viewport_size: (viewport[2], viewport[3]),
+            tiled_lights: TiledLights::new(TILED_TEXTURE_UNIT),
+            deterministic: false,
+            world_up: WorldUp::Y,
+            frame_ubo,
+            render_scale: MAX_RENDER_SCALE,
+            scene_target: None,
+            exposure: DEFAULT_EXPOSURE,
+            auto_exposure: None,
+            adapted_exposure: 1.0,
+            scene_luminance: None,
+            post: PostProcess::new(),
+            prev_view_proj: Mat4::IDENTITY,
+            mirror_target: None,
+            prev_models: HashMap::new(),
+            decals: DecalRenderer::new(DECAL_TEXTURE_UNIT),
+            xray: XRayRenderer::new(),
+            gizmos: GizmoRenderer::new(),
+        }
+    }
+
+    /// Scale the linear scene colour (lights, fog and sky alike) by `exposure` before it is
+    /// encoded for output, so dark or bright scenes can be evened out without retuning lights.
+    /// Any future tonemapping pass should take this as its input scale instead.
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure.clamp(MIN_EXPOSURE, MAX_EXPOSURE);
+    }
+
+    /// Turn eye adaptation on (or off with `None`). It measures the offscreen scene target,
+    /// so that target is kept even at full render scale while adaptation is on.
+    pub fn set_auto_exposure(&mut self, settings: Option<AutoExposure>) {
+        self.auto_exposure = settings.map(|mut s| {
+            s.min = s.min.clamp(MIN_EXPOSURE, MAX_EXPOSURE);
+            s.max = s.max.clamp(s.min, MAX_EXPOSURE);
+            s
+        });
+        self.adapted_exposure = 1.0;
+        self.scene_luminance = None;
+    }
+
+    /// Move the adapted exposure `dt` seconds further toward the level the last measured
+    /// frame asks for. Call once per frame before `draw_scene`.
+    pub fn adapt_exposure(&mut self, dt: f32) {
+        let (Some(settings), Some(luminance)) = (self.auto_exposure, self.scene_luminance) else {
+            return;
+        };
+        let desired = (settings.target / luminance.max(1e-4)).clamp(settings.min, settings.max);
+        let blend = 1.0 - (-settings.speed * dt).exp();
+        let current = self.adapted_exposure.ln();
+        self.adapted_exposure = (current + (desired.ln() - current) * blend).exp();
+    }
+
+    /// Blur along each pixel's screen motion (objects and camera), smearing over `strength`
+    /// (0–1) of a frame's movement; 0 turns it off.
+    pub fn set_motion_blur(&mut self, strength: f32) {
+        self.post.set_motion_blur(strength);
+    }
+
+    /// Blur the scene away from a focus plane; `None` turns it off.
+    pub fn set_depth_of_field(&mut self, settings: Option<DepthOfField>) {
+        self.post.set_depth_of_field(settings);
+    }
+
+    /// Darken the screen edges in the scene composite (the UI is drawn after, unaffected);
+    /// `None` turns it off.
+    pub fn set_vignette(&mut self, settings: Option<Vignette>) {
+        self.post.set_vignette(settings);
+    }
+
+    /// Grade the final image (lift/gamma/gain and saturation, after exposure and the sRGB
+    /// encode). `ColorGrading::default()` leaves the output unchanged.
+    pub fn set_color_grading(&mut self, grading: ColorGrading) {
+        self.post.set_color_grading(grading);
+    }
+
+    /// Move the depth-of-field focus plane (view depth, world units), e.g. to follow a target.
+    pub fn set_focus_distance(&mut self, distance: f32) {
+        self.post.set_focus_distance(distance);
+    }
+
+    /// Exposure the scene is actually drawn with: manual × adapted.
+    fn output_exposure(&self) -> f32 {
+        self.exposure * self.adapted_exposure
+    }
+
+    /// Average the scene target down to one texel through its mipmap chain and record the
+    /// frame's luminance with the exposure divided back out. The 1×1 readback stalls until
+    /// the GPU has finished the frame; fine at this scene scale.
+    fn measure_luminance(&mut self) {
+        let Some(target) = &self.scene_target else {
+            return;
+        };
+        let top_level = (target.size.0.max(target.size.1) as f32).log2().floor() as i32;
+        let mut texel = [0u8; 4];
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, target.color);
+            // sRGB texels are decoded before filtering, so the average is taken in linear.
+            gl::GenerateMipmap(gl::TEXTURE_2D);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::GetTexImage(
+                gl::TEXTURE_2D,
+                top_level,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                texel.as_mut_ptr() as *mut _,
+            );
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 4);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        let color = srgb_to_linear(Vec3::new(
+            texel[0] as f32 / 255.0,
+            texel[1] as f32 / 255.0,
+            texel[2] as f32 / 255.0,
+        ));
+        let luminance = color.dot(Vec3::new(0.2126, 0.7152, 0.0722));
+        self.scene_luminance = Some(luminance / self.output_exposure());
+    }
+
+    /// Draw the 3D scene at `scale` × the window resolution and upscale it to the window
+    /// afterwards. Whatever is drawn after `draw_scene` (the UI) stays at native resolution.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.render_scale = scale.clamp(MIN_RENDER_SCALE, MAX_RENDER_SCALE);
+    }
+
+    /// Framebuffer the scene pass draws into: the offscreen target, or the window's.
+    fn scene_fbo(&self) -> GLuint {
+        self.scene_target.as_ref().map_or(0, |t| t.fbo)
+    }
+
+    /// Make sure the offscreen target matches `render_scale` × `window_size`, dropping it at
+    /// full scale unless eye adaptation or post-processing needs it. Returns the size the
+    /// scene renders at.
+    fn prepare_scene_target(&mut self, window_size: (i32, i32)) -> (i32, i32) {
+        let needs_target = self.auto_exposure.is_some() || self.post.enabled();
+        if self.render_scale >= MAX_RENDER_SCALE && !needs_target {
+            self.scene_target = None;
+            return window_size;
         }
+        let scaled = |n: i32| ((n as f32 * self.render_scale).round() as i32).max(1);
+        let size = (scaled(window_size.0), scaled(window_size.1));
+        if self.scene_target.as_ref().map(|t| t.size) != Some(size) {
+            self.scene_target = Some(SceneTarget::new(size));
+        }
+        size
+    }
+
+    /// Upscale the offscreen scene into the window framebuffer (no-op without one), through
+    /// the post-process composite when an effect is on, and leave the window framebuffer and
+    /// viewport bound for the UI. `proj` and `view` are this frame's camera.
+    fn present_scene_target(&mut self, window_size: (i32, i32), view: &Mat4, proj: &Mat4) {
+        let Some(target) = &self.scene_target else {
+            return;
+        };
+        if self.post.enabled() {
+            let inputs = PostInputs {
+                color: target.color,
+                velocity: target.velocity,
+                material: target.material,
+                depth: target.depth,
+            };
+            self.post.set_fog_color(srgb_to_linear(FOG_COLOR) * self.output_exposure());
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+                gl::Viewport(0, 0, window_size.0, window_size.1);
+            }
+            self.post.draw(&inputs, proj, &(*proj * *view), &self.prev_view_proj);
+            return;
+        }
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, target.fbo);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                target.size.0,
+                target.size.1,
+                0,
+                0,
+                window_size.0,
+                window_size.1,
+                gl::COLOR_BUFFER_BIT,
+                gl::LINEAR,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, window_size.0, window_size.1);
+        }
+    }
+
+    /// Debug line queue, drawn over the next `draw_scene`.
+    pub fn gizmos(&mut self) -> &mut GizmoRenderer {
+        &mut self.gizmos
+    }
+
+    pub fn set_deterministic(&mut self, on: bool) {
+        self.deterministic = on;
+    }
+
+    pub fn set_world_up(&mut self, up: WorldUp) {
+        self.world_up = up;
+    }
+
+    /// Draw every visible mesh entity with the cel shader, which must be bound with its
+    /// textures set. `skip` is left out; the `mirror` entity blends in the reflection texture.
+    /// Returns each drawn entity's model matrix, the history for next frame's velocities.
+    fn draw_entities(
+        &mut self,
+        world: &World,
+        meshes: &MeshStore,
+        skip: Option<Entity>,
+        mirror: Option<&MirrorPlane>,
+    ) -> HashMap<Entity, Mat4> {
+        let mut models = HashMap::with_capacity(self.prev_models.len());
+        for (entity, (gt, mesh_handle, color, checker, hidden, no_receive, reflective)) in world
+            .query::<(
+                &GlobalTransform,
+                &MeshHandle,
+                &Color,
+                Option<&Checkerboard>,
+                Option<&Hidden>,
+                Option<&NoShadowReceive>,
+                Option<&Reflective>,
+            )>()
+            .iter()
+        {
+            if hidden.is_some() || Some(entity) == skip {
+                continue;
+            }
+            self.shader.set_int("u_receive_shadows", if no_receive.is_some() { 0 } else { 1 });
+            self.shader.set_mat4("u_model", &gt.0);
+            // First frame drawn: no history, so no motion.
+            let prev_model = self.prev_models.get(&entity).unwrap_or(&gt.0);
+            self.shader.set_mat4("u_prev_model", prev_model);
+            models.insert(entity, gt.0);
+            self.shader.set_vec3("u_object_color", srgb_to_linear(color.0));
+            let reflectivity = reflective.map_or(0.0, |r| r.strength.clamp(0.0, 1.0));
+            self.shader.set_float("u_reflectivity", reflectivity);
+            let mirror_strength =
+                mirror.filter(|m| m.entity == entity).map_or(0.0, |m| m.strength);
+            self.shader.set_float("u_mirror_strength", mirror_strength);
+            if let Some(checker) = checker {
+                self.shader.set_int("u_checkerboard", 1);
+                self.shader.set_vec3("u_object_color_2", srgb_to_linear(checker.0));
+            } else {
+                self.shader.set_int("u_checkerboard", 0);
+            }
+            meshes.get(*mesh_handle).draw();
+        }
+        models
+    }
+
+    /// Render the scene reflected in `plane` into the mirror target, at `MIRROR_SCALE` of the
+    /// scene size. Everything else is this frame's: shadow maps and lights are reused as they
+    /// are, and only the camera at the start of the Frame block is swapped for its mirror
+    /// image (and put back after). Tiled light lists stay binned for the main view, so past
+    /// `MAX_POINT_LIGHTS` the reflection's point lighting is approximate. Leaves the scene
+    /// framebuffer and viewport bound.
+    fn draw_mirror(
+        &mut self,
+        world: &World,
+        meshes: &MeshStore,
+        plane: &MirrorPlane,
+        view: &Mat4,
+        proj: &Mat4,
+        camera_pos: Vec3,
+    ) {
+        let scaled = |n: i32| ((n as f32 * MIRROR_SCALE).round() as i32).max(1);
+        let size = (scaled(self.viewport_size.0), scaled(self.viewport_size.1));
+        if self.mirror_target.as_ref().map(|t| t.size) != Some(size) {
+            self.mirror_target = Some(MirrorTarget::new(size));
+        }
+        let fbo = self.mirror_target.as_ref().map_or(0, |t| t.fbo);
+
+        self.write_frame_camera(&plane.reflect_view(view), proj, plane.reflect_point(camera_pos));
+        let clear = srgb_to_linear(FOG_COLOR) * self.output_exposure();
+        unsafe {
+            // Last frame left the target bound for sampling; unbind it before drawing into it.
+            gl::ActiveTexture(gl::TEXTURE0 + MIRROR_TEXTURE_UNIT);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::Viewport(0, 0, size.0, size.1);
+            gl::ClearColor(clear.x, clear.y, clear.z, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            gl::Enable(gl::CLIP_DISTANCE0);
+        }
+        // Cut away everything below the mirror, which would otherwise poke up through it.
+        self.shader.set_vec4("u_clip_plane", plane.clip_plane());
+        self.draw_entities(world, meshes, Some(plane.entity), None);
+        unsafe {
+            gl::Disable(gl::CLIP_DISTANCE0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_fbo());
+            gl::Viewport(0, 0, self.viewport_size.0, self.viewport_size.1);
+        }
+        self.write_frame_camera(view, proj, camera_pos);
+    }
+
+    /// Overwrite only the camera members at the start of the Frame block.
+    fn write_frame_camera(&self, view: &Mat4, proj: &Mat4, camera_pos: Vec3) {
+        let mut camera = Std140::new();
+        camera.mat4(*view);
+        camera.mat4(*proj);
+        camera.vec3(camera_pos);
+        self.frame_ubo.update_range(0, &camera.finish());
     }
 
     /// Compute a tight light-space VP matrix for cascade slice [near_dist, far_dist].
@@ -171,6 +764,7 @@ impl Renderer {
     /// bounding sphere, and builds an orthographic shadow frustum around it. The sphere-based
     /// approach is rotation-invariant, preventing shadow shimmer when the camera rotates.
     fn cascade_matrix(
+        &self,
         light_dir: Vec3,
         view: &Mat4,
         proj: &Mat4,
@@ -207,7 +801,12 @@ impl Renderer {
 
         // Position the shadow camera behind the scene along the light direction.
         let ld = light_dir.normalize();
-        let up = if ld.y.abs() < 0.99 { Vec3::Y } else { Vec3::X };
+        let world_up = self.world_up.vector();
+        let up = if ld.dot(world_up).abs() < 0.99 {
+            world_up
+        } else {
+            self.world_up.align() * Vec3::X
+        };
         let eye = centroid - ld * SHADOW_CASTER_REACH;
         let light_view = Mat4::look_at_rh(eye, centroid, up);
 
@@ -274,49 +873,208 @@ impl Renderer {
         proj: &Mat4,
         camera_pos: Vec3,
     ) {
-        // Update cached viewport size.
+        // The current viewport is the window; below full render scale the scene goes to the
+        // smaller offscreen target instead and is upscaled once it's done.
         let mut viewport = [0i32; 4];
         unsafe {
             gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
         }
-        self.viewport_size = (viewport[2], viewport[3]);
+        let window_size = (viewport[2], viewport[3]);
+        self.post.set_reflections(world.query::<&Reflective>().iter().next().is_some());
+        self.viewport_size = self.prepare_scene_target(window_size);
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_fbo());
+            gl::Viewport(0, 0, self.viewport_size.0, self.viewport_size.1);
+        }
 
-        // --- Find directional light ---
+        // --- Find directional lights ---
+        // The primary slot goes to the first shadow caster (or, failing that, the first
+        // light); every other directional light is uploaded as an unshadowed fill light.
         let mut dir_light_dir = Vec3::new(-0.5, -1.0, -0.3);
         let mut dir_light_color = Vec3::ONE;
         let mut dir_light_intensity: f32 = 1.0;
         let mut shadows_enabled = false;
         let mut shadow_resolution = self.shadow_resolution;
+        let mut shadow_distance = self.shadow_distance;
 
-        for (_e, (dl,)) in world.query::<(&DirectionalLight,)>().iter() {
+        let mut dir_query = world.query::<(&DirectionalLight,)>();
+        let mut dir_lights: Vec<(Entity, &DirectionalLight)> =
+            dir_query.iter().map(|(e, (dl,))| (e, dl)).collect();
+        if self.deterministic {
+            dir_lights.sort_by_key(|&(e, _)| e);
+        }
+        let primary = dir_lights
+            .iter()
+            .find(|(_, dl)| dl.casts_shadows)
+            .or_else(|| dir_lights.first())
+            .map(|&(e, _)| e);
+        if let Some(&(_, dl)) = dir_lights.iter().find(|(e, _)| Some(*e) == primary) {
             dir_light_dir = dl.direction;
             dir_light_color = dl.color;
             dir_light_intensity = dl.intensity;
             shadow_resolution = dl.shadow_resolution;
-            shadows_enabled = true;
-            break; // first directional light only
+            shadows_enabled = dl.casts_shadows;
+            shadow_distance = dl.shadow_distance.unwrap_or(shadow_distance);
         }
 
         // Recreate shadow maps if resolution changed.
         if shadow_resolution != self.shadow_resolution {
             self.shadow_maps =
-                (0..NUM_CASCADES).map(|_| ShadowMap::new(shadow_resolution)).collect();
+                (0..self.num_cascades).map(|_| ShadowMap::new(shadow_resolution)).collect();
             self.shadow_resolution = shadow_resolution;
         }
 
         // Compute per-cascade light-space VP matrices.
-        let mut cascade_matrices = [Mat4::IDENTITY; NUM_CASCADES];
+        // Camera clip planes from the GL perspective matrix: near = P32 / (P22 - 1),
+        // far = P32 / (P22 + 1). The splits run from the camera near plane out to the
+        // light's shadow distance, independent of the camera far plane except that shadows
+        // never reach past it. Each cascade slice is unprojected from these split distances,
+        // so nothing beyond `shadow_far` is fitted into a shadow map.
+        let camera_near = proj.col(3).z / (proj.col(2).z - 1.0);
+        let camera_far = proj.col(3).z / (proj.col(2).z + 1.0);
+        let shadow_far = shadow_distance.min(camera_far);
+        let splits =
+            cascade_splits(camera_near, shadow_far, self.num_cascades, self.split_lambda);
+        let mut cascade_matrices = [Mat4::IDENTITY; MAX_CASCADES];
         if shadows_enabled {
-            for i in 0..NUM_CASCADES {
-                cascade_matrices[i] = Self::cascade_matrix(
-                    dir_light_dir,
-                    view,
-                    proj,
-                    CASCADE_SPLITS[i],
-                    CASCADE_SPLITS[i + 1],
-                );
+            for i in 0..self.num_cascades {
+                cascade_matrices[i] =
+                    self.cascade_matrix(dir_light_dir, view, proj, splits[i], splits[i + 1]);
+            }
+        }
+
+        // --- Fill (non-shadowed) directional lights ---
+        let fill_lights: Vec<&DirectionalLight> = dir_lights
+            .iter()
+            .filter(|(e, _)| Some(*e) != primary)
+            .map(|&(_, dl)| dl)
+            .take(MAX_FILL_LIGHTS)
+            .collect();
+
+        // Lights whose radius sphere misses the view frustum can't touch anything on screen,
+        // so they're skipped before they use up one of the limited slots. The test is on the
+        // light's sphere, not its position, so a light just behind the camera still counts.
+        let view_planes = Self::frustum_planes(&(*proj * *view));
+
+        // --- Point lights ---
+        // Up to MAX_POINT_LIGHTS visible lights go through the plain uniform arrays. Past
+        // that they're binned into screen tiles instead, so a busy scene no longer drops
+        // lights at the cap and each fragment only evaluates the ones covering its tile.
+        let mut point_lights: Vec<PackedPointLight> = Vec::new();
+        for (_e, (lt, pl)) in world.query::<(&LocalTransform, &PointLight)>().iter() {
+            let light = PackedPointLight {
+                position: lt.position,
+                radius: pl.radius,
+                color: srgb_to_linear(pl.color),
+                intensity: pl.intensity,
+                attenuation: pl.attenuation as i32,
+                constant: pl.constant,
+                linear: pl.linear,
+                quadratic: pl.quadratic,
+            };
+            // Culled by the same reach the tiled path bins with, so both paths light a
+            // scene alike whichever one the light count picks.
+            if !Self::sphere_outside_frustum(light.position, light.reach(), &view_planes) {
+                point_lights.push(light);
             }
         }
+        let tiled = point_lights.len() > MAX_POINT_LIGHTS;
+        if tiled {
+            self.tiled_lights.upload(&point_lights, &(*proj * *view), self.viewport_size);
+        }
+        let simple_lights: &[PackedPointLight] = if tiled { &[] } else { &point_lights };
+
+        // --- Spot lights ---
+        let mut spot_query = world.query::<(&LocalTransform, &SpotLight)>();
+        let spot_lights: Vec<(Vec3, &SpotLight)> = spot_query
+            .iter()
+            .map(|(_e, (lt, sl))| (lt.position, sl))
+            .filter(|&(pos, sl)| !Self::sphere_outside_frustum(pos, sl.radius, &view_planes))
+            .take(MAX_SPOT_LIGHTS)
+            .collect();
+
+        // --- Contact AO spheres (dynamic bodies, nearest first) ---
+        let mut ao_spheres: Vec<(Vec3, f32)> = world
+            .query::<(&GlobalTransform, &Collider, &Velocity)>()
+            .without::<&Static>()
+            .iter()
+            .filter_map(|(_e, (gt, collider, _vel))| {
+                let radius = match collider {
+                    Collider::Sphere { radius } | Collider::Capsule { radius, .. } => *radius,
+                    Collider::Box { half_extents } => half_extents.x.max(half_extents.z),
+                    Collider::Plane { .. } => return None,
+                };
+                Some((gt.0.col(3).truncate(), radius))
+            })
+            .collect();
+        ao_spheres.sort_by(|a, b| {
+            let da = a.0.distance_squared(camera_pos);
+            let db = b.0.distance_squared(camera_pos);
+            da.total_cmp(&db)
+        });
+        ao_spheres.truncate(MAX_AO_SPHERES);
+
+        // --- Upload the Frame block, shared by both passes ---
+        // Member order must match shaders/frame.glsl exactly.
+        let mut frame = Std140::new();
+        frame.mat4(*view);
+        frame.mat4(*proj);
+        frame.vec3(camera_pos);
+        frame.vec3(Vec3::new(0.15, 0.15, 0.15));
+        frame.vec3(srgb_to_linear(FOG_COLOR));
+        frame.float(50.0);
+        frame.float(300.0);
+        frame.float(self.output_exposure());
+
+        frame.vec3(dir_light_dir);
+        frame.float(dir_light_intensity);
+        frame.vec3(srgb_to_linear(dir_light_color));
+        frame.int(if shadows_enabled { 1 } else { 0 });
+        frame.int(self.num_cascades as i32);
+        frame.array(cascade_matrices, MAX_CASCADES, Std140::mat4);
+        // Camera depth at the boundary after each cascade.
+        frame.array(splits[1..self.num_cascades].iter().copied(), MAX_CASCADES - 1, Std140::float);
+
+        frame.int(fill_lights.len() as i32);
+        frame.array(fill_lights.iter().map(|dl| dl.direction), MAX_FILL_LIGHTS, Std140::vec3);
+        let fill_colors = fill_lights.iter().map(|dl| srgb_to_linear(dl.color));
+        frame.array(fill_colors, MAX_FILL_LIGHTS, Std140::vec3);
+        frame.array(fill_lights.iter().map(|dl| dl.intensity), MAX_FILL_LIGHTS, Std140::float);
+
+        frame.int(simple_lights.len() as i32);
+        let point = |f: fn(&PackedPointLight) -> f32| simple_lights.iter().map(f);
+        frame.array(simple_lights.iter().map(|l| l.position), MAX_POINT_LIGHTS, Std140::vec3);
+        frame.array(simple_lights.iter().map(|l| l.color), MAX_POINT_LIGHTS, Std140::vec3);
+        frame.array(point(|l| l.intensity), MAX_POINT_LIGHTS, Std140::float);
+        frame.array(point(|l| l.constant), MAX_POINT_LIGHTS, Std140::float);
+        frame.array(point(|l| l.linear), MAX_POINT_LIGHTS, Std140::float);
+        frame.array(point(|l| l.quadratic), MAX_POINT_LIGHTS, Std140::float);
+        frame.array(point(|l| l.radius), MAX_POINT_LIGHTS, Std140::float);
+        frame.array(simple_lights.iter().map(|l| l.attenuation), MAX_POINT_LIGHTS, Std140::int);
+        frame.int(if tiled { 1 } else { 0 });
+        frame.int(TILE_SIZE);
+
+        frame.int(spot_lights.len() as i32);
+        let spot = |f: fn(&SpotLight) -> f32| spot_lights.iter().map(move |&(_, sl)| f(sl));
+        frame.array(spot_lights.iter().map(|&(pos, _)| pos), MAX_SPOT_LIGHTS, Std140::vec3);
+        frame.array(spot_lights.iter().map(|(_, sl)| sl.direction), MAX_SPOT_LIGHTS, Std140::vec3);
+        let spot_colors = spot_lights.iter().map(|(_, sl)| srgb_to_linear(sl.color));
+        frame.array(spot_colors, MAX_SPOT_LIGHTS, Std140::vec3);
+        frame.array(spot(|sl| sl.intensity), MAX_SPOT_LIGHTS, Std140::float);
+        frame.array(spot(|sl| sl.inner_cone), MAX_SPOT_LIGHTS, Std140::float);
+        frame.array(spot(|sl| sl.outer_cone), MAX_SPOT_LIGHTS, Std140::float);
+        frame.array(spot(|sl| sl.constant), MAX_SPOT_LIGHTS, Std140::float);
+        frame.array(spot(|sl| sl.linear), MAX_SPOT_LIGHTS, Std140::float);
+        frame.array(spot(|sl| sl.quadratic), MAX_SPOT_LIGHTS, Std140::float);
+
+        frame.int(ao_spheres.len() as i32);
+        frame.float(AO_STRENGTH);
+        let ao = ao_spheres.iter().map(|&(center, radius)| center.extend(radius));
+        frame.array(ao, MAX_AO_SPHERES, Std140::vec4);
+
+        frame.mat4(self.prev_view_proj);
+
+        self.frame_ubo.update(&frame.finish());
 
         // ============ PASS 1: Shadow maps (one per cascade) ============
         if shadows_enabled {
@@ -328,18 +1086,20 @@ impl Renderer {
 
             self.shadow_shader.bind();
 
-            for c in 0..NUM_CASCADES {
+            for c in 0..self.num_cascades {
                 unsafe {
                     gl::BindFramebuffer(gl::FRAMEBUFFER, self.shadow_maps[c].fbo);
                     gl::Clear(gl::DEPTH_BUFFER_BIT);
                 }
 
-                self.shadow_shader.set_mat4("u_light_space", &cascade_matrices[c]);
+                self.shadow_shader.set_int("u_cascade", c as i32);
 
                 let planes = Self::frustum_planes(&cascade_matrices[c]);
 
-                for (_entity, (gt, mesh_handle, hidden)) in
-                    world.query::<(&GlobalTransform, &MeshHandle, Option<&Hidden>)>().iter()
+                for (_entity, (gt, mesh_handle, hidden)) in world
+                    .query::<(&GlobalTransform, &MeshHandle, Option<&Hidden>)>()
+                    .without::<&NoShadowCast>()
+                    .iter()
                 {
                     if hidden.is_some() {
                         continue;
@@ -358,131 +1118,110 @@ impl Renderer {
 
             unsafe {
                 gl::Disable(gl::CULL_FACE);
-                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_fbo());
                 gl::Viewport(0, 0, self.viewport_size.0, self.viewport_size.1);
             }
         }
 
-        // ============ PASS 2: Scene rendering ============
-        unsafe {
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        self.shader.bind();
+
+        // Bind cascade shadow maps to texture units 0..MAX_CASCADES. Unused sampler slots
+        // get the last live map so every declared sampler points at a valid depth texture.
+        for i in 0..MAX_CASCADES {
+            let map = &self.shadow_maps[i.min(self.num_cascades - 1)];
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0 + i as u32);
+                gl::BindTexture(gl::TEXTURE_2D, map.texture);
+            }
+            self.shader.set_int(&format!("u_shadow_map_{}", i), i as i32);
         }
 
-        self.shader.bind();
-        self.shader.set_mat4("u_view", view);
-        self.shader.set_mat4("u_projection", proj);
-        self.shader.set_vec3("u_camera_pos", camera_pos);
-        self.shader.set_vec3("u_ambient_color", Vec3::new(0.15, 0.15, 0.15));
-        self.shader.set_vec3("u_fog_color", FOG_COLOR);
-        self.shader.set_float("u_fog_start", 50.0);
-        self.shader.set_float("u_fog_end", 300.0);
-
-        // Directional light uniforms
-        self.shader.set_vec3("u_dir_light_dir", dir_light_dir);
-        self.shader.set_vec3("u_dir_light_color", dir_light_color);
-        self.shader.set_float("u_dir_light_intensity", dir_light_intensity);
-        self.shader.set_int("u_shadows_enabled", if shadows_enabled { 1 } else { 0 });
-
-        // Upload cascade light-space matrices
-        for i in 0..NUM_CASCADES {
-            self.shader
-                .set_mat4(&format!("u_cascade_light_space[{}]", i), &cascade_matrices[i]);
-        }
-
-        // Bind cascade shadow maps to texture units 0–2
+        self.tiled_lights.bind();
+        self.shader.set_int("u_tile_light_data", TILED_TEXTURE_UNIT as i32);
+        self.shader.set_int("u_tile_grid", TILED_TEXTURE_UNIT as i32 + 1);
+        self.shader.set_int("u_tile_indices", TILED_TEXTURE_UNIT as i32 + 2);
+
+        // ============ PASS 2: Planar mirror reflection ============
+        let mirror = MirrorPlane::find(world);
+        match &mirror {
+            Some(plane) => self.draw_mirror(world, meshes, plane, view, proj, camera_pos),
+            None => self.mirror_target = None,
+        }
+
+        // ============ PASS 3: Scene rendering ============
+        // Offscreen, the cel pass also fills the velocity attachment (cleared to "no motion")
+        // and the material attachment (cleared to "not reflective").
+        let offscreen = self.scene_target.is_some();
+        let clear = srgb_to_linear(FOG_COLOR) * self.output_exposure();
         unsafe {
-            gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D, self.shadow_maps[0].texture);
-            gl::ActiveTexture(gl::TEXTURE1);
-            gl::BindTexture(gl::TEXTURE_2D, self.shadow_maps[1].texture);
-            gl::ActiveTexture(gl::TEXTURE2);
-            gl::BindTexture(gl::TEXTURE_2D, self.shadow_maps[2].texture);
-        }
-        self.shader.set_int("u_shadow_map_0", 0);
-        self.shader.set_int("u_shadow_map_1", 1);
-        self.shader.set_int("u_shadow_map_2", 2);
-
-        // Cascade split thresholds (camera depth at cascade boundaries)
-        self.shader.set_float("u_cascade_splits[0]", CASCADE_SPLITS[1]);
-        self.shader.set_float("u_cascade_splits[1]", CASCADE_SPLITS[2]);
-
-        // --- Upload point lights ---
-        let mut point_count = 0usize;
-        for (_e, (lt, pl)) in world.query::<(&LocalTransform, &PointLight)>().iter() {
-            if point_count >= MAX_POINT_LIGHTS {
-                break;
+            if offscreen {
+                let buffers = [gl::COLOR_ATTACHMENT0, gl::COLOR_ATTACHMENT1, gl::COLOR_ATTACHMENT2];
+                gl::DrawBuffers(3, buffers.as_ptr());
             }
-            self.shader.set_vec3(&format!("u_point_light_pos[{}]", point_count), lt.position);
-            self.shader
-                .set_vec3(&format!("u_point_light_color[{}]", point_count), pl.color);
-            self.shader
-                .set_float(&format!("u_point_light_intensity[{}]", point_count), pl.intensity);
-            self.shader
-                .set_float(&format!("u_point_light_constant[{}]", point_count), pl.constant);
-            self.shader
-                .set_float(&format!("u_point_light_linear[{}]", point_count), pl.linear);
-            self.shader
-                .set_float(&format!("u_point_light_quadratic[{}]", point_count), pl.quadratic);
-            point_count += 1;
-        }
-        self.shader.set_int("u_num_point_lights", point_count as i32);
-
-        // --- Upload spot lights ---
-        let mut spot_count = 0usize;
-        for (_e, (lt, sl)) in world.query::<(&LocalTransform, &SpotLight)>().iter() {
-            if spot_count >= MAX_SPOT_LIGHTS {
-                break;
+            gl::ClearColor(clear.x, clear.y, clear.z, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            if offscreen {
+                gl::ClearBufferfv(gl::COLOR, 1, [0.0f32; 4].as_ptr());
+                gl::ClearBufferfv(gl::COLOR, 2, [0.0f32; 4].as_ptr());
             }
-            self.shader.set_vec3(&format!("u_spot_light_pos[{}]", spot_count), lt.position);
-            self.shader
-                .set_vec3(&format!("u_spot_light_dir[{}]", spot_count), sl.direction);
-            self.shader
-                .set_vec3(&format!("u_spot_light_color[{}]", spot_count), sl.color);
-            self.shader
-                .set_float(&format!("u_spot_light_intensity[{}]", spot_count), sl.intensity);
-            self.shader.set_float(
-                &format!("u_spot_light_inner_cone[{}]", spot_count),
-                sl.inner_cone,
-            );
-            self.shader.set_float(
-                &format!("u_spot_light_outer_cone[{}]", spot_count),
-                sl.outer_cone,
-            );
-            self.shader
-                .set_float(&format!("u_spot_light_constant[{}]", spot_count), sl.constant);
-            self.shader
-                .set_float(&format!("u_spot_light_linear[{}]", spot_count), sl.linear);
-            self.shader.set_float(
-                &format!("u_spot_light_quadratic[{}]", spot_count),
-                sl.quadratic,
-            );
-            spot_count += 1;
         }
-        self.shader.set_int("u_num_spot_lights", spot_count as i32);
 
-        // --- Draw entities ---
-        for (_entity, (gt, mesh_handle, color, checker, hidden)) in world
-            .query::<(
-                &GlobalTransform,
-                &MeshHandle,
-                &Color,
-                Option<&Checkerboard>,
-                Option<&Hidden>,
-            )>()
-            .iter()
-        {
-            if hidden.is_some() {
-                continue;
+        if let Some(target) = &self.mirror_target {
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0 + MIRROR_TEXTURE_UNIT);
+                gl::BindTexture(gl::TEXTURE_2D, target.color);
             }
-            self.shader.set_mat4("u_model", &gt.0);
-            self.shader.set_vec3("u_object_color", color.0);
-            if let Some(checker) = checker {
-                self.shader.set_int("u_checkerboard", 1);
-                self.shader.set_vec3("u_object_color_2", checker.0);
-            } else {
-                self.shader.set_int("u_checkerboard", 0);
+        }
+        self.shader.set_int("u_mirror_texture", MIRROR_TEXTURE_UNIT as i32);
+        self.prev_models = self.draw_entities(world, meshes, None, mirror.as_ref());
+        // Later passes only write colour.
+        if offscreen {
+            unsafe {
+                gl::DrawBuffers(1, [gl::COLOR_ATTACHMENT0].as_ptr());
             }
-            meshes.get(*mesh_handle).draw();
         }
+
+        // ============ PASS 4: Decals over the opaque scene ============
+        self.decals.draw(world);
+
+        // ============ PASS 5: X-ray outlines of occluded flagged entities ============
+        self.xray.draw(world, meshes);
+
+        // ============ PASS 6: Gizmo lines queued this frame ============
+        self.gizmos.draw();
+
+        if self.auto_exposure.is_some() {
+            self.measure_luminance();
+        }
+
+        self.present_scene_target(window_size, view, proj);
+        self.prev_view_proj = *proj * *view;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::mesh::MeshData;
+
+    /// A mesh told apart from the others by its vertex count (built without GL in tests).
+    fn mesh_with_vertices(count: usize) -> Mesh {
+        MeshData { vertices: vec![0.0; count * 6], indices: Vec::new() }.upload()
+    }
+
+    #[test]
+    fn mesh_store_reuses_removed_slot() {
+        let mut store = MeshStore::new();
+        let a = store.add(mesh_with_vertices(1));
+        let b = store.add(mesh_with_vertices(2));
+        let c = store.add(mesh_with_vertices(3));
+        store.remove(b);
+        assert!(store.try_get(b).is_none());
+
+        let d = store.add(mesh_with_vertices(4));
+        assert_eq!(d.0, 1);
+        assert_eq!(store.get(a).vertex_count(), 1);
+        assert_eq!(store.get(c).vertex_count(), 3);
+        assert_eq!(store.get(d).vertex_count(), 4);
     }
 }
@@ -0,0 +1,236 @@
+use gl::types::*;
+use glam::{Mat4, Vec3};
+
+use super::shader::ShaderProgram;
+
+const POST_VERT_SRC: &str = include_str!("../../shaders/post.vert");
+const POST_FRAG_SRC: &str = include_str!("../../shaders/post.frag");
+
+/// Default motion blur strength when it's turned on without one.
+pub const DEFAULT_MOTION_BLUR: f32 = 0.5;
+/// Default depth-of-field aperture.
+pub const DEFAULT_DOF_APERTURE: f32 = 0.5;
+/// Default vignette strength when it's turned on without one.
+pub const DEFAULT_VIGNETTE_STRENGTH: f32 = 0.35;
+/// Default distance from the centre where the vignette starts darkening.
+pub const DEFAULT_VIGNETTE_RADIUS: f32 = 0.6;
+
+/// Focus blur: points blur with their distance from a focus plane in front of the camera.
+#[derive(Clone, Copy, Debug)]
+pub struct DepthOfField {
+    /// View depth that stays sharp (world units).
+    pub focus_distance: f32,
+    /// Blur strength (0–1): the fraction of the largest blur radius reached far behind the
+    /// focus plane, and at half the focus distance in front of it.
+    pub aperture: f32,
+}
+
+/// Radial darkening towards the screen edges.
+#[derive(Clone, Copy, Debug)]
+pub struct Vignette {
+    /// How much the corners darken (0–1).
+    pub strength: f32,
+    /// Where darkening starts, as a fraction of the centre-to-corner distance (0–1).
+    pub radius: f32,
+}
+
+/// Channel-wise lift/gamma/gain plus saturation, applied to the display-encoded (sRGB)
+/// image as the very last step. The default is the identity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorGrading {
+    /// Raises the blacks, per channel (0 = unchanged).
+    pub lift: Vec3,
+    /// Midtone power, per channel; above 1 brightens (1 = unchanged).
+    pub gamma: Vec3,
+    /// Scales the whites, per channel (1 = unchanged).
+    pub gain: Vec3,
+    /// 0 = greyscale, 1 = unchanged, above 1 = more vivid.
+    pub saturation: f32,
+}
+
+impl Default for ColorGrading {
+    fn default() -> Self {
+        Self { lift: Vec3::ZERO, gamma: Vec3::ONE, gain: Vec3::ONE, saturation: 1.0 }
+    }
+}
+
+impl ColorGrading {
+    /// Warm, punchy grade: golden highlights, deeper shadows.
+    pub fn sunset() -> Self {
+        Self {
+            lift: Vec3::new(0.02, -0.01, -0.04),
+            gamma: Vec3::new(1.05, 0.95, 0.85),
+            gain: Vec3::new(1.12, 1.0, 0.85),
+            saturation: 1.2,
+        }
+    }
+
+    /// Cold, muted grade: blue shadows, washed-out colour.
+    pub fn night() -> Self {
+        Self {
+            lift: Vec3::new(-0.01, 0.0, 0.04),
+            gamma: Vec3::new(0.9, 0.95, 1.05),
+            gain: Vec3::new(0.85, 0.92, 1.05),
+            saturation: 0.55,
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Scene textures the composite reads.
+pub struct PostInputs {
+    pub color: GLuint,
+    pub velocity: GLuint,
+    pub material: GLuint,
+    pub depth: GLuint,
+}
+
+/// Fullscreen composite from the offscreen scene target into the window, applying the
+/// enabled screen-space effects on the way. Replaces the plain upscale blit whenever any
+/// effect is on; the UI is drawn afterwards, so it is never affected.
+pub struct PostProcess {
+    shader: ShaderProgram,
+    /// Empty VAO for the attribute-less fullscreen triangle (core profile requires one).
+    vao: GLuint,
+    /// Fraction of each frame's screen motion to blur along; 0 = off.
+    motion_blur: f32,
+    depth_of_field: Option<DepthOfField>,
+    vignette: Option<Vignette>,
+    grading: ColorGrading,
+    /// Screen-space reflections on surfaces the material attachment marks reflective.
+    reflections: bool,
+    /// Linear fog colour (exposure applied) that reflections fall back to off screen.
+    fog_color: Vec3,
+}
+
+impl PostProcess {
+    pub fn new() -> Self {
+        let shader = ShaderProgram::from_sources(POST_VERT_SRC, POST_FRAG_SRC)
+            .expect("Failed to compile post-process shaders");
+        let mut vao: GLuint = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+        }
+        Self {
+            shader,
+            vao,
+            motion_blur: 0.0,
+            depth_of_field: None,
+            vignette: None,
+            grading: ColorGrading::default(),
+            reflections: false,
+            fog_color: Vec3::ZERO,
+        }
+    }
+
+    pub fn set_motion_blur(&mut self, strength: f32) {
+        self.motion_blur = strength.clamp(0.0, 1.0);
+    }
+
+    pub fn set_depth_of_field(&mut self, settings: Option<DepthOfField>) {
+        self.depth_of_field = settings.map(|dof| DepthOfField {
+            focus_distance: dof.focus_distance.max(0.01),
+            aperture: dof.aperture.clamp(0.0, 1.0),
+        });
+    }
+
+    /// Move the focus plane; ignored while depth of field is off.
+    pub fn set_focus_distance(&mut self, distance: f32) {
+        if let Some(dof) = &mut self.depth_of_field {
+            dof.focus_distance = distance.max(0.01);
+        }
+    }
+
+    pub fn set_vignette(&mut self, settings: Option<Vignette>) {
+        self.vignette = settings.map(|v| Vignette {
+            strength: v.strength.clamp(0.0, 1.0),
+            radius: v.radius.clamp(0.0, 1.0),
+        });
+    }
+
+    pub fn set_color_grading(&mut self, grading: ColorGrading) {
+        self.grading = grading;
+    }
+
+    pub fn set_reflections(&mut self, on: bool) {
+        self.reflections = on;
+    }
+
+    pub fn set_fog_color(&mut self, linear: Vec3) {
+        self.fog_color = linear;
+    }
+
+    /// Whether any effect needs the composite (and with it the offscreen scene target).
+    pub fn enabled(&self) -> bool {
+        self.motion_blur > 0.0
+            || self.reflections
+            || self.depth_of_field.is_some()
+            || self.vignette.is_some()
+            || !self.grading.is_identity()
+    }
+
+    /// Composite `inputs` into the currently bound framebuffer and viewport. `proj` is this
+    /// frame's projection; `view_proj` and `prev_view_proj` are this frame's and last frame's
+    /// camera matrices.
+    pub fn draw(
+        &mut self,
+        inputs: &PostInputs,
+        proj: &Mat4,
+        view_proj: &Mat4,
+        prev_view_proj: &Mat4,
+    ) {
+        self.shader.bind();
+        let textures = [
+            ("u_scene", inputs.color),
+            ("u_velocity", inputs.velocity),
+            ("u_depth", inputs.depth),
+            ("u_material", inputs.material),
+        ];
+        for (unit, &(name, texture)) in textures.iter().enumerate() {
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+            self.shader.set_int(name, unit as i32);
+        }
+        self.shader.set_float("u_motion_blur", self.motion_blur);
+        self.shader.set_int("u_reflections", self.reflections as i32);
+        self.shader.set_mat4("u_projection", proj);
+        self.shader.set_vec3("u_fog_color", self.fog_color);
+        // Zero aperture: every circle of confusion is zero and the gather is skipped.
+        let dof = self
+            .depth_of_field
+            .unwrap_or(DepthOfField { focus_distance: 1.0, aperture: 0.0 });
+        self.shader.set_float("u_focus_distance", dof.focus_distance);
+        self.shader.set_float("u_aperture", dof.aperture);
+        self.shader.set_mat4("u_inv_projection", &proj.inverse());
+        let vignette = self.vignette.unwrap_or(Vignette { strength: 0.0, radius: 1.0 });
+        self.shader.set_float("u_vignette_strength", vignette.strength);
+        self.shader.set_float("u_vignette_radius", vignette.radius);
+        self.shader.set_vec3("u_grade_lift", self.grading.lift);
+        self.shader.set_vec3("u_grade_gamma", self.grading.gamma.max(Vec3::splat(0.01)));
+        self.shader.set_vec3("u_grade_gain", self.grading.gain);
+        self.shader.set_float("u_grade_saturation", self.grading.saturation.max(0.0));
+        self.shader.set_mat4("u_inv_view_proj", &view_proj.inverse());
+        self.shader.set_mat4("u_prev_view_proj", prev_view_proj);
+
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::BindVertexArray(self.vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            gl::BindVertexArray(0);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+    }
+}
+
+impl Drop for PostProcess {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
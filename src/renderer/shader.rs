@@ -1,5 +1,6 @@
 use gl::types::*;
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec3, Vec4};
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::ptr;
@@ -97,6 +98,25 @@ impl ShaderProgram {
     }
 }
 
+impl ShaderProgram {
+    /// Point the uniform block `name` at buffer binding point `binding`. Returns the block's
+    /// size in bytes as the driver laid it out, or `None` if the program has no such
+    /// (active) block.
+    pub fn bind_uniform_block(&self, name: &str, binding: u32) -> Option<usize> {
+        let cname = CString::new(name).unwrap();
+        unsafe {
+            let index = gl::GetUniformBlockIndex(self.id, cname.as_ptr());
+            if index == gl::INVALID_INDEX {
+                return None;
+            }
+            gl::UniformBlockBinding(self.id, index, binding);
+            let mut size = 0;
+            gl::GetActiveUniformBlockiv(self.id, index, gl::UNIFORM_BLOCK_DATA_SIZE, &mut size);
+            Some(size as usize)
+        }
+    }
+}
+
 impl Drop for ShaderProgram {
     fn drop(&mut self) {
         unsafe {
@@ -130,3 +150,146 @@ unsafe fn compile_shader(src: &str, shader_type: GLenum) -> Result<GLuint, Strin
     }
     Ok(shader)
 }
+
+/// A uniform buffer object attached to a fixed binding point, so every program whose block
+/// is bound there (`ShaderProgram::bind_uniform_block`) reads the same data.
+pub struct UniformBuffer {
+    id: GLuint,
+    size: usize,
+    /// Whether an oversized write has been reported, so it's logged once, not every frame.
+    overflow_reported: Cell<bool>,
+}
+
+impl UniformBuffer {
+    pub fn new(size: usize, binding: u32) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, id);
+            gl::BufferData(gl::UNIFORM_BUFFER, size as isize, ptr::null(), gl::DYNAMIC_DRAW);
+            gl::BindBufferBase(gl::UNIFORM_BUFFER, binding, id);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+        Self { id, size, overflow_reported: Cell::new(false) }
+    }
+
+    /// The part of a write of `bytes` at `offset` that fits in the block. The block size is
+    /// what the driver reports and may include trailing padding, so shorter writes are fine;
+    /// a longer one means the writer and the GLSL declaration disagree on layout. That is
+    /// reported once and the excess dropped instead of failing the frame.
+    fn fit<'a>(&self, offset: usize, bytes: &'a [u8]) -> &'a [u8] {
+        let end = offset + bytes.len();
+        if end > self.size && !self.overflow_reported.replace(true) {
+            eprintln!(
+                "[render] uniform data ends at byte {end} but the block holds {}; the std140 \
+                 writer and the GLSL block disagree on layout",
+                self.size
+            );
+        }
+        &bytes[..self.size.saturating_sub(offset).min(bytes.len())]
+    }
+
+    /// Overwrite the start of the buffer with `bytes`, normally the whole block.
+    pub fn update(&self, bytes: &[u8]) {
+        let bytes = self.fit(0, bytes);
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, self.id);
+            let len = bytes.len() as isize;
+            gl::BufferSubData(gl::UNIFORM_BUFFER, 0, len, bytes.as_ptr() as *const _);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+    }
+
+    /// Overwrite `bytes.len()` bytes starting at `offset`, leaving the rest of the block as
+    /// it was. `offset` must be where the first member written lives in the block.
+    pub fn update_range(&self, offset: usize, bytes: &[u8]) {
+        let bytes = self.fit(offset, bytes);
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, self.id);
+            let len = bytes.len() as isize;
+            gl::BufferSubData(gl::UNIFORM_BUFFER, offset as isize, len, bytes.as_ptr() as *const _);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+    }
+}
+
+impl Drop for UniformBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
+
+/// CPU-side writer for a std140 uniform block. Members must be written in declaration order;
+/// each call pads to the member's std140 alignment first:
+/// - `float`/`int`: 4 bytes, so they pack into the unused tail of a preceding `vec3`,
+/// - `vec3`/`vec4`/`mat4` columns: 16 bytes,
+/// - arrays: every element starts a 16-byte slot, whatever its type.
+pub struct Std140 {
+    data: Vec<u8>,
+}
+
+impl Std140 {
+    pub fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    fn align(&mut self, alignment: usize) {
+        let len = self.data.len().next_multiple_of(alignment);
+        self.data.resize(len, 0);
+    }
+
+    fn push(&mut self, values: &[f32]) {
+        for v in values {
+            self.data.extend_from_slice(&v.to_ne_bytes());
+        }
+    }
+
+    pub fn float(&mut self, v: f32) {
+        self.align(4);
+        self.push(&[v]);
+    }
+
+    pub fn int(&mut self, v: i32) {
+        self.align(4);
+        self.data.extend_from_slice(&v.to_ne_bytes());
+    }
+
+    pub fn vec3(&mut self, v: Vec3) {
+        self.align(16);
+        self.push(&v.to_array());
+    }
+
+    pub fn vec4(&mut self, v: Vec4) {
+        self.align(16);
+        self.push(&v.to_array());
+    }
+
+    pub fn mat4(&mut self, m: Mat4) {
+        self.align(16);
+        self.push(&m.to_cols_array());
+    }
+
+    /// A `len`-element array written with `write`. Slots `values` runs out before are filled
+    /// with `T::default()`; values past `len` are ignored.
+    pub fn array<T: Default>(
+        &mut self,
+        values: impl IntoIterator<Item = T>,
+        len: usize,
+        write: fn(&mut Self, T),
+    ) {
+        let mut values = values.into_iter();
+        for _ in 0..len {
+            self.align(16);
+            write(self, values.next().unwrap_or_default());
+        }
+        self.align(16);
+    }
+
+    /// The block contents, padded to a whole 16-byte slot like the driver's block size.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.align(16);
+        self.data
+    }
+}
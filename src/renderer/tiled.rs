@@ -0,0 +1,250 @@
+use gl::types::*;
+use glam::{Mat4, Vec3, Vec4};
+
+use crate::components::Attenuation;
+
+/// Screen tile edge length in pixels.
+pub const TILE_SIZE: i32 = 32;
+/// Most point lights the tiled path handles per frame.
+pub const MAX_TILED_LIGHTS: usize = 256;
+/// Width of the tile index texture; the flat index list wraps onto further rows.
+const INDEX_TEXTURE_WIDTH: usize = 1024;
+/// RGBA32F texels per light in the light data texture (layout documented in cel.frag).
+const TEXELS_PER_LIGHT: usize = 3;
+/// Light reaching a surface below this (linear, colour × intensity / d²) counts as none when
+/// bounding an inverse-square light, which never falls to zero: about one 8-bit step.
+const INVERSE_SQUARE_CUTOFF: f32 = 1.0 / 256.0;
+
+/// A point light as the tiled path uploads it.
+pub struct PackedPointLight {
+    pub position: Vec3,
+    pub radius: f32,
+    pub color: Vec3,
+    pub intensity: f32,
+    pub attenuation: i32,
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl PackedPointLight {
+    /// Distance past which the light contributes nothing visible, for culling and binning.
+    /// That is `radius`, except for inverse-square lights, which ignore `radius` and are
+    /// cut off where their falloff drops under `INVERSE_SQUARE_CUTOFF`.
+    pub fn reach(&self) -> f32 {
+        if self.attenuation == Attenuation::InverseSquare as i32 {
+            let peak = self.color.max_element() * self.intensity;
+            (peak.max(0.0) / INVERSE_SQUARE_CUTOFF).sqrt()
+        } else {
+            self.radius
+        }
+    }
+}
+
+/// Forward+ style light lists for many point lights, without compute shaders (GL 3.3).
+///
+/// Each frame the CPU bins lights into `TILE_SIZE` screen tiles by the screen rectangle of
+/// the sphere they reach (see `PackedPointLight::reach`). Three integer/float textures
+/// carry the result to the fragment shader, which only loops over the lights listed for
+/// its own tile:
+/// - light data: `TEXELS_PER_LIGHT` texels per light,
+/// - tile grid: one texel per tile holding (offset, count) into the index list,
+/// - index list: light indices for all tiles back to back.
+pub struct TiledLights {
+    light_texture: GLuint,
+    grid_texture: GLuint,
+    index_texture: GLuint,
+    /// First of the three texture units the textures live on.
+    first_unit: u32,
+}
+
+impl TiledLights {
+    /// The textures occupy units `first_unit..first_unit + 3`; uploads go through those units
+    /// too, so other bindings (the shadow maps) are never disturbed.
+    pub fn new(first_unit: u32) -> Self {
+        let mut textures = [0 as GLuint; 3];
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + first_unit);
+            gl::GenTextures(3, textures.as_mut_ptr());
+            for &texture in &textures {
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            }
+        }
+        let tiled = Self {
+            light_texture: textures[0],
+            grid_texture: textures[1],
+            index_texture: textures[2],
+            first_unit,
+        };
+        // Start with valid (empty) contents so the samplers are complete before first use.
+        tiled.upload_textures(&[0.0; 4], 1, &[0, 0], (1, 1), &[0]);
+        tiled
+    }
+
+    /// Bin `lights` into screen tiles for a `viewport`-sized target seen through `view_proj`
+    /// and upload the lists. Lights past `MAX_TILED_LIGHTS` are dropped.
+    pub fn upload(&mut self, lights: &[PackedPointLight], view_proj: &Mat4, viewport: (i32, i32)) {
+        let lights = &lights[..lights.len().min(MAX_TILED_LIGHTS)];
+        let tiles_x = ((viewport.0 + TILE_SIZE - 1) / TILE_SIZE).max(1) as usize;
+        let tiles_y = ((viewport.1 + TILE_SIZE - 1) / TILE_SIZE).max(1) as usize;
+
+        let mut tile_lists: Vec<Vec<i32>> = vec![Vec::new(); tiles_x * tiles_y];
+        for (index, light) in lights.iter().enumerate() {
+            let Some((min, max)) = screen_rect(light.position, light.reach(), view_proj) else {
+                continue;
+            };
+            // NDC → pixel (bottom-left origin, matching gl_FragCoord) → tile.
+            let to_tile = |ndc: f32, size: i32, tiles: usize| {
+                let pixel = (ndc * 0.5 + 0.5) * size as f32;
+                ((pixel as i32) / TILE_SIZE).clamp(0, tiles as i32 - 1) as usize
+            };
+            let x_range = to_tile(min.x, viewport.0, tiles_x)..=to_tile(max.x, viewport.0, tiles_x);
+            let y_range = to_tile(min.y, viewport.1, tiles_y)..=to_tile(max.y, viewport.1, tiles_y);
+            for y in y_range {
+                for x in x_range.clone() {
+                    tile_lists[y * tiles_x + x].push(index as i32);
+                }
+            }
+        }
+
+        let mut grid = Vec::with_capacity(tiles_x * tiles_y * 2);
+        let mut indices = Vec::new();
+        for list in &tile_lists {
+            grid.push(indices.len() as i32);
+            grid.push(list.len() as i32);
+            indices.extend_from_slice(list);
+        }
+        let rows = indices.len().div_ceil(INDEX_TEXTURE_WIDTH).max(1);
+        indices.resize(rows * INDEX_TEXTURE_WIDTH, 0);
+
+        let mut light_data = Vec::with_capacity(lights.len().max(1) * TEXELS_PER_LIGHT * 4);
+        for light in lights {
+            let color = light.color * light.intensity;
+            light_data.extend_from_slice(&[
+                light.position.x,
+                light.position.y,
+                light.position.z,
+                light.radius,
+                color.x,
+                color.y,
+                color.z,
+                light.attenuation as f32,
+                light.constant,
+                light.linear,
+                light.quadratic,
+                0.0,
+            ]);
+        }
+        if light_data.is_empty() {
+            light_data.extend_from_slice(&[0.0; 4]);
+        }
+        let light_texels = light_data.len() / 4;
+
+        self.upload_textures(&light_data, light_texels, &grid, (tiles_x, tiles_y), &indices);
+    }
+
+    fn upload_textures(
+        &self,
+        light_data: &[f32],
+        light_texels: usize,
+        grid: &[i32],
+        tiles: (usize, usize),
+        indices: &[i32],
+    ) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + self.first_unit);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+
+            gl::BindTexture(gl::TEXTURE_2D, self.light_texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA32F as i32,
+                light_texels as i32,
+                1,
+                0,
+                gl::RGBA,
+                gl::FLOAT,
+                light_data.as_ptr() as *const _,
+            );
+
+            gl::BindTexture(gl::TEXTURE_2D, self.grid_texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RG32I as i32,
+                tiles.0 as i32,
+                tiles.1 as i32,
+                0,
+                gl::RG_INTEGER,
+                gl::INT,
+                grid.as_ptr() as *const _,
+            );
+
+            gl::BindTexture(gl::TEXTURE_2D, self.index_texture);
+            let width = indices.len().min(INDEX_TEXTURE_WIDTH);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::R32I as i32,
+                width as i32,
+                (indices.len() / width) as i32,
+                0,
+                gl::RED_INTEGER,
+                gl::INT,
+                indices.as_ptr() as *const _,
+            );
+        }
+    }
+
+    /// Bind the light data, tile grid and index textures to units `first_unit`,
+    /// `first_unit + 1` and `first_unit + 2`.
+    pub fn bind(&self) {
+        let textures = [self.light_texture, self.grid_texture, self.index_texture];
+        for (i, &texture) in textures.iter().enumerate() {
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0 + self.first_unit + i as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+        }
+    }
+}
+
+impl Drop for TiledLights {
+    fn drop(&mut self) {
+        let textures = [self.light_texture, self.grid_texture, self.index_texture];
+        unsafe {
+            gl::DeleteTextures(3, textures.as_ptr());
+        }
+    }
+}
+
+/// NDC-space (x, y) rectangle covered by a sphere, from the eight corners of its bounding
+/// box. Spheres reaching behind the camera cover the whole screen. `None` if off-screen.
+fn screen_rect(center: Vec3, radius: f32, view_proj: &Mat4) -> Option<(Vec3, Vec3)> {
+    let mut min = Vec3::splat(f32::MAX);
+    let mut max = Vec3::splat(f32::MIN);
+    for i in 0..8 {
+        let corner = center
+            + Vec3::new(
+                if i & 1 == 0 { -radius } else { radius },
+                if i & 2 == 0 { -radius } else { radius },
+                if i & 4 == 0 { -radius } else { radius },
+            );
+        let clip = *view_proj * Vec4::new(corner.x, corner.y, corner.z, 1.0);
+        if clip.w <= 1e-4 {
+            return Some((Vec3::splat(-1.0), Vec3::splat(1.0)));
+        }
+        let ndc = clip.truncate() / clip.w;
+        min = min.min(ndc);
+        max = max.max(ndc);
+    }
+    if max.x < -1.0 || max.y < -1.0 || min.x > 1.0 || min.y > 1.0 {
+        return None;
+    }
+    Some((min.max(Vec3::splat(-1.0)), max.min(Vec3::splat(1.0))))
+}
@@ -0,0 +1,77 @@
+use glam::Vec3;
+use hecs::{Entity, World};
+
+use super::shader::ShaderProgram;
+use super::{srgb_to_linear, with_frame_block, MeshStore, FRAME_BINDING};
+use crate::components::{GlobalTransform, GrabState, Hidden, MeshHandle, XRay};
+
+const XRAY_VERT_SRC: &str = include_str!("../../shaders/xray.vert");
+const XRAY_FRAG_SRC: &str = include_str!("../../shaders/xray.frag");
+
+/// Outline colour of the grabbable the player is aimed at.
+const GRAB_TARGET_COLOR: Vec3 = Vec3::new(0.35, 0.85, 1.0);
+
+/// Draws the occluded parts of flagged entities: anything with `XRay`, plus each player's
+/// `GrabState::target`. Runs after the opaque pass with the depth test inverted (`GREATER`),
+/// so only fragments behind already-drawn geometry come out, blended as a rim outline.
+pub struct XRayRenderer {
+    shader: ShaderProgram,
+}
+
+impl XRayRenderer {
+    pub fn new() -> Self {
+        let shader = ShaderProgram::from_sources(
+            &with_frame_block(XRAY_VERT_SRC),
+            &with_frame_block(XRAY_FRAG_SRC),
+        )
+        .expect("Failed to compile x-ray shaders");
+        shader.bind_uniform_block("Frame", FRAME_BINDING);
+        Self { shader }
+    }
+
+    pub fn draw(&mut self, world: &World, meshes: &MeshStore) {
+        let mut flagged: Vec<(Entity, Vec3)> =
+            world.query::<&XRay>().iter().map(|(e, xray)| (e, xray.color)).collect();
+        flagged.extend(
+            world
+                .query::<&GrabState>()
+                .iter()
+                .filter_map(|(_e, grab)| grab.target)
+                .map(|e| (e, GRAB_TARGET_COLOR)),
+        );
+        if flagged.is_empty() {
+            return;
+        }
+
+        self.shader.bind();
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(gl::FALSE);
+            gl::DepthFunc(gl::GREATER);
+        }
+
+        for (entity, color) in flagged {
+            let Ok(mut query) =
+                world.query_one::<(&GlobalTransform, &MeshHandle, Option<&Hidden>)>(entity)
+            else {
+                continue;
+            };
+            let Some((gt, mesh_handle, hidden)) = query.get() else {
+                continue;
+            };
+            if hidden.is_some() {
+                continue;
+            }
+            self.shader.set_mat4("u_model", &gt.0);
+            self.shader.set_vec3("u_xray_color", srgb_to_linear(color));
+            meshes.get(*mesh_handle).draw();
+        }
+
+        unsafe {
+            gl::DepthFunc(gl::LESS);
+            gl::DepthMask(gl::TRUE);
+            gl::Disable(gl::BLEND);
+        }
+    }
+}
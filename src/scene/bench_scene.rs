@@ -0,0 +1,100 @@
+use glam::{Mat4, Vec3};
+use hecs::{Entity, World};
+
+use crate::components::{
+    Collider, Color, Drag, Friction, GlobalTransform, Grabbable, GravityAffected,
+    LocalTransform, Mass, MeshShape, Restitution, Velocity,
+};
+use crate::renderer::mesh::create_shape;
+use crate::renderer::MeshStore;
+use crate::scene::prefabs::{
+    spawn_directional_light, spawn_ground, spawn_player, spawn_static_box,
+};
+
+const SPHERE_RADIUS: f32 = 0.4;
+/// Grid pitch between sphere centers (X/Z).
+const SPACING: f32 = 1.2;
+/// Height of the lowest sphere layer, and the spread added on top to stagger spawns.
+const BASE_HEIGHT: f32 = 3.0;
+const HEIGHT_SPREAD: f32 = 4.0;
+
+/// Build a stress-test scene: an `count`×`count` grid of dynamic spheres dropped onto
+/// the ground plane, with static boxes scattered around the grid, for loading the O(n²)
+/// collision pass and the renderer. Spheres share one mesh, and their spawn heights are
+/// staggered so no two start at the same point.
+/// Returns the mesh store (owns all GPU mesh data) and the player entity.
+pub fn load_bench_scene(world: &mut World, count: usize) -> (MeshStore, Entity) {
+    let mut meshes = MeshStore::new();
+    let mut rng = Lcg(0x2545_f491);
+
+    spawn_ground(world, &mut meshes);
+
+    let sphere_shape = MeshShape::Sphere { radius: 1.0, stacks: 12, sectors: 16 };
+    let sphere_handle = meshes.add(create_shape(&sphere_shape));
+    let half = (count as f32 - 1.0) * SPACING / 2.0;
+    for i in 0..count {
+        for j in 0..count {
+            let x = i as f32 * SPACING - half;
+            let z = j as f32 * SPACING - half;
+            let y = BASE_HEIGHT + rng.next_f32() * HEIGHT_SPREAD;
+            let color = Vec3::new(0.3 + 0.6 * rng.next_f32(), 0.3, 0.3 + 0.6 * rng.next_f32());
+
+            let mut t = LocalTransform::new(Vec3::new(x, y, z));
+            t.scale = Vec3::splat(SPHERE_RADIUS);
+            world.spawn((
+                t,
+                GlobalTransform(Mat4::IDENTITY),
+                sphere_handle,
+                sphere_shape,
+                Color(color),
+                Velocity(Vec3::ZERO),
+                Mass(1.0),
+                GravityAffected,
+                Collider::Sphere { radius: SPHERE_RADIUS },
+                Restitution(0.3),
+                Friction(0.7),
+                Drag(0.5),
+                Grabbable,
+            ));
+        }
+    }
+
+    // Static boxes scattered in a ring around the grid (some overlap its edge).
+    let grey = Vec3::new(0.5, 0.5, 0.52);
+    let ring = half + 4.0;
+    for _ in 0..count.max(4) {
+        let angle = rng.next_f32() * std::f32::consts::TAU;
+        let dist = ring * (0.6 + 0.6 * rng.next_f32());
+        let h = 0.5 + 2.5 * rng.next_f32();
+        spawn_static_box(
+            world,
+            &mut meshes,
+            Vec3::new(angle.cos() * dist, h / 2.0, angle.sin() * dist),
+            Vec3::new(0.5 + rng.next_f32(), h / 2.0, 0.5 + rng.next_f32()),
+            grey,
+        );
+    }
+
+    let player_entity = spawn_player(world, &mut meshes, Vec3::new(0.0, 10.0, ring + 6.0));
+
+    spawn_directional_light(
+        world,
+        Vec3::new(-0.5, -1.0, -0.3),
+        Vec3::new(1.0, 0.95, 0.85),
+        1.0,
+        true,
+    );
+
+    (meshes, player_entity)
+}
+
+/// Tiny deterministic generator so every bench run spawns the same layout.
+struct Lcg(u32);
+
+impl Lcg {
+    /// Uniform in [0, 1).
+    fn next_f32(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (self.0 >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
@@ -0,0 +1,594 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use glam::{EulerRot, Mat4, Quat, Vec3};
+use hecs::{Entity, World};
+use serde::de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+
+use crate::components::{
+    Collider, Color, DirectionalLight, Flicker, GlobalTransform, Lifetime, LocalTransform,
+    Persistent, PlanarMirror, PointLight, QuadraticDrag, Reflective, SpotLight, Static, Velocity,
+};
+use crate::engine::world_up::WorldUp;
+use crate::renderer::MeshStore;
+use crate::systems::find_free_spawn;
+use crate::scene::prefabs::{
+    spawn_directional_light, spawn_ground, spawn_physics_sphere, spawn_player, spawn_point_light,
+    spawn_spot_light, spawn_static_box,
+};
+
+/// How far above its listed position a dynamic prefab may be moved to clear overlaps.
+const SPAWN_SEARCH_HEIGHT: f32 = 10.0;
+
+fn default_true() -> bool {
+    true
+}
+
+fn euler_degrees_to_quat(degrees: Vec3) -> Quat {
+    let r = degrees * (std::f32::consts::PI / 180.0);
+    Quat::from_euler(EulerRot::XYZ, r.x, r.y, r.z)
+}
+
+/// Inverse of `euler_degrees_to_quat`, rounded to a thousandth of a degree so the round
+/// trip's float noise doesn't leak into saved files.
+fn quat_to_euler_degrees(rotation: Quat) -> Vec3 {
+    let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+    (Vec3::new(x, y, z) * (180.0 / std::f32::consts::PI) * 1000.0).round() / 1000.0
+}
+
+/// A human-authored scene: a RON file listing prefabs and their parameters, each spawned
+/// through the matching factory in `prefabs`, so layouts can change without a rebuild.
+///
+/// ```ron
+/// Scene(
+///     player: (0.0, 10.0, 0.0),
+///     ground_reflectivity: 0.25,
+///     entities: [
+///         StaticBox(position: (6.0, 1.0, -4.0), half_extents: (2.5, 1.0, 3.5),
+///                   color: (0.5, 0.5, 0.52)),
+///         StaticBox(position: (-4.0, 0.1, 4.0), half_extents: (3.0, 0.1, 2.0),
+///                   color: (0.1, 0.2, 0.25), mirror: 0.7),
+///         PhysicsSphere(position: (0.0, 2.0, -3.0), color: (0.8, 0.2, 0.15), radius: 0.5),
+///         DirectionalLight(direction: (-0.5, -1.0, -0.3), color: (1.0, 0.95, 0.85),
+///                          intensity: 1.0, casts_shadows: true, shadow_distance: Some(40.0)),
+///         PointLight(position: (3.0, 3.0, 0.0), color: (1.0, 0.6, 0.2), intensity: 2.0,
+///                    radius: 15.0),
+///     ],
+/// )
+/// ```
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "Scene", deny_unknown_fields)]
+pub struct SceneFile {
+    /// Player spawn position.
+    player: Vec3,
+    /// Spawn the standard ground plane.
+    #[serde(default = "default_true")]
+    ground: bool,
+    /// Screen-space reflection strength of the ground (0–1); 0 keeps it matte.
+    #[serde(default)]
+    ground_reflectivity: f32,
+    #[serde(default, deserialize_with = "validated_entities")]
+    entities: Vec<Prefab>,
+}
+
+/// One entry in `entities`: a prefab name and its parameters.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+enum Prefab {
+    StaticBox {
+        position: Vec3,
+        half_extents: Vec3,
+        color: Vec3,
+        /// Planar mirror strength of the top face (0–1), e.g. for still water; 0 is opaque.
+        #[serde(default)]
+        mirror: f32,
+        /// Orientation as XYZ Euler angles in degrees, for ramps and tilted walls.
+        #[serde(default)]
+        rotation: Vec3,
+    },
+    PhysicsSphere {
+        position: Vec3,
+        color: Vec3,
+        radius: f32,
+        #[serde(default)]
+        velocity: Vec3,
+        /// Quadratic air drag coefficient; 0 leaves the sphere with linear drag only.
+        #[serde(default)]
+        air_drag: f32,
+        /// Seconds until the sphere despawns itself; `None` keeps it for good.
+        #[serde(default)]
+        lifetime: Option<f32>,
+    },
+    DirectionalLight {
+        direction: Vec3,
+        color: Vec3,
+        intensity: f32,
+        #[serde(default)]
+        casts_shadows: bool,
+        #[serde(default)]
+        shadow_distance: Option<f32>,
+    },
+    PointLight {
+        position: Vec3,
+        color: Vec3,
+        intensity: f32,
+        radius: f32,
+    },
+    SpotLight {
+        position: Vec3,
+        direction: Vec3,
+        color: Vec3,
+        intensity: f32,
+        inner_deg: f32,
+        outer_deg: f32,
+        radius: f32,
+    },
+}
+
+impl Prefab {
+    fn name(&self) -> &'static str {
+        match self {
+            Prefab::StaticBox { .. } => "StaticBox",
+            Prefab::PhysicsSphere { .. } => "PhysicsSphere",
+            Prefab::DirectionalLight { .. } => "DirectionalLight",
+            Prefab::PointLight { .. } => "PointLight",
+            Prefab::SpotLight { .. } => "SpotLight",
+        }
+    }
+
+    /// Reject parameters that parse fine but can't make a working entity (they would turn
+    /// into NaNs or unlit, zero-sized lights further down).
+    fn validate(&self) -> Result<(), String> {
+        let positive = |name: &str, value: f32| {
+            if value > 0.0 {
+                Ok(())
+            } else {
+                Err(format!("{name} must be positive, got {value}"))
+            }
+        };
+        let nonzero = |name: &str, v: Vec3| {
+            if v.length_squared() > 1e-12 {
+                Ok(())
+            } else {
+                Err(format!("{name} must not be zero"))
+            }
+        };
+        match *self {
+            Prefab::StaticBox { half_extents, .. } => {
+                if half_extents.min_element() > 0.0 {
+                    Ok(())
+                } else {
+                    Err(format!("half_extents must all be positive, got {half_extents}"))
+                }
+            }
+            Prefab::PhysicsSphere { radius, lifetime, .. } => {
+                positive("radius", radius)?;
+                lifetime.map_or(Ok(()), |l| positive("lifetime", l))
+            }
+            Prefab::DirectionalLight { direction, .. } => nonzero("direction", direction),
+            Prefab::PointLight { radius, .. } => positive("radius", radius),
+            Prefab::SpotLight { direction, inner_deg, outer_deg, radius, .. } => {
+                nonzero("direction", direction)?;
+                positive("radius", radius)?;
+                if outer_deg < inner_deg {
+                    return Err(format!(
+                        "outer_deg ({outer_deg}) must not be smaller than inner_deg ({inner_deg})"
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Deserialize `entities`, checking each prefab with `Prefab::validate` as soon as it is
+/// parsed. A rejected entity fails the parse right after its closing bracket, so ron reports
+/// the error at that entity's position in the file.
+fn validated_entities<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Prefab>, D::Error> {
+    struct Entities;
+
+    impl<'de> Visitor<'de> for Entities {
+        type Value = Vec<Prefab>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a list of prefabs")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<Prefab>, A::Error> {
+            let mut entities = Vec::new();
+            while let Some(prefab) = seq.next_element_seed(ValidPrefab(entities.len()))? {
+                entities.push(prefab);
+            }
+            Ok(entities)
+        }
+    }
+
+    deserializer.deserialize_seq(Entities)
+}
+
+/// Parses the prefab at this index in `entities` and rejects it if it fails validation.
+struct ValidPrefab(usize);
+
+impl<'de> DeserializeSeed<'de> for ValidPrefab {
+    type Value = Prefab;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Prefab, D::Error> {
+        let prefab = Prefab::deserialize(deserializer)?;
+        if let Err(message) = prefab.validate() {
+            let message = format!("entity #{} ({}): {message}", self.0, prefab.name());
+            return Err(de::Error::custom(message));
+        }
+        Ok(prefab)
+    }
+}
+
+/// Failure to read or parse a scene file. Parse errors carry the 1-based line and column;
+/// for an entity whose parameters are unusable that is where the entity ends, and the
+/// message names its index (0-based in `entities`) and prefab.
+#[derive(Debug)]
+pub enum SceneError {
+    Io { path: PathBuf, error: std::io::Error },
+    Parse { path: PathBuf, line: usize, col: usize, message: String },
+    Serialize { path: PathBuf, message: String },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            SceneError::Parse { path, line, col, message } => {
+                write!(f, "{}:{}:{}: {}", path.display(), line, col, message)
+            }
+            SceneError::Serialize { path, message } => {
+                write!(f, "{}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl SceneFile {
+    /// Read and parse `path`. Unknown prefab names, unknown or missing parameters,
+    /// malformed values and parameters that can't make a working entity (zero directions,
+    /// non-positive radii, a spot cone whose outer angle is inside its inner one) are all
+    /// reported with their position in the file.
+    pub fn load(path: &Path) -> Result<Self, SceneError> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|error| SceneError::Io { path: path.to_path_buf(), error })?;
+        ron::from_str(&source).map_err(|e| SceneError::Parse {
+            path: path.to_path_buf(),
+            line: e.position.line,
+            col: e.position.col,
+            message: e.code.to_string(),
+        })
+    }
+
+    /// Spawn everything the file describes into `world`, tagged `Persistent` so a later
+    /// `from_world` finds them again. Dynamic prefabs are lifted clear of overlaps along
+    /// `up`. Returns the player entity.
+    pub fn spawn(&self, world: &mut World, meshes: &mut MeshStore, up: WorldUp) -> Entity {
+        if self.ground {
+            let ground = spawn_ground(world, meshes);
+            let _ = world.insert_one(ground, Persistent);
+            if self.ground_reflectivity > 0.0 {
+                let _ = world.insert_one(ground, Reflective { strength: self.ground_reflectivity });
+            }
+        }
+        for prefab in &self.entities {
+            let entity = match *prefab {
+                Prefab::StaticBox { position, half_extents, color, mirror, rotation } => {
+                    let entity = spawn_static_box(world, meshes, position, half_extents, color);
+                    if rotation != Vec3::ZERO {
+                        let rotation = euler_degrees_to_quat(rotation);
+                        if let Ok(mut local) = world.get::<&mut LocalTransform>(entity) {
+                            local.rotation = rotation;
+                        }
+                        if let Ok(mut global) = world.get::<&mut GlobalTransform>(entity) {
+                            global.0 = Mat4::from_rotation_translation(rotation, position);
+                        }
+                    }
+                    if mirror > 0.0 {
+                        let _ = world.insert_one(entity, PlanarMirror { strength: mirror });
+                    }
+                    entity
+                }
+                Prefab::PhysicsSphere { position, color, radius, velocity, air_drag, lifetime } => {
+                    // Lifted clear of anything spawned before it rather than left embedded.
+                    let collider = Collider::Sphere { radius };
+                    let position =
+                        find_free_spawn(world, &collider, position, SPAWN_SEARCH_HEIGHT, up);
+                    let entity =
+                        spawn_physics_sphere(world, meshes, position, color, radius, velocity);
+                    if air_drag > 0.0 {
+                        let _ = world.insert_one(entity, QuadraticDrag(air_drag));
+                    }
+                    if let Some(remaining) = lifetime {
+                        let _ = world.insert_one(entity, Lifetime { remaining });
+                    }
+                    entity
+                }
+                Prefab::DirectionalLight {
+                    direction,
+                    color,
+                    intensity,
+                    casts_shadows,
+                    shadow_distance,
+                } => {
+                    let light =
+                        spawn_directional_light(world, direction, color, intensity, casts_shadows);
+                    if let Ok(mut dl) = world.get::<&mut DirectionalLight>(light) {
+                        dl.shadow_distance = shadow_distance;
+                    }
+                    light
+                }
+                Prefab::PointLight { position, color, intensity, radius } => {
+                    spawn_point_light(world, position, color, intensity, radius)
+                }
+                Prefab::SpotLight {
+                    position,
+                    direction,
+                    color,
+                    intensity,
+                    inner_deg,
+                    outer_deg,
+                    radius,
+                } => {
+                    spawn_spot_light(
+                        world, position, direction, color, intensity, inner_deg, outer_deg,
+                        radius,
+                    )
+                }
+            };
+            let _ = world.insert_one(entity, Persistent);
+        }
+        spawn_player(world, meshes, self.player)
+    }
+
+    /// Describe the `Persistent` entities in `world` as a scene, for saving an edited layout.
+    /// Each entity's prefab is recognised from its components and its parameters read back
+    /// from their current values; entities matching no prefab are skipped.
+    pub fn from_world(world: &World, player: Entity) -> Self {
+        let player = world
+            .get::<&LocalTransform>(player)
+            .map(|local| local.position)
+            .unwrap_or(Vec3::ZERO);
+        let mut ground = false;
+        let mut ground_reflectivity = 0.0;
+        let mut entities = Vec::new();
+
+        let mut persistent: Vec<Entity> =
+            world.query::<&Persistent>().iter().map(|(entity, _)| entity).collect();
+        // Keep saves stable across runs so the file diffs cleanly.
+        persistent.sort();
+        for entity in persistent {
+            let position =
+                world.get::<&LocalTransform>(entity).map(|local| local.position).ok();
+            let color = world.get::<&Color>(entity).map(|color| color.0).ok();
+            // Flicker rewrites the light's intensity every frame; save the steady value.
+            let flicker = world.get::<&Flicker>(entity).map(|f| f.base_intensity).ok();
+
+            if let Ok(light) = world.get::<&DirectionalLight>(entity) {
+                entities.push(Prefab::DirectionalLight {
+                    direction: light.direction,
+                    color: light.color,
+                    intensity: light.intensity,
+                    casts_shadows: light.casts_shadows,
+                    shadow_distance: light.shadow_distance,
+                });
+                continue;
+            }
+            let Some(position) = position else {
+                continue;
+            };
+            if let Ok(light) = world.get::<&PointLight>(entity) {
+                entities.push(Prefab::PointLight {
+                    position,
+                    color: light.color,
+                    intensity: flicker.unwrap_or(light.intensity),
+                    radius: light.radius,
+                });
+            } else if let Ok(light) = world.get::<&SpotLight>(entity) {
+                entities.push(Prefab::SpotLight {
+                    position,
+                    direction: light.direction,
+                    color: light.color,
+                    intensity: flicker.unwrap_or(light.intensity),
+                    inner_deg: light.inner_cone.acos().to_degrees(),
+                    outer_deg: light.outer_cone.acos().to_degrees(),
+                    radius: light.radius,
+                });
+            } else if let Ok(collider) = world.get::<&Collider>(entity) {
+                let is_static = world.get::<&Static>(entity).is_ok();
+                match (&*collider, is_static, color) {
+                    (Collider::Plane { .. }, _, _) => {
+                        ground = true;
+                        ground_reflectivity =
+                            world.get::<&Reflective>(entity).map_or(0.0, |r| r.strength);
+                    }
+                    (&Collider::Box { half_extents }, true, Some(color)) => {
+                        let mirror =
+                            world.get::<&PlanarMirror>(entity).map_or(0.0, |m| m.strength);
+                        let rotation = world
+                            .get::<&LocalTransform>(entity)
+                            .map_or(Vec3::ZERO, |local| quat_to_euler_degrees(local.rotation));
+                        entities.push(Prefab::StaticBox {
+                            position,
+                            half_extents,
+                            color,
+                            mirror,
+                            rotation,
+                        });
+                    }
+                    (&Collider::Sphere { radius }, false, Some(color)) => {
+                        let velocity =
+                            world.get::<&Velocity>(entity).map(|v| v.0).unwrap_or(Vec3::ZERO);
+                        let air_drag =
+                            world.get::<&QuadraticDrag>(entity).map_or(0.0, |d| d.0);
+                        let lifetime = world.get::<&Lifetime>(entity).ok().map(|l| l.remaining);
+                        entities.push(Prefab::PhysicsSphere {
+                            position,
+                            color,
+                            radius,
+                            velocity,
+                            air_drag,
+                            lifetime,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Self { player, ground, ground_reflectivity, entities }
+    }
+
+    /// Write the scene to `path` in the same RON format `load` reads.
+    pub fn save(&self, path: &Path) -> Result<(), SceneError> {
+        let config = ron::ser::PrettyConfig::new().depth_limit(2);
+        let source = ron::ser::to_string_pretty(self, config).map_err(|e| {
+            SceneError::Serialize { path: path.to_path_buf(), message: e.to_string() }
+        })?;
+        std::fs::write(path, source + "\n")
+            .map_err(|error| SceneError::Io { path: path.to_path_buf(), error })
+    }
+
+    /// Spawn into `world` with a fresh mesh store, as a `SceneLoader` expects.
+    pub fn build(&self, world: &mut World, up: WorldUp) -> (MeshStore, Entity) {
+        let mut meshes = MeshStore::new();
+        let player = self.spawn(world, &mut meshes, up);
+        (meshes, player)
+    }
+}
+
+/// How often `SceneWatch` checks the file's modification time (seconds).
+const WATCH_INTERVAL: f32 = 0.5;
+
+/// Polls a scene file's modification time so the running app can hot-reload it.
+pub struct SceneWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: f32,
+}
+
+impl SceneWatch {
+    pub fn new(path: PathBuf) -> Self {
+        let modified = modified_time(&path);
+        Self { path, modified, timer: 0.0 }
+    }
+
+    /// Once the file's mtime changes, re-parse it and return the result. Returns `None` while
+    /// the file is unchanged (or between checks). A failed parse is only reported once per
+    /// change, so a broken file doesn't spam the log until it's saved again.
+    pub fn poll(&mut self, dt: f32) -> Option<Result<SceneFile, SceneError>> {
+        self.timer += dt;
+        if self.timer < WATCH_INTERVAL {
+            return None;
+        }
+        self.timer = 0.0;
+        let modified = modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(SceneFile::load(&self.path))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Load `path` and spawn it into `world` in one step. Returns the player entity.
+#[allow(dead_code)]
+pub fn load_from_file(
+    world: &mut World,
+    meshes: &mut MeshStore,
+    path: &Path,
+    up: WorldUp,
+) -> Result<Entity, SceneError> {
+    Ok(SceneFile::load(path)?.spawn(world, meshes, up))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `source` to a temporary file and load it as a scene.
+    fn load_source(name: &str, source: &str) -> Result<SceneFile, SceneError> {
+        let path = std::env::temp_dir().join(format!("lance_{name}_{}.ron", std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        let result = SceneFile::load(&path);
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    /// Line and message of a parse error, panicking on anything else.
+    fn parse_error(result: Result<SceneFile, SceneError>) -> (usize, String) {
+        match result {
+            Err(SceneError::Parse { line, message, .. }) => (line, message),
+            Err(e) => panic!("expected a parse error, got {e}"),
+            Ok(_) => panic!("expected a parse error, the scene loaded"),
+        }
+    }
+
+    #[test]
+    fn valid_scene_loads() {
+        let scene = load_source(
+            "scene_valid",
+            "Scene(
+                player: (0.0, 10.0, 0.0),
+                entities: [
+                    PointLight(position: (3.0, 3.0, 0.0), color: (1.0, 0.6, 0.2),
+                               intensity: 2.0, radius: 15.0),
+                    PhysicsSphere(position: (0.0, 2.0, -3.0), color: (0.8, 0.2, 0.15),
+                                  radius: 0.5),
+                ],
+            )",
+        )
+        .unwrap();
+        assert_eq!(scene.player, Vec3::new(0.0, 10.0, 0.0));
+        assert!(scene.ground);
+        assert_eq!(scene.entities.len(), 2);
+    }
+
+    #[test]
+    fn invalid_entity_reports_its_line() {
+        let result = load_source(
+            "scene_invalid",
+            "Scene(
+                player: (0.0, 10.0, 0.0),
+                entities: [
+                    PointLight(position: (3.0, 3.0, 0.0), color: (1.0, 1.0, 1.0),
+                               intensity: 2.0, radius: 15.0),
+                    PointLight(position: (0.0, 3.0, 0.0), color: (1.0, 1.0, 1.0),
+                               intensity: 2.0, radius: 0.0),
+                ],
+            )",
+        );
+        let (line, message) = parse_error(result);
+        assert_eq!(line, 7);
+        assert!(message.starts_with("entity #1 (PointLight): radius"), "{message}");
+    }
+
+    #[test]
+    fn comment_before_entities_key_is_ignored() {
+        let result = load_source(
+            "scene_comment",
+            "Scene(
+                // entities: lights first, then props
+                player: (0.0, 10.0, 0.0),
+                entities: [
+                    SpotLight(position: (0.0, 3.0, 0.0), direction: (0.0, -1.0, 0.0),
+                              color: (1.0, 1.0, 1.0), intensity: 2.0, inner_deg: 40.0,
+                              outer_deg: 20.0, radius: 10.0),
+                ],
+            )",
+        );
+        let (line, message) = parse_error(result);
+        assert_eq!(line, 7);
+        assert!(message.starts_with("entity #0 (SpotLight): outer_deg"), "{message}");
+    }
+}
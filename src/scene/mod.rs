@@ -1,2 +1,267 @@
+pub mod bench_scene;
+pub mod loader;
 pub mod prefabs;
 pub mod test_scene;
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use glam::Vec3;
+use hecs::{Entity, EntityBuilder, World};
+use serde::{Deserialize, Serialize};
+
+use crate::components::{
+    add_child, descendants, Checkerboard, Children, Collider, Color, DirectionalLight, Drag,
+    Flicker, Friction, GlobalTransform, Grabbable, GravityAffected, LocalTransform, Mass,
+    MeshHandle, MeshShape, Player, PointLight, Restitution, SpotLight, Static, Velocity,
+};
+use crate::renderer::mesh::create_shape;
+use crate::renderer::MeshStore;
+use crate::scene::prefabs::spawn_player;
+
+/// A world as written by `save_scene`. Unlike a `SceneFile`, which lists hand-authored
+/// prefabs, this is a snapshot of every entity's components as they are at save time.
+#[derive(Deserialize, Serialize)]
+struct SavedScene {
+    /// Player position; the character itself is rebuilt by `spawn_player` on load.
+    player: Vec3,
+    entities: Vec<SavedEntity>,
+}
+
+/// One entity's data-only components. Absent components are `None` (or `false` for
+/// markers); `children` are indices into `SavedScene::entities`.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+struct SavedEntity {
+    transform: Option<LocalTransform>,
+    mesh: Option<MeshShape>,
+    color: Option<Vec3>,
+    checkerboard: Option<Vec3>,
+    collider: Option<Collider>,
+    is_static: bool,
+    gravity: bool,
+    grabbable: bool,
+    velocity: Option<Vec3>,
+    mass: Option<f32>,
+    restitution: Option<f32>,
+    friction: Option<f32>,
+    drag: Option<f32>,
+    point_light: Option<PointLight>,
+    spot_light: Option<SpotLight>,
+    directional_light: Option<DirectionalLight>,
+    flicker: Option<Flicker>,
+    children: Vec<usize>,
+}
+
+impl SavedEntity {
+    fn capture(world: &World, entity: Entity) -> Self {
+        Self {
+            transform: world.get::<&LocalTransform>(entity).ok().map(|t| *t),
+            mesh: world.get::<&MeshShape>(entity).ok().map(|s| *s),
+            color: world.get::<&Color>(entity).ok().map(|c| c.0),
+            checkerboard: world.get::<&Checkerboard>(entity).ok().map(|c| c.0),
+            collider: world.get::<&Collider>(entity).ok().map(|c| *c),
+            is_static: world.get::<&Static>(entity).is_ok(),
+            gravity: world.get::<&GravityAffected>(entity).is_ok(),
+            grabbable: world.get::<&Grabbable>(entity).is_ok(),
+            velocity: world.get::<&Velocity>(entity).ok().map(|v| v.0),
+            mass: world.get::<&Mass>(entity).ok().map(|m| m.0),
+            restitution: world.get::<&Restitution>(entity).ok().map(|r| r.0),
+            friction: world.get::<&Friction>(entity).ok().map(|f| f.0),
+            drag: world.get::<&Drag>(entity).ok().map(|d| d.0),
+            point_light: world.get::<&PointLight>(entity).ok().map(|l| *l),
+            spot_light: world.get::<&SpotLight>(entity).ok().map(|l| *l),
+            directional_light: world.get::<&DirectionalLight>(entity).ok().map(|l| *l),
+            flicker: world.get::<&Flicker>(entity).ok().map(|f| *f),
+            children: Vec::new(),
+        }
+    }
+
+    /// True for entities with nothing worth saving (decals, effects and other runtime-only
+    /// entities carry none of these components).
+    fn is_empty(&self) -> bool {
+        self.transform.is_none()
+            && self.mesh.is_none()
+            && self.collider.is_none()
+            && self.point_light.is_none()
+            && self.spot_light.is_none()
+            && self.directional_light.is_none()
+    }
+
+    fn spawn(&self, world: &mut World, mesh: Option<MeshHandle>) -> Entity {
+        let mut builder = EntityBuilder::new();
+        if let Some(transform) = self.transform {
+            // Exact for roots; children are corrected by the next propagation pass.
+            builder.add(transform).add(GlobalTransform(transform.matrix()));
+        }
+        if let (Some(shape), Some(handle)) = (self.mesh, mesh) {
+            builder.add(shape).add(handle);
+        }
+        if let Some(color) = self.color {
+            builder.add(Color(color));
+        }
+        if let Some(color) = self.checkerboard {
+            builder.add(Checkerboard(color));
+        }
+        if let Some(collider) = self.collider {
+            builder.add(collider);
+        }
+        if self.is_static {
+            builder.add(Static);
+        }
+        if self.gravity {
+            builder.add(GravityAffected);
+        }
+        if self.grabbable {
+            builder.add(Grabbable);
+        }
+        if let Some(velocity) = self.velocity {
+            builder.add(Velocity(velocity));
+        }
+        if let Some(mass) = self.mass {
+            builder.add(Mass(mass));
+        }
+        if let Some(restitution) = self.restitution {
+            builder.add(Restitution(restitution));
+        }
+        if let Some(friction) = self.friction {
+            builder.add(Friction(friction));
+        }
+        if let Some(drag) = self.drag {
+            builder.add(Drag(drag));
+        }
+        if let Some(light) = self.point_light {
+            builder.add(light);
+        }
+        if let Some(light) = self.spot_light {
+            builder.add(light);
+        }
+        if let Some(light) = self.directional_light {
+            builder.add(light);
+        }
+        if let Some(flicker) = self.flicker {
+            builder.add(flicker);
+        }
+        world.spawn(builder.build())
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Write `world` to `path` as RON, component by component: transforms, mesh shapes, colors,
+/// colliders, physics parameters, lights and the `Children` hierarchy. The player's
+/// character is saved as its position only and rebuilt by `load_scene`; entities with none
+/// of these components are left out.
+pub fn save_scene(world: &World, path: &str) -> io::Result<()> {
+    let player = world.query::<&Player>().iter().next().map(|(entity, _)| entity);
+    let Some(player) = player else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the world has no player"));
+    };
+    let position = world.get::<&LocalTransform>(player).map(|t| t.position).unwrap_or(Vec3::ZERO);
+    let mut skipped: HashSet<Entity> = descendants(world, player).into_iter().collect();
+    skipped.insert(player);
+
+    let mut saved = Vec::new();
+    let mut indices = HashMap::new();
+    for entity in world.iter().map(|e| e.entity()) {
+        if skipped.contains(&entity) {
+            continue;
+        }
+        let entry = SavedEntity::capture(world, entity);
+        if !entry.is_empty() {
+            indices.insert(entity, saved.len());
+            saved.push((entity, entry));
+        }
+    }
+    let entities = saved
+        .into_iter()
+        .map(|(entity, mut entry)| {
+            if let Ok(children) = world.get::<&Children>(entity) {
+                entry.children =
+                    children.0.iter().filter_map(|child| indices.get(child).copied()).collect();
+            }
+            entry
+        })
+        .collect();
+
+    let scene = SavedScene { player: position, entities };
+    let config = ron::ser::PrettyConfig::new().depth_limit(3);
+    let source = ron::ser::to_string_pretty(&scene, config)
+        .map_err(|e| invalid_data(format!("{path}: {e}")))?;
+    std::fs::write(path, source + "\n")
+}
+
+/// Read a scene written by `save_scene` into a new world. Meshes are rebuilt from their
+/// shapes into `meshes`, once per distinct shape, and the player is spawned at its saved
+/// position. Returns the world and the player entity.
+pub fn load_scene(path: &str, meshes: &mut MeshStore) -> io::Result<(World, Entity)> {
+    let source = std::fs::read_to_string(path)?;
+    let scene: SavedScene =
+        ron::from_str(&source).map_err(|e| invalid_data(format!("{path}:{e}")))?;
+    let count = scene.entities.len();
+    if let Some(child) = scene.entities.iter().flat_map(|e| &e.children).find(|&&c| c >= count) {
+        return Err(invalid_data(format!("{path}: child index {child} is out of range")));
+    }
+
+    let mut world = World::new();
+    let mut built: Vec<(MeshShape, MeshHandle)> = Vec::new();
+    let mut spawned = Vec::with_capacity(count);
+    for entry in &scene.entities {
+        let mesh = entry.mesh.map(|shape| {
+            match built.iter().find(|(built_shape, _)| *built_shape == shape) {
+                Some(&(_, handle)) => handle,
+                None => {
+                    let handle = meshes.add(create_shape(&shape));
+                    built.push((shape, handle));
+                    handle
+                }
+            }
+        });
+        spawned.push(entry.spawn(&mut world, mesh));
+    }
+    for (entry, &parent) in scene.entities.iter().zip(&spawned) {
+        for &child in &entry.children {
+            add_child(&mut world, parent, spawned[child]);
+        }
+    }
+
+    let player = spawn_player(&mut world, meshes, scene.player);
+    Ok((world, player))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::test_scene::load_test_scene;
+
+    /// Transform of the test scene's physics sphere, the only sphere collider in it.
+    fn sphere_transform(world: &World) -> LocalTransform {
+        let mut query = world.query::<(&LocalTransform, &Collider)>();
+        query
+            .iter()
+            .find(|(_, (_, collider))| matches!(collider, Collider::Sphere { .. }))
+            .map(|(_, (transform, _))| *transform)
+            .expect("the test scene has a physics sphere")
+    }
+
+    #[test]
+    fn test_scene_round_trips() {
+        let mut world = World::new();
+        let (mut meshes, _) = load_test_scene(&mut world);
+        let path = std::env::temp_dir().join(format!("lance_scene_{}.ron", std::process::id()));
+        let path = path.to_str().unwrap();
+        save_scene(&world, path).unwrap();
+        let loaded = load_scene(path, &mut meshes);
+        let _ = std::fs::remove_file(path);
+        let (loaded, player) = loaded.unwrap();
+
+        assert_eq!(loaded.len(), world.len());
+        assert_eq!(sphere_transform(&loaded), sphere_transform(&world));
+        assert!(loaded.get::<&Player>(player).is_ok());
+        // Every mesh, the player's included, can be described for the next save.
+        let untagged = loaded.query::<&MeshHandle>().without::<&MeshShape>().iter().count();
+        assert_eq!(untagged, 0);
+    }
+}
@@ -1,10 +1,8 @@
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Quat, Vec3};
 use hecs::{Entity, World};
 
 use crate::components::*;
-use crate::renderer::mesh::{
-    create_capsule, create_sphere, create_sword, create_tapered_box,
-};
+use crate::renderer::mesh::create_shape;
 use crate::renderer::MeshStore;
 
 // ---------------------------------------------------------------------------
@@ -53,6 +51,11 @@ impl CharacterRig {
         self.torso_height / 2.0 + self.head_world_radius()
     }
 
+    /// Eye level above the body (torso) centre: a little above the middle of the head.
+    fn eye_y(&self) -> f32 {
+        self.head_y() + self.head_world_radius() * 0.25
+    }
+
     /// Y offset from a parent capsule center to its child (joint connection point).
     fn joint_y(&self) -> f32 {
         -(self.limb_height / 2.0 + self.limb_height / 2.0 + self.limb_radius)
@@ -103,7 +106,6 @@ fn spawn_character(
     sword_handle: MeshHandle,
     rig: &CharacterRig,
 ) -> CharacterBody {
-    use glam::Quat;
     use std::f32::consts::FRAC_PI_2;
     use std::f32::consts::FRAC_PI_6;
 
@@ -237,6 +239,7 @@ fn spawn_character(
         right_upper_leg,
         right_lower_leg,
         sword: sword_entity,
+        eye_height: rig.eye_y(),
     }
 }
 
@@ -256,7 +259,9 @@ pub fn spawn_ground(world: &mut World, meshes: &mut MeshStore) -> Entity {
     const HALF_EXTENT: f32 = 500.0;
     const THICKNESS: f32 = 2.0;
     // Unit box: Y from -0.5 to +0.5 in local space.
-    let ground_handle = meshes.add(create_tapered_box(1.0, 1.0, 1.0, 1.0, 1.0));
+    let ground_shape =
+        MeshShape::TaperedBox { top_w: 1.0, top_d: 1.0, bot_w: 1.0, bot_d: 1.0, height: 1.0 };
+    let ground_handle = meshes.add(create_shape(&ground_shape));
     // Scale so the box covers [-HALF_EXTENT, HALF_EXTENT] in X/Z and [0, -THICKNESS] in Y.
     let mut ground_t = LocalTransform::new(Vec3::new(0.0, -THICKNESS / 2.0, 0.0));
     ground_t.scale = Vec3::new(HALF_EXTENT * 2.0, THICKNESS, HALF_EXTENT * 2.0);
@@ -264,6 +269,7 @@ pub fn spawn_ground(world: &mut World, meshes: &mut MeshStore) -> Entity {
         ground_t,
         GlobalTransform(Mat4::IDENTITY),
         ground_handle,
+        ground_shape,
         Color(Vec3::new(0.3, 0.6, 0.2)),
         Checkerboard(Vec3::new(0.22, 0.48, 0.15)),
         Collider::Plane { normal: Vec3::Y, offset: 0.0 },
@@ -282,15 +288,19 @@ pub fn spawn_physics_sphere(
     initial_vel: Vec3,
 ) -> Entity {
     let mesh_scale = collider_radius; // mesh was built at radius 1.0
-    let sphere_handle = meshes.add(create_sphere(1.0, 16, 32));
+    let sphere_shape = MeshShape::Sphere { radius: 1.0, stacks: 16, sectors: 32 };
+    let sphere_handle = meshes.add(create_shape(&sphere_shape));
 
     let mut sphere_t = LocalTransform::new(pos);
     sphere_t.scale = Vec3::splat(mesh_scale);
 
+    // A root, so this is already final; spawn queries see it before propagation runs.
+    let sphere_global = GlobalTransform(sphere_t.matrix());
     let root = world.spawn((
         sphere_t,
-        GlobalTransform(Mat4::IDENTITY),
+        sphere_global,
         sphere_handle,
+        sphere_shape,
         Color(color),
         Velocity(initial_vel),
         Mass(1.0),
@@ -309,6 +319,7 @@ pub fn spawn_physics_sphere(
         child_t,
         GlobalTransform(Mat4::IDENTITY),
         sphere_handle,
+        sphere_shape,
         Mass(1.0),
         GravityAffected,
         Color(Vec3::new(0.2, 0.4, 0.9)),
@@ -328,16 +339,22 @@ pub fn spawn_static_box(
     half_extents: Vec3,
     color: Vec3,
 ) -> Entity {
-    // create_tapered_box takes full dimensions; half_extents * 2 = full size
-    let box_handle = meshes.add(create_tapered_box(
-        half_extents.x * 2.0, half_extents.z * 2.0,
-        half_extents.x * 2.0, half_extents.z * 2.0,
-        half_extents.y * 2.0,
-    ));
+    // The tapered box takes full dimensions; half_extents * 2 = full size
+    let size = half_extents * 2.0;
+    let box_shape = MeshShape::TaperedBox {
+        top_w: size.x,
+        top_d: size.z,
+        bot_w: size.x,
+        bot_d: size.z,
+        height: size.y,
+    };
+    let box_handle = meshes.add(create_shape(&box_shape));
     world.spawn((
         LocalTransform::new(pos),
-        GlobalTransform(Mat4::IDENTITY),
+        // A root, so this is already final; spawn queries see it before propagation runs.
+        GlobalTransform(Mat4::from_translation(pos)),
         box_handle,
+        box_shape,
         Color(color),
         Collider::Box { half_extents },
         Static,
@@ -351,17 +368,23 @@ pub fn spawn_static_box(
 pub fn spawn_player(world: &mut World, meshes: &mut MeshStore, pos: Vec3) -> Entity {
     let rig = default_rig();
 
-    let torso_handle = meshes.add(create_tapered_box(
-        rig.torso_top_w, rig.torso_top_d,
-        rig.torso_bot_w, rig.torso_bot_d,
-        rig.torso_height,
-    ));
-    let upper_arm_handle = meshes.add(create_capsule(rig.limb_radius, rig.limb_height, 8, 8));
-    let forearm_handle   = meshes.add(create_capsule(rig.limb_radius, rig.limb_height, 8, 8));
-    let upper_leg_handle = meshes.add(create_capsule(rig.limb_radius, rig.limb_height, 8, 8));
-    let lower_leg_handle = meshes.add(create_capsule(rig.limb_radius, rig.limb_height, 8, 8));
-    let head_handle      = meshes.add(create_sphere(rig.head_mesh_radius, 8, 8));
-    let sword_handle     = meshes.add(create_sword());
+    let torso_shape = MeshShape::TaperedBox {
+        top_w: rig.torso_top_w, top_d: rig.torso_top_d,
+        bot_w: rig.torso_bot_w, bot_d: rig.torso_bot_d,
+        height: rig.torso_height,
+    };
+    let limb_shape = MeshShape::Capsule {
+        radius: rig.limb_radius, height: rig.limb_height, sectors: 8, stacks: 8,
+    };
+    let head_shape = MeshShape::Sphere { radius: rig.head_mesh_radius, stacks: 8, sectors: 8 };
+
+    let torso_handle     = meshes.add(create_shape(&torso_shape));
+    let upper_arm_handle = meshes.add(create_shape(&limb_shape));
+    let forearm_handle   = meshes.add(create_shape(&limb_shape));
+    let upper_leg_handle = meshes.add(create_shape(&limb_shape));
+    let lower_leg_handle = meshes.add(create_shape(&limb_shape));
+    let head_handle      = meshes.add(create_shape(&head_shape));
+    let sword_handle     = meshes.add(create_shape(&MeshShape::Sword));
 
     let mut player_t = LocalTransform::new(pos);
     player_t.scale = Vec3::splat(1.0);
@@ -382,6 +405,7 @@ pub fn spawn_player(world: &mut World, meshes: &mut MeshStore, pos: Vec3) -> Ent
         Friction(0.8),
         Player,
         GrabState::new(),
+        FootprintTrail::new(),
         // Player spawns airborne (pos.y = 10); starts in Falling so the FSM
         // is correct immediately without a dummy Grounded → Falling transition.
         PlayerFsm::new(PlayerState::Falling),
@@ -398,7 +422,36 @@ pub fn spawn_player(world: &mut World, meshes: &mut MeshStore, pos: Vec3) -> Ent
         sword_handle,
         &rig,
     );
-    world.insert_one(player_entity, body).unwrap();
+    let arm_aim = ArmAim {
+        weight: 0.0,
+        left_rest: Quat::from_rotation_z(rig.shoulder_angle),
+        right_rest: Quat::from_rotation_z(-rig.shoulder_angle),
+    };
+    let locomotion = Locomotion {
+        phase: 0.0,
+        velocity: Vec3::ZERO,
+        hips: [Vec3::new(rig.hip_x, rig.hip_y, 0.0), Vec3::new(-rig.hip_x, rig.hip_y, 0.0)],
+        hip_pivot: rig.limb_height / 2.0,
+    };
+    world.insert(player_entity, (body, arm_aim, locomotion, SwordCombo::new())).unwrap();
+
+    // Tag every body part with the shape of its mesh, as the other prefabs do.
+    let shapes = [
+        (torso_handle, torso_shape),
+        (upper_arm_handle, limb_shape),
+        (forearm_handle, limb_shape),
+        (upper_leg_handle, limb_shape),
+        (lower_leg_handle, limb_shape),
+        (head_handle, head_shape),
+        (sword_handle, MeshShape::Sword),
+    ];
+    for part in descendants(world, player_entity).into_iter().chain([player_entity]) {
+        let handle = world.get::<&MeshHandle>(part).map(|h| h.0);
+        let shape = handle.ok().and_then(|h| shapes.iter().find(|(built, _)| built.0 == h));
+        if let Some(&(_, shape)) = shape {
+            world.insert_one(part, shape).unwrap();
+        }
+    }
 
     player_entity
 }
@@ -409,12 +462,15 @@ pub fn spawn_directional_light(
     direction: Vec3,
     color: Vec3,
     intensity: f32,
+    casts_shadows: bool,
 ) -> Entity {
     world.spawn((DirectionalLight {
         direction,
         color,
         intensity,
         shadow_resolution: 2048,
+        casts_shadows,
+        shadow_distance: None,
     },))
 }
 
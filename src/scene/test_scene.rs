@@ -1,6 +1,7 @@
 use glam::Vec3;
 use hecs::{Entity, World};
 
+use crate::components::{Attenuation, DirectionalLight, Flicker, PointLight};
 use crate::renderer::MeshStore;
 use crate::scene::prefabs::{
     spawn_directional_light, spawn_ground, spawn_physics_sphere, spawn_player, spawn_point_light,
@@ -37,14 +38,35 @@ pub fn load_test_scene(world: &mut World) -> (MeshStore, Entity) {
 
     let player_entity = spawn_player(world, &mut meshes, Vec3::new(0.0, 10.0, 0.0));
 
-    spawn_directional_light(
+    let sun = spawn_directional_light(
         world,
         Vec3::new(-0.5, -1.0, -0.3),
         Vec3::new(1.0, 0.95, 0.85),
         1.0,
+        true,
+    );
+    // Pull the sun's cascades in tight: crisp nearby shadows, none in the distance.
+    if let Ok(mut light) = world.get::<&mut DirectionalLight>(sun) {
+        light.shadow_distance = Some(40.0);
+    }
+    // Cool sky fill from the opposite side; no shadows.
+    spawn_directional_light(
+        world,
+        Vec3::new(0.4, -0.6, 0.5),
+        Vec3::new(0.5, 0.6, 0.8),
+        0.25,
+        false,
     );
-    spawn_point_light(world, Vec3::new(3.0, 3.0, 0.0), Vec3::new(1.0, 0.6, 0.2), 2.0, 15.0);
-    spawn_point_light(world, Vec3::new(-4.0, 2.0, -3.0), Vec3::new(0.2, 0.4, 1.0), 1.5, 12.0);
+    // Warm point light flickers like firelight.
+    let torch =
+        spawn_point_light(world, Vec3::new(3.0, 3.0, 0.0), Vec3::new(1.0, 0.6, 0.2), 2.0, 15.0);
+    world.insert_one(torch, Flicker::new(2.0, 0.6, 1.5)).unwrap();
+    // Blue light fades smoothly to nothing at its radius.
+    let blue =
+        spawn_point_light(world, Vec3::new(-4.0, 2.0, -3.0), Vec3::new(0.2, 0.4, 1.0), 1.5, 12.0);
+    if let Ok(mut light) = world.get::<&mut PointLight>(blue) {
+        light.attenuation = Attenuation::Windowed;
+    }
     spawn_point_light(world, Vec3::new(0.0, 4.0, -8.0), Vec3::new(0.1, 0.9, 0.3), 1.8, 18.0);
     spawn_spot_light(
         world,
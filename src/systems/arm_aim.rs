@@ -0,0 +1,95 @@
+use glam::{Quat, Vec3};
+use hecs::World;
+
+use crate::camera::Camera;
+use crate::components::{world_matrix, ArmAim, CharacterBody, GrabState, LocalTransform, Player};
+
+/// How fast the aim weight eases in/out (per second).
+const AIM_BLEND_SPEED: f32 = 8.0;
+/// Maximum swing of the upper arm away from hanging straight down (radians, ~160°).
+const MAX_SHOULDER_ANGLE: f32 = 2.8;
+/// Lowest allowed player-local Z of the aim direction; keeps the hand from reaching
+/// behind the back.
+const MIN_FORWARD: f32 = -0.2;
+
+/// Point the upper arms toward the held object (both arms) or, during a throw wind-up,
+/// along the camera look direction (right arm only). A look-at overlay, not IK: each
+/// upper arm is rotated so its hanging axis (-Y) aims at the target, clamped to a
+/// believable shoulder range, and blended over the rest pose by `ArmAim::weight`.
+///
+/// Runs after `grab_throw_system` so the held object's position is current.
+pub fn arm_aim_system(world: &mut World, camera: &Camera, dt: f32) {
+    let Some((player, held, winding)) = world
+        .query::<(&Player, &GrabState)>()
+        .iter()
+        .next()
+        .map(|(e, (_p, grab))| (e, grab.held_entity, grab.is_winding))
+    else {
+        return;
+    };
+    let Ok((left_arm, right_arm)) =
+        world.get::<&CharacterBody>(player).map(|b| (b.left_upper_arm, b.right_upper_arm))
+    else {
+        return;
+    };
+
+    let player_world = world_matrix(world, player);
+    let (_scale, player_rot, _pos) = player_world.to_scale_rotation_translation();
+    let inv_player_rot = player_rot.inverse();
+
+    // Aim target as a direction per arm in player-local space.
+    let shoulder_dir = |arm: hecs::Entity, target: Vec3| -> Vec3 {
+        let shoulder = world
+            .get::<&LocalTransform>(arm)
+            .map(|lt| player_world.transform_point3(lt.position))
+            .unwrap_or(target);
+        inv_player_rot * (target - shoulder)
+    };
+    let (left_dir, right_dir) = if let Some(held) = held.filter(|&h| world.contains(h)) {
+        let target = world_matrix(world, held).w_axis.truncate();
+        (Some(shoulder_dir(left_arm, target)), Some(shoulder_dir(right_arm, target)))
+    } else if winding {
+        (None, Some(inv_player_rot * camera.front()))
+    } else {
+        (None, None)
+    };
+
+    let Ok(mut aim) = world.get::<&mut ArmAim>(player) else {
+        return;
+    };
+    let target_weight = if right_dir.is_some() { 1.0 } else { 0.0 };
+    let step = (AIM_BLEND_SPEED * dt).min(1.0);
+    aim.weight += (target_weight - aim.weight) * step;
+    let weight = aim.weight;
+    let (left_rest, right_rest) = (aim.left_rest, aim.right_rest);
+    drop(aim);
+
+    for (arm, rest, dir) in [(left_arm, left_rest, left_dir), (right_arm, right_rest, right_dir)] {
+        if let Ok(mut lt) = world.get::<&mut LocalTransform>(arm) {
+            lt.rotation = match dir {
+                Some(dir) => rest.slerp(clamped_aim(dir), weight),
+                // No target for this arm: ease from wherever it is back to rest.
+                None => lt.rotation.slerp(rest, step),
+            };
+        }
+    }
+}
+
+/// Rotation taking the arm's hanging axis (-Y) to `dir`, with the shoulder range clamped.
+fn clamped_aim(dir: Vec3) -> Quat {
+    let mut dir = dir.normalize_or_zero();
+    if dir == Vec3::ZERO {
+        return Quat::IDENTITY;
+    }
+    if dir.z < MIN_FORWARD {
+        dir.z = MIN_FORWARD;
+        dir = dir.normalize();
+    }
+    let arc = Quat::from_rotation_arc(Vec3::NEG_Y, dir);
+    let (axis, angle) = arc.to_axis_angle();
+    if angle > MAX_SHOULDER_ANGLE {
+        Quat::from_axis_angle(axis, MAX_SHOULDER_ANGLE)
+    } else {
+        arc
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::{HashMap, HashSet};
+
+use glam::{IVec3, Vec3};
+
+/// Default edge length of a grid cell (world units).
+pub const DEFAULT_CELL_SIZE: f32 = 4.0;
+/// A collider overlapping more cells than this is kept out of the grid and paired with
+/// everything instead; bucketing it would cost more than it saves.
+const MAX_CELLS_PER_ITEM: i64 = 512;
+
+/// Uniform-grid broadphase: items are bucketed by the cells their AABB overlaps, and only
+/// items sharing a cell become candidate pairs. Unbounded items (planes) and very large ones
+/// pair with every other item. Items are identified by caller-chosen indices.
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<usize>>,
+    unbounded: Vec<usize>,
+    items: usize,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(1e-3),
+            cells: HashMap::new(),
+            unbounded: Vec::new(),
+            items: 0,
+        }
+    }
+
+    /// Add item `index` covering the box `min..max`, or every cell when `bounds` is `None`.
+    pub fn insert(&mut self, index: usize, bounds: Option<(Vec3, Vec3)>) {
+        self.items = self.items.max(index + 1);
+        let Some((lo, hi)) = bounds.map(|(min, max)| (self.cell(min), self.cell(max))) else {
+            self.unbounded.push(index);
+            return;
+        };
+        let span = (hi - lo + IVec3::ONE).as_i64vec3();
+        if span.x * span.y * span.z > MAX_CELLS_PER_ITEM {
+            self.unbounded.push(index);
+            return;
+        }
+        for x in lo.x..=hi.x {
+            for y in lo.y..=hi.y {
+                for z in lo.z..=hi.z {
+                    self.cells.entry(IVec3::new(x, y, z)).or_default().push(index);
+                }
+            }
+        }
+    }
+
+    /// Every pair of items that share a cell (or involve an unbounded item), each once as
+    /// `(i, j)` with `i < j`, in ascending order — the order a brute-force double loop
+    /// would visit them in.
+    pub fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = HashSet::new();
+        for bucket in self.cells.values() {
+            for (k, &a) in bucket.iter().enumerate() {
+                for &b in &bucket[k + 1..] {
+                    pairs.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+        for &a in &self.unbounded {
+            for b in (0..self.items).filter(|&b| b != a) {
+                pairs.insert((a.min(b), a.max(b)));
+            }
+        }
+        let mut pairs: Vec<_> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Items that may overlap the box `min..max`, in ascending order. A box spanning more
+    /// than `MAX_CELLS_PER_ITEM` cells returns every item rather than walk its cells.
+    pub fn query(&self, min: Vec3, max: Vec3) -> Vec<usize> {
+        let (lo, hi) = (self.cell(min), self.cell(max));
+        let span = (hi - lo + IVec3::ONE).as_i64vec3();
+        if span.x * span.y * span.z > MAX_CELLS_PER_ITEM {
+            return (0..self.items).collect();
+        }
+        let mut found: HashSet<usize> = self.unbounded.iter().copied().collect();
+        for x in lo.x..=hi.x {
+            for y in lo.y..=hi.y {
+                for z in lo.z..=hi.z {
+                    if let Some(bucket) = self.cells.get(&IVec3::new(x, y, z)) {
+                        found.extend(bucket);
+                    }
+                }
+            }
+        }
+        let mut found: Vec<_> = found.into_iter().collect();
+        found.sort_unstable();
+        found
+    }
+
+    fn cell(&self, p: Vec3) -> IVec3 {
+        (p / self.cell_size).floor().as_ivec3()
+    }
+}
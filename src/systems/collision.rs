@@ -1,7 +1,17 @@
-use glam::Vec3;
+use std::collections::HashSet;
+
+use glam::{Quat, Vec3};
 use hecs::{Entity, World};
 
-use crate::components::{Collider, CollisionEvent, Friction, GlobalTransform, Held, LocalTransform, NoSelfCollision, Parent, Restitution, Static, Velocity};
+use crate::components::{
+    Collider, CollisionEvent, ContactChange, ContactStatus, Friction, GlobalTransform, Held,
+    LocalTransform, Mass, NoSelfCollision, Noclip, NoclipRecovery, Parent, Restitution, Static,
+    Velocity,
+};
+use crate::engine::world_up::WorldUp;
+
+use super::broadphase::{SpatialGrid, DEFAULT_CELL_SIZE};
+use super::physics::PhysicsConfig;
 
 struct ColliderEntry {
     entity: Entity,
@@ -12,9 +22,30 @@ struct ColliderEntry {
 
 enum ColliderKind {
     Sphere { radius: f32 },
-    Capsule { radius: f32, half_height: f32 },
+    /// `axis` is the unit direction of the capsule's segment in the world.
+    Capsule { radius: f32, half_height: f32, axis: Vec3 },
     Plane { normal: Vec3, offset: f32 },
-    Box { half_extents: Vec3 },
+    /// `rotation` turns the box's local axes into world axes.
+    Box { half_extents: Vec3, rotation: Quat },
+}
+
+impl ColliderKind {
+    /// World-space bounding box at `position`; `None` for unbounded planes.
+    fn aabb(&self, position: Vec3) -> Option<(Vec3, Vec3)> {
+        let half = match *self {
+            ColliderKind::Sphere { radius } => Vec3::splat(radius),
+            ColliderKind::Capsule { radius, half_height, axis } => {
+                Vec3::splat(radius) + axis.abs() * half_height
+            }
+            ColliderKind::Box { half_extents, rotation } => {
+                (rotation * Vec3::X).abs() * half_extents.x
+                    + (rotation * Vec3::Y).abs() * half_extents.y
+                    + (rotation * Vec3::Z).abs() * half_extents.z
+            }
+            ColliderKind::Plane { .. } => return None,
+        };
+        Some((position - half, position + half))
+    }
 }
 
 /// Closest point on an AABB (centered at `box_pos` with `half` extents) to point `p`.
@@ -27,6 +58,12 @@ fn closest_point_on_aabb(box_pos: Vec3, half: Vec3, p: Vec3) -> Vec3 {
     ) + box_pos
 }
 
+/// End points `(bottom, top)` of a capsule's inner segment, `half_height` either side of
+/// `center` along `axis`.
+fn capsule_segment(center: Vec3, half_height: f32, axis: Vec3) -> (Vec3, Vec3) {
+    (center - axis * half_height, center + axis * half_height)
+}
+
 fn closest_point_on_segment(a: Vec3, b: Vec3, p: Vec3) -> Vec3 {
     let ab = b - a;
     let len_sq = ab.length_squared();
@@ -37,6 +74,81 @@ fn closest_point_on_segment(a: Vec3, b: Vec3, p: Vec3) -> Vec3 {
     a + ab * t
 }
 
+/// Overlap of a sphere at `center` with an oriented box, as `(normal, depth)` with the normal
+/// pointing out of the box toward the sphere.
+fn box_vs_sphere(
+    box_pos: Vec3,
+    rotation: Quat,
+    half: Vec3,
+    center: Vec3,
+    radius: f32,
+) -> Option<(Vec3, f32)> {
+    let local = rotation.inverse() * (center - box_pos);
+    if local.abs().cmple(half).all() {
+        // Sphere center inside box — find shortest axis to push out
+        let dx = half.x - local.x.abs();
+        let dy = half.y - local.y.abs();
+        let dz = half.z - local.z.abs();
+        let (pen, normal) = if dx <= dy && dx <= dz {
+            (dx + radius, Vec3::X * local.x.signum())
+        } else if dy <= dz {
+            (dy + radius, Vec3::Y * local.y.signum())
+        } else {
+            (dz + radius, Vec3::Z * local.z.signum())
+        };
+        return Some((rotation * normal, pen));
+    }
+    let diff = local - closest_point_on_aabb(Vec3::ZERO, half, local);
+    let dist = diff.length();
+    if dist < radius {
+        let normal = if dist > 1e-6 { diff / dist } else { Vec3::Y };
+        Some((rotation * normal, radius - dist))
+    } else {
+        None
+    }
+}
+
+/// Overlap of two oriented boxes by the separating axis theorem, as `(normal, depth)` with
+/// the normal pointing from A toward B along the axis of least penetration. Tests both boxes'
+/// face normals, then the cross products of their edges; `None` once any axis separates them.
+fn box_vs_box(
+    pos_a: Vec3,
+    rot_a: Quat,
+    half_a: Vec3,
+    pos_b: Vec3,
+    rot_b: Quat,
+    half_b: Vec3,
+) -> Option<(Vec3, f32)> {
+    let axes_a = [rot_a * Vec3::X, rot_a * Vec3::Y, rot_a * Vec3::Z];
+    let axes_b = [rot_b * Vec3::X, rot_b * Vec3::Y, rot_b * Vec3::Z];
+    // Half the box's extent along `axis`.
+    let reach = |axes: &[Vec3; 3], half: Vec3, axis: Vec3| {
+        half.x * axes[0].dot(axis).abs()
+            + half.y * axes[1].dot(axis).abs()
+            + half.z * axes[2].dot(axis).abs()
+    };
+    let d = pos_b - pos_a;
+    let edges = axes_a.iter().flat_map(|&ea| axes_b.iter().map(move |&eb| ea.cross(eb)));
+    let mut best: Option<(Vec3, f32)> = None;
+    for axis in axes_a.into_iter().chain(axes_b).chain(edges) {
+        // (Nearly) parallel edges give no usable axis; the face normals already cover it.
+        if axis.length_squared() < 1e-6 {
+            continue;
+        }
+        let axis = axis.normalize();
+        let separation = d.dot(axis);
+        let overlap = reach(&axes_a, half_a, axis) + reach(&axes_b, half_b, axis)
+            - separation.abs();
+        if overlap <= 0.0 {
+            return None;
+        }
+        if best.map_or(true, |(_, depth)| overlap < depth) {
+            best = Some((axis * separation.signum(), overlap));
+        }
+    }
+    best
+}
+
 /// All returned normals point from entity_a toward entity_b.
 fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
     match (&a.collider_kind, &b.collider_kind) {
@@ -50,6 +162,7 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: b.entity,
                     contact_normal: -*normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
@@ -65,6 +178,7 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: a.entity,
                     contact_normal: -*normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
@@ -83,6 +197,7 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: b.entity,
                     contact_normal: normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
@@ -90,9 +205,11 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
         }
 
         // Capsule(A) vs Plane(B): normal = -plane_normal (toward plane)
-        (ColliderKind::Capsule { radius, half_height }, ColliderKind::Plane { normal, offset }) => {
-            let top = a.position + Vec3::Y * *half_height;
-            let bottom = a.position - Vec3::Y * *half_height;
+        (
+            ColliderKind::Capsule { radius, half_height, axis },
+            ColliderKind::Plane { normal, offset },
+        ) => {
+            let (bottom, top) = capsule_segment(a.position, *half_height, *axis);
             let dist_top = top.dot(*normal) - offset;
             let dist_bottom = bottom.dot(*normal) - offset;
             let min_dist = dist_top.min(dist_bottom);
@@ -103,15 +220,18 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: b.entity,
                     contact_normal: -*normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
             }
         }
         // Plane(A) vs Capsule(B): canonicalize so capsule=entity_a, plane=entity_b
-        (ColliderKind::Plane { normal, offset }, ColliderKind::Capsule { radius, half_height }) => {
-            let top = b.position + Vec3::Y * *half_height;
-            let bottom = b.position - Vec3::Y * *half_height;
+        (
+            ColliderKind::Plane { normal, offset },
+            ColliderKind::Capsule { radius, half_height, axis },
+        ) => {
+            let (bottom, top) = capsule_segment(b.position, *half_height, *axis);
             let dist_top = top.dot(*normal) - offset;
             let dist_bottom = bottom.dot(*normal) - offset;
             let min_dist = dist_top.min(dist_bottom);
@@ -122,6 +242,7 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: a.entity,
                     contact_normal: -*normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
@@ -129,9 +250,11 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
         }
 
         // Capsule(A) vs Sphere(B): normal from A's closest point toward B
-        (ColliderKind::Capsule { radius: cr, half_height }, ColliderKind::Sphere { radius: sr }) => {
-            let top = a.position + Vec3::Y * *half_height;
-            let bottom = a.position - Vec3::Y * *half_height;
+        (
+            ColliderKind::Capsule { radius: cr, half_height, axis },
+            ColliderKind::Sphere { radius: sr },
+        ) => {
+            let (bottom, top) = capsule_segment(a.position, *half_height, *axis);
             let closest = closest_point_on_segment(bottom, top, b.position);
             let diff = b.position - closest;
             let dist = diff.length();
@@ -143,15 +266,18 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: b.entity,
                     contact_normal: normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
             }
         }
         // Sphere(A) vs Capsule(B): normal from A toward B's closest point
-        (ColliderKind::Sphere { radius: sr }, ColliderKind::Capsule { radius: cr, half_height }) => {
-            let top = b.position + Vec3::Y * *half_height;
-            let bottom = b.position - Vec3::Y * *half_height;
+        (
+            ColliderKind::Sphere { radius: sr },
+            ColliderKind::Capsule { radius: cr, half_height, axis },
+        ) => {
+            let (bottom, top) = capsule_segment(b.position, *half_height, *axis);
             let closest = closest_point_on_segment(bottom, top, a.position);
             let diff = closest - a.position;
             let dist = diff.length();
@@ -163,6 +289,7 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: b.entity,
                     contact_normal: normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
@@ -170,13 +297,12 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
         }
 
         // --- Box collisions ---
+        // Boxes may be rotated: the tests below work in a box's local frame, where it is an
+        // AABB at the origin, and rotate the resulting normal back into the world.
 
-        // Box(A) vs Plane(B): project half-extents onto plane normal
-        (ColliderKind::Box { half_extents }, ColliderKind::Plane { normal, offset }) => {
-            let projected_radius =
-                half_extents.x * normal.x.abs() +
-                half_extents.y * normal.y.abs() +
-                half_extents.z * normal.z.abs();
+        // Box(A) vs Plane(B): project the box's (rotated) half-extents onto the plane normal
+        (ColliderKind::Box { half_extents, rotation }, ColliderKind::Plane { normal, offset }) => {
+            let projected_radius = (rotation.inverse() * *normal).abs().dot(*half_extents);
             let center_dist = a.position.dot(*normal) - offset;
             let penetration = projected_radius - center_dist;
             if penetration > 0.0 {
@@ -185,17 +311,15 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: b.entity,
                     contact_normal: -*normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
             }
         }
         // Plane(A) vs Box(B): canonicalize so box=entity_a, plane=entity_b
-        (ColliderKind::Plane { normal, offset }, ColliderKind::Box { half_extents }) => {
-            let projected_radius =
-                half_extents.x * normal.x.abs() +
-                half_extents.y * normal.y.abs() +
-                half_extents.z * normal.z.abs();
+        (ColliderKind::Plane { normal, offset }, ColliderKind::Box { half_extents, rotation }) => {
+            let projected_radius = (rotation.inverse() * *normal).abs().dot(*half_extents);
             let center_dist = b.position.dot(*normal) - offset;
             let penetration = projected_radius - center_dist;
             if penetration > 0.0 {
@@ -204,207 +328,87 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
                     entity_b: a.entity,
                     contact_normal: -*normal,
                     penetration_depth: penetration,
+                    relative_normal_velocity: 0.0,
                 })
             } else {
                 None
             }
         }
 
-        // Box(A) vs Sphere(B): clamp sphere center to box, check distance
-        (ColliderKind::Box { half_extents }, ColliderKind::Sphere { radius }) => {
-            let closest = closest_point_on_aabb(a.position, *half_extents, b.position);
-            let diff = b.position - closest;
-            let dist = diff.length();
-            // Check if sphere center is inside the box
-            let local = b.position - a.position;
-            let inside = local.x.abs() <= half_extents.x
-                && local.y.abs() <= half_extents.y
-                && local.z.abs() <= half_extents.z;
-            if inside {
-                // Sphere center inside box — find shortest axis to push out
-                let dx = half_extents.x - local.x.abs();
-                let dy = half_extents.y - local.y.abs();
-                let dz = half_extents.z - local.z.abs();
-                let (pen, normal) = if dx <= dy && dx <= dz {
-                    (dx + radius, Vec3::X * local.x.signum())
-                } else if dy <= dz {
-                    (dy + radius, Vec3::Y * local.y.signum())
-                } else {
-                    (dz + radius, Vec3::Z * local.z.signum())
-                };
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: normal,
-                    penetration_depth: pen,
-                })
-            } else if dist < *radius {
-                let normal = if dist > 1e-6 { diff / dist } else { Vec3::Y };
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: normal,
-                    penetration_depth: radius - dist,
-                })
-            } else {
-                None
-            }
+        // Box(A) vs Sphere(B): the box's normal already points A→B
+        (ColliderKind::Box { half_extents, rotation }, ColliderKind::Sphere { radius }) => {
+            let (normal, penetration) =
+                box_vs_sphere(a.position, *rotation, *half_extents, b.position, *radius)?;
+            Some(CollisionEvent {
+                entity_a: a.entity,
+                entity_b: b.entity,
+                contact_normal: normal,
+                penetration_depth: penetration,
+                relative_normal_velocity: 0.0,
+            })
         }
-        // Sphere(A) vs Box(B): swap and negate normal
-        (ColliderKind::Sphere { radius }, ColliderKind::Box { half_extents }) => {
-            let closest = closest_point_on_aabb(b.position, *half_extents, a.position);
-            let diff = a.position - closest;
-            let dist = diff.length();
-            let local = a.position - b.position;
-            let inside = local.x.abs() <= half_extents.x
-                && local.y.abs() <= half_extents.y
-                && local.z.abs() <= half_extents.z;
-            if inside {
-                let dx = half_extents.x - local.x.abs();
-                let dy = half_extents.y - local.y.abs();
-                let dz = half_extents.z - local.z.abs();
-                let (pen, axis_normal) = if dx <= dy && dx <= dz {
-                    (dx + radius, Vec3::X * local.x.signum())
-                } else if dy <= dz {
-                    (dy + radius, Vec3::Y * local.y.signum())
-                } else {
-                    (dz + radius, Vec3::Z * local.z.signum())
-                };
-                // Normal points A→B, so negate (axis_normal points sphere outward from box)
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: -axis_normal,
-                    penetration_depth: pen,
-                })
-            } else if dist < *radius {
-                // Normal from A toward B: -(diff/dist) since diff = A - closest_on_B
-                let normal = if dist > 1e-6 { -diff / dist } else { Vec3::Y };
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: normal,
-                    penetration_depth: radius - dist,
-                })
-            } else {
-                None
-            }
+        // Sphere(A) vs Box(B): the box's normal points B→A, so negate it
+        (ColliderKind::Sphere { radius }, ColliderKind::Box { half_extents, rotation }) => {
+            let (normal, penetration) =
+                box_vs_sphere(b.position, *rotation, *half_extents, a.position, *radius)?;
+            Some(CollisionEvent {
+                entity_a: a.entity,
+                entity_b: b.entity,
+                contact_normal: -normal,
+                penetration_depth: penetration,
+                relative_normal_velocity: 0.0,
+            })
         }
 
         // Box(A) vs Capsule(B): closest point on capsule segment, then treat as box-vs-sphere
-        (ColliderKind::Box { half_extents }, ColliderKind::Capsule { radius: cr, half_height }) => {
-            let top = b.position + Vec3::Y * *half_height;
-            let bottom = b.position - Vec3::Y * *half_height;
+        (
+            ColliderKind::Box { half_extents, rotation },
+            ColliderKind::Capsule { radius: cr, half_height, axis },
+        ) => {
+            let (bottom, top) = capsule_segment(b.position, *half_height, *axis);
             // Find the point on the capsule segment closest to the box center
             let seg_closest = closest_point_on_segment(bottom, top, a.position);
-            // Now test box vs sphere centered at seg_closest with radius cr
-            let closest = closest_point_on_aabb(a.position, *half_extents, seg_closest);
-            let diff = seg_closest - closest;
-            let dist = diff.length();
-            let local = seg_closest - a.position;
-            let inside = local.x.abs() <= half_extents.x
-                && local.y.abs() <= half_extents.y
-                && local.z.abs() <= half_extents.z;
-            if inside {
-                let dx = half_extents.x - local.x.abs();
-                let dy = half_extents.y - local.y.abs();
-                let dz = half_extents.z - local.z.abs();
-                let (pen, normal) = if dx <= dy && dx <= dz {
-                    (dx + cr, Vec3::X * local.x.signum())
-                } else if dy <= dz {
-                    (dy + cr, Vec3::Y * local.y.signum())
-                } else {
-                    (dz + cr, Vec3::Z * local.z.signum())
-                };
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: normal,
-                    penetration_depth: pen,
-                })
-            } else if dist < *cr {
-                let normal = if dist > 1e-6 { diff / dist } else { Vec3::Y };
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: normal,
-                    penetration_depth: cr - dist,
-                })
-            } else {
-                None
-            }
+            let (normal, penetration) =
+                box_vs_sphere(a.position, *rotation, *half_extents, seg_closest, *cr)?;
+            Some(CollisionEvent {
+                entity_a: a.entity,
+                entity_b: b.entity,
+                contact_normal: normal,
+                penetration_depth: penetration,
+                relative_normal_velocity: 0.0,
+            })
         }
-        // Capsule(A) vs Box(B): swap
-        (ColliderKind::Capsule { radius: cr, half_height }, ColliderKind::Box { half_extents }) => {
-            let top = a.position + Vec3::Y * *half_height;
-            let bottom = a.position - Vec3::Y * *half_height;
+        // Capsule(A) vs Box(B): swap, negating the box's outward normal for A→B
+        (
+            ColliderKind::Capsule { radius: cr, half_height, axis },
+            ColliderKind::Box { half_extents, rotation },
+        ) => {
+            let (bottom, top) = capsule_segment(a.position, *half_height, *axis);
             let seg_closest = closest_point_on_segment(bottom, top, b.position);
-            let closest = closest_point_on_aabb(b.position, *half_extents, seg_closest);
-            let diff = seg_closest - closest;
-            let dist = diff.length();
-            let local = seg_closest - b.position;
-            let inside = local.x.abs() <= half_extents.x
-                && local.y.abs() <= half_extents.y
-                && local.z.abs() <= half_extents.z;
-            if inside {
-                let dx = half_extents.x - local.x.abs();
-                let dy = half_extents.y - local.y.abs();
-                let dz = half_extents.z - local.z.abs();
-                let (pen, axis_normal) = if dx <= dy && dx <= dz {
-                    (dx + cr, Vec3::X * local.x.signum())
-                } else if dy <= dz {
-                    (dy + cr, Vec3::Y * local.y.signum())
-                } else {
-                    (dz + cr, Vec3::Z * local.z.signum())
-                };
-                // Normal points A→B: capsule segment is "A-side", box is "B-side"
-                // axis_normal points capsule outward from box, so negate for A→B
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: -axis_normal,
-                    penetration_depth: pen,
-                })
-            } else if dist < *cr {
-                // diff = seg_closest - closest_on_box, points from box toward capsule
-                // Normal A→B means from capsule toward box = -diff
-                let normal = if dist > 1e-6 { -diff / dist } else { Vec3::Y };
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: normal,
-                    penetration_depth: cr - dist,
-                })
-            } else {
-                None
-            }
+            let (normal, penetration) =
+                box_vs_sphere(b.position, *rotation, *half_extents, seg_closest, *cr)?;
+            Some(CollisionEvent {
+                entity_a: a.entity,
+                entity_b: b.entity,
+                contact_normal: -normal,
+                penetration_depth: penetration,
+                relative_normal_velocity: 0.0,
+            })
         }
 
-        // Box(A) vs Box(B): AABB overlap (SAT on 3 axes)
-        (ColliderKind::Box { half_extents: ha }, ColliderKind::Box { half_extents: hb }) => {
-            let d = b.position - a.position;
-            let overlap_x = (ha.x + hb.x) - d.x.abs();
-            let overlap_y = (ha.y + hb.y) - d.y.abs();
-            let overlap_z = (ha.z + hb.z) - d.z.abs();
-            if overlap_x > 0.0 && overlap_y > 0.0 && overlap_z > 0.0 {
-                // Minimum penetration axis
-                let (penetration, normal) = if overlap_x <= overlap_y && overlap_x <= overlap_z {
-                    (overlap_x, Vec3::X * d.x.signum())
-                } else if overlap_y <= overlap_z {
-                    (overlap_y, Vec3::Y * d.y.signum())
-                } else {
-                    (overlap_z, Vec3::Z * d.z.signum())
-                };
-                let normal = if normal.length_squared() < 1e-6 { Vec3::Y } else { normal };
-                Some(CollisionEvent {
-                    entity_a: a.entity,
-                    entity_b: b.entity,
-                    contact_normal: normal,
-                    penetration_depth: penetration,
-                })
-            } else {
-                None
-            }
+        // Box(A) vs Box(B): separating axis test
+        (
+            ColliderKind::Box { half_extents: ha, rotation: ra },
+            ColliderKind::Box { half_extents: hb, rotation: rb },
+        ) => {
+            let (normal, penetration) = box_vs_box(a.position, *ra, *ha, b.position, *rb, *hb)?;
+            Some(CollisionEvent {
+                entity_a: a.entity,
+                entity_b: b.entity,
+                contact_normal: normal,
+                penetration_depth: penetration,
+                relative_normal_velocity: 0.0,
+            })
         }
 
         // Plane vs Plane, Capsule vs Capsule — skip for now
@@ -412,12 +416,15 @@ fn test_pair(a: &ColliderEntry, b: &ColliderEntry) -> Option<CollisionEvent> {
     }
 }
 
-fn collider_to_kind(collider: &Collider) -> ColliderKind {
+/// `rotation` is the collider's world orientation; boxes turn with it and capsules run
+/// along its Y axis.
+fn collider_to_kind(collider: &Collider, rotation: Quat) -> ColliderKind {
     match collider {
         Collider::Sphere { radius } => ColliderKind::Sphere { radius: *radius },
         Collider::Capsule { radius, height } => ColliderKind::Capsule {
             radius: *radius,
             half_height: height * 0.5,
+            axis: rotation * Vec3::Y,
         },
         Collider::Plane { normal, offset } => ColliderKind::Plane {
             normal: *normal,
@@ -425,11 +432,94 @@ fn collider_to_kind(collider: &Collider) -> ColliderKind {
         },
         Collider::Box { half_extents } => ColliderKind::Box {
             half_extents: *half_extents,
+            rotation,
         },
     }
 }
 
-/// Query overlapping colliders for a hypothetical collider placed at `world_pos`.
+/// World orientation of a collider, from its `GlobalTransform` (dropping any scale).
+fn collider_rotation(global: &GlobalTransform) -> Quat {
+    global.0.to_scale_rotation_translation().1
+}
+
+/// Colliders read from the world once and bucketed in a `SpatialGrid`, so each query only
+/// runs the narrowphase against the colliders near it.
+struct ColliderSnapshot {
+    entries: Vec<ColliderEntry>,
+    grid: SpatialGrid,
+}
+
+impl ColliderSnapshot {
+    fn new(entries: Vec<ColliderEntry>) -> Self {
+        let mut grid = SpatialGrid::new(DEFAULT_CELL_SIZE);
+        for (i, entry) in entries.iter().enumerate() {
+            grid.insert(i, entry.collider_kind.aabb(entry.position));
+        }
+        Self { entries, grid }
+    }
+
+    /// Every collider except `Held` entities and those in `skip_entities`.
+    fn unheld(world: &World, skip_entities: &[Entity]) -> Self {
+        let entries = world
+            .query::<(&GlobalTransform, &Collider)>()
+            .without::<&Held>()
+            .iter()
+            .filter(|(entity, _)| !skip_entities.contains(entity))
+            .map(|(entity, (global, collider))| ColliderEntry {
+                entity,
+                position: global.0.w_axis.truncate(),
+                collider_kind: collider_to_kind(collider, collider_rotation(global)),
+                body_owner: None,
+            })
+            .collect();
+        Self::new(entries)
+    }
+
+    /// Colliders that may overlap the box `min..max`, in the order they were gathered.
+    fn near(&self, min: Vec3, max: Vec3) -> impl Iterator<Item = &ColliderEntry> + '_ {
+        self.grid.query(min, max).into_iter().map(move |i| &self.entries[i])
+    }
+
+    /// `(push_normal, depth, other_entity)` for each collider overlapping `test_collider`
+    /// placed at `world_pos` with `rotation`; see `query_collisions_at`.
+    fn overlaps(
+        &self,
+        test_collider: &Collider,
+        world_pos: Vec3,
+        rotation: Quat,
+    ) -> Vec<(Vec3, f32, Entity)> {
+        let test_entry = ColliderEntry {
+            entity: Entity::DANGLING,
+            position: world_pos,
+            collider_kind: collider_to_kind(test_collider, rotation),
+            body_owner: None,
+        };
+        let candidates = match test_entry.collider_kind.aabb(world_pos) {
+            Some((min, max)) => self.grid.query(min, max),
+            None => (0..self.entries.len()).collect(),
+        };
+        candidates
+            .into_iter()
+            .filter_map(|i| {
+                let other_entry = &self.entries[i];
+                let event = test_pair(&test_entry, other_entry)?;
+                // Determine push direction for test collider.
+                // test_pair may canonicalize some pairs (e.g. Plane vs Sphere) by swapping
+                // entity_a/b. When entity_a == DANGLING the test collider is A; normal points
+                // A→B so push is -normal. When entity_b == DANGLING the test collider is B;
+                // normal points A→B so push is +normal.
+                let push = if event.entity_a == Entity::DANGLING {
+                    -event.contact_normal
+                } else {
+                    event.contact_normal
+                };
+                Some((push, event.penetration_depth, other_entry.entity))
+            })
+            .collect()
+    }
+}
+
+/// Query overlapping colliders for a hypothetical, unrotated collider placed at `world_pos`.
 /// Returns `(push_normal, depth, other_entity, is_dynamic)` for each overlap found.
 /// `push_normal` is the direction to move the test collider to resolve the overlap.
 /// Skips entities in `skip_entities` and all `Held` entities.
@@ -439,43 +529,9 @@ pub fn query_collisions_at(
     world_pos: Vec3,
     skip_entities: &[Entity],
 ) -> Vec<(Vec3, f32, Entity, bool)> {
-    let test_entry = ColliderEntry {
-        entity: Entity::DANGLING,
-        position: world_pos,
-        collider_kind: collider_to_kind(test_collider),
-        body_owner: None,
-    };
-
-    // Phase 1: collect overlaps (immutable query; borrow released after collect)
-    let raw: Vec<(Vec3, f32, Entity)> = world
-        .query::<(&GlobalTransform, &Collider, Option<&Held>)>()
-        .iter()
-        .filter_map(|(entity, (global, collider, held))| {
-            if held.is_some() || skip_entities.contains(&entity) {
-                return None;
-            }
-            let other_entry = ColliderEntry {
-                entity,
-                position: global.0.w_axis.truncate(),
-                collider_kind: collider_to_kind(collider),
-                body_owner: None,
-            };
-            let event = test_pair(&test_entry, &other_entry)?;
-            // Determine push direction for test collider.
-            // test_pair may canonicalize some pairs (e.g. Plane vs Sphere) by swapping entity_a/b.
-            // When entity_a == DANGLING the test collider is A; normal points A→B so push is -normal.
-            // When entity_b == DANGLING the test collider is B; normal points A→B so push is +normal.
-            let push = if event.entity_a == Entity::DANGLING {
-                -event.contact_normal
-            } else {
-                event.contact_normal
-            };
-            Some((push, event.penetration_depth, entity))
-        })
-        .collect();
-
-    // Phase 2: tag is_dynamic (separate borrow after query is dropped)
-    raw.into_iter()
+    ColliderSnapshot::unheld(world, skip_entities)
+        .overlaps(test_collider, world_pos, Quat::IDENTITY)
+        .into_iter()
         .map(|(push, depth, entity)| {
             let is_dynamic = world.get::<&Static>(entity).is_err();
             (push, depth, entity, is_dynamic)
@@ -483,6 +539,33 @@ pub fn query_collisions_at(
         .collect()
 }
 
+/// Height step between `find_free_spawn` probes (metres).
+const SPAWN_SEARCH_STEP: f32 = 0.05;
+
+/// The lowest position from `near` up to `up_search` above it (along `up`) where `collider`,
+/// standing along `up`, would overlap nothing, so a spawned body doesn't start embedded and
+/// get launched out by the solver. Probes rise in `SPAWN_SEARCH_STEP` increments; colliders
+/// are read from their current `GlobalTransform`s. With no free spot in range, warns and
+/// returns `near` unchanged.
+pub fn find_free_spawn(
+    world: &World,
+    collider: &Collider,
+    near: Vec3,
+    up_search: f32,
+    up: WorldUp,
+) -> Vec3 {
+    let colliders = ColliderSnapshot::unheld(world, &[]);
+    let steps = (up_search.max(0.0) / SPAWN_SEARCH_STEP).ceil() as usize;
+    for step in 0..=steps {
+        let pos = near + up.vector() * (step as f32 * SPAWN_SEARCH_STEP).min(up_search);
+        if colliders.overlaps(collider, pos, up.align()).is_empty() {
+            return pos;
+        }
+    }
+    eprintln!("No free spawn within {up_search} above {near}; spawning there anyway");
+    near
+}
+
 // ---------------------------------------------------------------------------
 // Swept-sphere CCD against static geometry
 // ---------------------------------------------------------------------------
@@ -532,11 +615,16 @@ fn sweep_sphere_vs(
             }
             (t_contact / len).clamp(0.0, 1.0)
         }
-        ColliderKind::Box { half_extents } => {
+        ColliderKind::Box { half_extents, rotation } => {
+            // In the box's local frame it is an AABB at the origin; rotating the sweep there
+            // keeps its length, so `len` and the returned fraction carry over unchanged.
+            let to_local = rotation.inverse();
+            let start = to_local * (start - other_pos);
+            let dir = to_local * dir;
             // Expand AABB by sphere radius and do a ray test (Minkowski sum).
             let exp_half = *half_extents + Vec3::splat(radius);
-            let box_min = other_pos - exp_half;
-            let box_max = other_pos + exp_half;
+            let box_min = -exp_half;
+            let box_max = exp_half;
             // If start is already inside the expanded box, let overlap-resolution handle it.
             if start.x > box_min.x && start.y > box_min.y && start.z > box_min.z
                 && start.x < box_max.x && start.y < box_max.y && start.z < box_max.z
@@ -555,7 +643,7 @@ fn sweep_sphere_vs(
             }
             (entry.max(0.0) / len).clamp(0.0, 1.0)
         }
-        ColliderKind::Capsule { radius: other_r, half_height } => {
+        ColliderKind::Capsule { radius: other_r, half_height, .. } => {
             // Conservative: bounding sphere of the capsule.
             let approx_r = other_r + half_height;
             let combined_r = radius + approx_r;
@@ -594,17 +682,25 @@ pub fn sweep_sphere_static(
     }
     let dir = delta / len;
 
-    let entries: Vec<(Vec3, ColliderKind)> = world
+    let entries: Vec<ColliderEntry> = world
         .query::<(&Static, &GlobalTransform, &Collider)>()
         .iter()
         .filter(|(entity, _)| !skip_entities.contains(entity))
-        .map(|(_, (_, global, collider))| {
-            (global.0.w_axis.truncate(), collider_to_kind(collider))
+        .map(|(entity, (_, global, collider))| ColliderEntry {
+            entity,
+            position: global.0.w_axis.truncate(),
+            collider_kind: collider_to_kind(collider, collider_rotation(global)),
+            body_owner: None,
         })
         .collect();
 
-    entries.iter().fold(1.0_f32, |t_min, (other_pos, kind)| {
-        t_min.min(sweep_sphere_vs(radius, start, dir, len, *other_pos, kind))
+    // Only colliders near the swept sphere's bounding box can stop it. (A static capsule's
+    // bounding-sphere test could report hits outside its box; those are now culled.)
+    let end = start + delta;
+    let (min, max) = (start.min(end) - Vec3::splat(radius), start.max(end) + Vec3::splat(radius));
+    ColliderSnapshot::new(entries).near(min, max).fold(1.0_f32, |t_min, entry| {
+        let t = sweep_sphere_vs(radius, start, dir, len, entry.position, &entry.collider_kind);
+        t_min.min(t)
     })
 }
 
@@ -617,10 +713,48 @@ fn find_physics_root(world: &World, entity: Entity) -> Entity {
     current
 }
 
-const REST_VELOCITY_THRESHOLD: f32 = 0.5;
+/// Maximum positional correction per step (metres) for a body recovering from noclip.
+const NOCLIP_RECOVERY_PUSH: f32 = 0.05;
+
+/// Positional correction to apply to `root`: the full `depth`, or a capped amount while the
+/// body is easing out of geometry after noclip.
+fn correction_depth(world: &World, root: Entity, depth: f32) -> f32 {
+    if world.get::<&NoclipRecovery>(root).is_ok() {
+        depth.min(NOCLIP_RECOVERY_PUSH)
+    } else {
+        depth
+    }
+}
+
+const DEFAULT_MASS: f32 = 1.0;
+/// Lightest mass honoured; keeps a zero or negative `Mass` from producing an infinite
+/// inverse.
+const MIN_MASS: f32 = 1e-3;
 const DEFAULT_RESTITUTION: f32 = 0.3;
 const DEFAULT_FRICTION: f32 = 0.5;
 const PHYSICS_DT: f32 = 1.0 / 60.0;
+/// Position-solver passes per tick. The first resolves every contact found this tick; the
+/// rest re-test with corrected positions, so a body wedged between two others isn't left
+/// pushed into one by the correction from the other.
+const POSITION_ITERATIONS: usize = 3;
+/// Penetration below this (metres) doesn't warrant another solver pass.
+const POSITION_SLOP: f32 = 1e-4;
+
+/// 1 / mass of a dynamic physics root, from its `Mass` (`DEFAULT_MASS` without one).
+fn inverse_mass(world: &World, root: Entity) -> f32 {
+    let mass = world.get::<&Mass>(root).map(|m| m.0).unwrap_or(DEFAULT_MASS);
+    1.0 / mass.max(MIN_MASS)
+}
+
+/// Restitution applied at closing speed `speed`: none below `config.rest_threshold`, the
+/// full `e` from `rest_threshold + restitution_falloff` up, and a smoothstep between, so a
+/// ball's last bounces shrink away instead of ending on a hop. A zero falloff is the hard
+/// cutoff.
+fn effective_restitution(e: f32, speed: f32, config: &PhysicsConfig) -> f32 {
+    let t = (speed - config.rest_threshold) / config.restitution_falloff.max(1e-6);
+    let t = t.clamp(0.0, 1.0);
+    e * t * t * (3.0 - 2.0 * t)
+}
 
 /// Apply Coulomb friction: reduce tangential velocity proportional to normal impulse.
 /// Clamps so friction never reverses the sliding direction.
@@ -640,172 +774,571 @@ fn apply_friction(vel: &mut Vec3, normal: Vec3, mu: f32, normal_impulse: f32) {
 /// contact_normal convention: always points from entity_a toward entity_b.
 /// - To push A out of B: move A along -normal
 /// - To push B out of A: move B along +normal
-pub fn collision_system(world: &mut World) -> Vec<CollisionEvent> {
+///
+/// Returns the tick's contacts and how many impulses the velocity solver applied over all
+/// its passes.
+pub fn collision_system(
+    world: &mut World,
+    config: &PhysicsConfig,
+) -> (Vec<CollisionEvent>, usize) {
     // Gather all collider entries
-    let entries: Vec<ColliderEntry> = world
+    let mut entries: Vec<ColliderEntry> = world
         .query_mut::<(&GlobalTransform, &Collider, Option<&NoSelfCollision>)>()
         .into_iter()
-        .map(|(entity, (global, collider, nsc))| {
-            let kind = match collider {
-                Collider::Sphere { radius } => ColliderKind::Sphere { radius: *radius },
-                Collider::Capsule { radius, height } => ColliderKind::Capsule {
-                    radius: *radius,
-                    half_height: height * 0.5,
-                },
-                Collider::Plane { normal, offset } => ColliderKind::Plane {
-                    normal: *normal,
-                    offset: *offset,
-                },
-                Collider::Box { half_extents } => ColliderKind::Box {
-                    half_extents: *half_extents,
-                },
-            };
-            ColliderEntry {
-                entity,
-                position: global.0.w_axis.truncate(),
-                collider_kind: kind,
-                body_owner: nsc.map(|n| n.0),
-            }
+        .map(|(entity, (global, collider, nsc))| ColliderEntry {
+            entity,
+            position: global.0.w_axis.truncate(),
+            collider_kind: collider_to_kind(collider, collider_rotation(global)),
+            body_owner: nsc.map(|n| n.0),
         })
         .collect();
 
-    // Broadphase: brute force O(n²)
-    let mut events = Vec::new();
-    for i in 0..entries.len() {
-        for j in (i + 1)..entries.len() {
-            // Skip self-collision between body parts of the same character
-            if let (Some(owner_a), Some(owner_b)) = (entries[i].body_owner, entries[j].body_owner) {
-                if owner_a == owner_b {
-                    continue;
-                }
-            }
-            if let Some(event) = test_pair(&entries[i], &entries[j]) {
-                events.push(event);
+    // Query order follows archetype layout, which shifts as components are added/removed;
+    // pairing and response order both depend on it.
+    if config.deterministic {
+        entries.sort_by_key(|entry| entry.entity);
+    }
+
+    // Noclip bodies (and their body parts) don't participate in collision at all.
+    let mut entries: Vec<ColliderEntry> = entries
+        .into_iter()
+        .filter(|entry| world.get::<&Noclip>(find_physics_root(world, entry.entity)).is_err())
+        .collect();
+
+    let mut events = find_contacts(&entries, config.broadphase_cell_size);
+
+    // Approach speed from the bodies' velocities before this tick's response changes them.
+    for event in &mut events {
+        let vel_of = |e: Entity| {
+            let root = find_physics_root(world, e);
+            world.get::<&Velocity>(root).map(|v| v.0).unwrap_or(Vec3::ZERO)
+        };
+        let relative = vel_of(event.entity_a) - vel_of(event.entity_b);
+        event.relative_normal_velocity = relative.dot(event.contact_normal);
+    }
+
+    // Position solve. Corrections only ever translate physics roots, so later passes shift
+    // each collider by how far its root has moved this tick instead of re-reading transforms.
+    let roots: Vec<Entity> = entries.iter().map(|e| find_physics_root(world, e.entity)).collect();
+    let root_pos =
+        |world: &World, root: Entity| world.get::<&LocalTransform>(root).map(|lt| lt.position);
+    let start: Vec<(Vec3, Vec3)> = entries
+        .iter()
+        .zip(&roots)
+        .map(|(entry, &root)| (entry.position, root_pos(world, root).unwrap_or(Vec3::ZERO)))
+        .collect();
+
+    resolve_penetrations(world, &events, true);
+    for _ in 1..POSITION_ITERATIONS {
+        for ((entry, &root), &(base, root_start)) in entries.iter_mut().zip(&roots).zip(&start) {
+            if let Ok(pos) = root_pos(world, root) {
+                entry.position = base + (pos - root_start);
             }
         }
+        let remaining: Vec<CollisionEvent> = find_contacts(&entries, config.broadphase_cell_size)
+            .into_iter()
+            .filter(|event| event.penetration_depth > POSITION_SLOP)
+            .collect();
+        if remaining.is_empty() {
+            break;
+        }
+        resolve_penetrations(world, &remaining, false);
     }
 
-    // Response — normal points from A to B in all cases
-    for event in &events {
-        // Held entities are kinematic: they block dynamic entities but aren't moved by collisions.
-        let a_held = world.get::<&Held>(event.entity_a).is_ok();
-        let b_held = world.get::<&Held>(event.entity_b).is_ok();
-        if a_held && b_held {
-            continue;
+    // Velocity response, against the contacts found at the start of the tick. Each extra
+    // pass revisits the contacts still closing after their neighbours' impulses, which a
+    // stack needs to settle; it stops early once a pass has nothing left to do.
+    let mut resolved = 0;
+    for _ in 0..config.solver_iterations.max(1) {
+        let applied = events.iter().filter(|event| resolve_velocity(world, event, config)).count();
+        if applied == 0 {
+            break;
         }
-        // For held entities don't walk up to the player root; treat the entity itself as the
-        // kinematic obstacle (so its position is the held object's position, not the player's).
-        let root_a = if !a_held { find_physics_root(world, event.entity_a) } else { event.entity_a };
-        let root_b = if !b_held { find_physics_root(world, event.entity_b) } else { event.entity_b };
-        let a_static = a_held || world.get::<&Static>(root_a).is_ok();
-        let b_static = b_held || world.get::<&Static>(root_b).is_ok();
+        resolved += applied;
+    }
 
-        if a_static && b_static {
-            continue;
+    (events, resolved)
+}
+
+/// Broadphase on a uniform grid of `cell_size` cells, then the narrow phase on each
+/// candidate pair. Pairs are tested in the same order as a brute-force double loop, so the
+/// events come out identically.
+fn find_contacts(entries: &[ColliderEntry], cell_size: f32) -> Vec<CollisionEvent> {
+    let mut grid = SpatialGrid::new(cell_size);
+    for (i, entry) in entries.iter().enumerate() {
+        grid.insert(i, entry.collider_kind.aabb(entry.position));
+    }
+    let mut events = Vec::new();
+    for (i, j) in grid.candidate_pairs() {
+        // Skip self-collision between body parts of the same character
+        if let (Some(owner_a), Some(owner_b)) = (entries[i].body_owner, entries[j].body_owner) {
+            if owner_a == owner_b {
+                continue;
+            }
+        }
+        if let Some(event) = test_pair(&entries[i], &entries[j]) {
+            events.push(event);
         }
+    }
+    events
+}
+
+/// Physics roots of both sides of a contact and whether each is immovable, or `None` when
+/// neither side can move.
+fn contact_bodies(world: &World, event: &CollisionEvent) -> Option<(Entity, Entity, bool, bool)> {
+    // Held entities are kinematic: they block dynamic entities but aren't moved by collisions.
+    let a_held = world.get::<&Held>(event.entity_a).is_ok();
+    let b_held = world.get::<&Held>(event.entity_b).is_ok();
+    if a_held && b_held {
+        return None;
+    }
+    // For held entities don't walk up to the player root; treat the entity itself as the
+    // kinematic obstacle (so its position is the held object's position, not the player's).
+    let root_a = if !a_held { find_physics_root(world, event.entity_a) } else { event.entity_a };
+    let root_b = if !b_held { find_physics_root(world, event.entity_b) } else { event.entity_b };
+    let a_static = a_held || world.get::<&Static>(root_a).is_ok();
+    let b_static = b_held || world.get::<&Static>(root_b).is_ok();
 
-        let restitution_a = world
-            .get::<&Restitution>(root_a)
-            .map(|r| r.0)
-            .unwrap_or(DEFAULT_RESTITUTION);
-        let restitution_b = world
-            .get::<&Restitution>(root_b)
-            .map(|r| r.0)
-            .unwrap_or(DEFAULT_RESTITUTION);
-        let e = (restitution_a + restitution_b) * 0.5;
-
-        let friction_a = world
-            .get::<&Friction>(root_a)
-            .map(|f| f.0)
-            .unwrap_or(DEFAULT_FRICTION);
-        let friction_b = world
-            .get::<&Friction>(root_b)
-            .map(|f| f.0)
-            .unwrap_or(DEFAULT_FRICTION);
-        let mu = (friction_a + friction_b) * 0.5;
+    if a_static && b_static {
+        return None;
+    }
+    Some((root_a, root_b, a_static, b_static))
+}
 
+/// Push dynamic bodies out of each other. Static and held bodies never move.
+/// `first_pass` is false for the extra solver passes, where bodies easing out of geometry
+/// after noclip are left alone so their per-tick push cap still holds.
+fn resolve_penetrations(world: &mut World, events: &[CollisionEvent], first_pass: bool) {
+    for event in events {
+        let Some((root_a, root_b, a_static, b_static)) = contact_bodies(world, event) else {
+            continue;
+        };
         let n = event.contact_normal;
         let depth = event.penetration_depth;
+        let push = |world: &World, root: Entity, depth: f32| {
+            if !first_pass && world.get::<&NoclipRecovery>(root).is_ok() {
+                0.0
+            } else {
+                correction_depth(world, root, depth)
+            }
+        };
 
         if a_static {
             // A is static, B is dynamic — push B's root away from A (along +normal)
-            let phys_b = find_physics_root(world, event.entity_b);
-            if let Ok(mut local) = world.get::<&mut LocalTransform>(phys_b) {
-                local.position += n * depth;
-            }
-            if let Ok(mut vel) = world.get::<&mut Velocity>(phys_b) {
-                let vel_along_n = vel.0.dot(n);
-                // Negative = B moving toward A (into collision)
-                if vel_along_n < 0.0 {
-                    let normal_impulse = if vel_along_n.abs() < REST_VELOCITY_THRESHOLD {
-                        vel.0 -= vel_along_n * n;
-                        vel_along_n.abs()
-                    } else {
-                        vel.0 -= (1.0 + e) * vel_along_n * n;
-                        (1.0 + e) * vel_along_n.abs()
-                    };
-
-                    // Coulomb friction: reduce tangential velocity
-                    apply_friction(&mut vel.0, n, mu, normal_impulse);
-                }
+            let push_b = push(world, root_b, depth);
+            if let Ok(mut local) = world.get::<&mut LocalTransform>(root_b) {
+                local.position += n * push_b;
             }
         } else if b_static {
             // B is static, A is dynamic — push A's root away from B (along -normal)
-            let phys_a = find_physics_root(world, event.entity_a);
-            if let Ok(mut local) = world.get::<&mut LocalTransform>(phys_a) {
-                local.position -= n * depth;
-            }
-            if let Ok(mut vel) = world.get::<&mut Velocity>(phys_a) {
-                let vel_along_n = vel.0.dot(n);
-                // Positive = A moving toward B (into collision)
-                if vel_along_n > 0.0 {
-                    let normal_impulse = if vel_along_n < REST_VELOCITY_THRESHOLD {
-                        vel.0 -= vel_along_n * n;
-                        vel_along_n
-                    } else {
-                        vel.0 -= (1.0 + e) * vel_along_n * n;
-                        (1.0 + e) * vel_along_n
-                    };
-
-                    // Coulomb friction: reduce tangential velocity
-                    apply_friction(&mut vel.0, n, mu, normal_impulse);
-                }
+            let push_a = push(world, root_a, depth);
+            if let Ok(mut local) = world.get::<&mut LocalTransform>(root_a) {
+                local.position -= n * push_a;
             }
         } else {
-            // Both dynamic — split push 50/50, redirect to physics roots
-            let phys_a = find_physics_root(world, event.entity_a);
-            let phys_b = find_physics_root(world, event.entity_b);
-
-            if let Ok(mut local) = world.get::<&mut LocalTransform>(phys_a) {
-                local.position -= n * (depth * 0.5);
+            // Both dynamic — split the push by inverse mass, so the heavier body moves less
+            let inv_a = inverse_mass(world, root_a);
+            let inv_b = inverse_mass(world, root_b);
+            let share_a = inv_a / (inv_a + inv_b);
+            let push_a = push(world, root_a, depth * share_a);
+            let push_b = push(world, root_b, depth * (1.0 - share_a));
+            if let Ok(mut local) = world.get::<&mut LocalTransform>(root_a) {
+                local.position -= n * push_a;
             }
-            if let Ok(mut local) = world.get::<&mut LocalTransform>(phys_b) {
-                local.position += n * (depth * 0.5);
+            if let Ok(mut local) = world.get::<&mut LocalTransform>(root_b) {
+                local.position += n * push_b;
             }
+        }
+    }
+}
+
+/// Impulse response for one contact: restitution along the normal plus Coulomb friction.
+/// Returns whether the bodies were still closing, i.e. whether an impulse was applied.
+fn resolve_velocity(world: &mut World, event: &CollisionEvent, config: &PhysicsConfig) -> bool {
+    let Some((root_a, root_b, a_static, b_static)) = contact_bodies(world, event) else {
+        return false;
+    };
+
+    let restitution_a = world
+        .get::<&Restitution>(root_a)
+        .map(|r| r.0)
+        .unwrap_or(DEFAULT_RESTITUTION);
+    let restitution_b = world
+        .get::<&Restitution>(root_b)
+        .map(|r| r.0)
+        .unwrap_or(DEFAULT_RESTITUTION);
+    let e = (restitution_a + restitution_b) * 0.5;
+
+    let friction_a = world
+        .get::<&Friction>(root_a)
+        .map(|f| f.0)
+        .unwrap_or(DEFAULT_FRICTION);
+    let friction_b = world
+        .get::<&Friction>(root_b)
+        .map(|f| f.0)
+        .unwrap_or(DEFAULT_FRICTION);
+    let mu = (friction_a + friction_b) * 0.5;
 
-            let vel_a = world.get::<&Velocity>(phys_a).map(|v| v.0).unwrap_or(Vec3::ZERO);
-            let vel_b = world.get::<&Velocity>(phys_b).map(|v| v.0).unwrap_or(Vec3::ZERO);
-            let relative_vel = vel_a - vel_b;
-            let vel_along_n = relative_vel.dot(n);
+    let n = event.contact_normal;
 
-            // Positive = A approaching B
+    if a_static {
+        if let Ok(mut vel) = world.get::<&mut Velocity>(root_b) {
+            let vel_along_n = vel.0.dot(n);
+            // Negative = B moving toward A (into collision)
+            if vel_along_n < 0.0 {
+                let bounce = 1.0 + effective_restitution(e, vel_along_n.abs(), config);
+                vel.0 -= bounce * vel_along_n * n;
+                let normal_impulse = bounce * vel_along_n.abs();
+
+                // Coulomb friction: reduce tangential velocity
+                apply_friction(&mut vel.0, n, mu, normal_impulse);
+                return true;
+            }
+        }
+        false
+    } else if b_static {
+        if let Ok(mut vel) = world.get::<&mut Velocity>(root_a) {
+            let vel_along_n = vel.0.dot(n);
+            // Positive = A moving toward B (into collision)
             if vel_along_n > 0.0 {
-                let impulse = if vel_along_n < REST_VELOCITY_THRESHOLD {
-                    vel_along_n * 0.5
-                } else {
-                    (1.0 + e) * vel_along_n * 0.5
-                };
-                if let Ok(mut vel) = world.get::<&mut Velocity>(phys_a) {
-                    vel.0 -= impulse * n;
-                    apply_friction(&mut vel.0, n, mu, impulse);
-                }
-                if let Ok(mut vel) = world.get::<&mut Velocity>(phys_b) {
-                    vel.0 += impulse * n;
-                    apply_friction(&mut vel.0, n, mu, impulse);
-                }
+                let bounce = 1.0 + effective_restitution(e, vel_along_n, config);
+                vel.0 -= bounce * vel_along_n * n;
+                let normal_impulse = bounce * vel_along_n;
+
+                // Coulomb friction: reduce tangential velocity
+                apply_friction(&mut vel.0, n, mu, normal_impulse);
+                return true;
             }
         }
+        false
+    } else {
+        let vel_a = world.get::<&Velocity>(root_a).map(|v| v.0).unwrap_or(Vec3::ZERO);
+        let vel_b = world.get::<&Velocity>(root_b).map(|v| v.0).unwrap_or(Vec3::ZERO);
+        let relative_vel = vel_a - vel_b;
+        let vel_along_n = relative_vel.dot(n);
+
+        // Positive = A approaching B
+        if vel_along_n > 0.0 {
+            // j = (1 + e)·v_rel·n / (1/m_a + 1/m_b); each body's velocity changes by j/m.
+            let inv_a = inverse_mass(world, root_a);
+            let inv_b = inverse_mass(world, root_b);
+            let bounce = 1.0 + effective_restitution(e, vel_along_n, config);
+            let impulse = bounce * vel_along_n / (inv_a + inv_b);
+            if let Ok(mut vel) = world.get::<&mut Velocity>(root_a) {
+                vel.0 -= impulse * inv_a * n;
+                apply_friction(&mut vel.0, n, mu, impulse * inv_a);
+            }
+            if let Ok(mut vel) = world.get::<&mut Velocity>(root_b) {
+                vel.0 += impulse * inv_b * n;
+                apply_friction(&mut vel.0, n, mu, impulse * inv_b);
+            }
+            return true;
+        }
+        false
     }
+}
 
-    events
+// ---------------------------------------------------------------------------
+// Contact persistence
+// ---------------------------------------------------------------------------
+
+/// Remembers which collider pairs touched last tick so contacts can be reported as
+/// began / persisted / ended. `collision_system` events carry no identity of their own;
+/// this keys them by unordered entity pair. Call `update` once per physics tick with that
+/// tick's events.
+pub struct ContactTracker {
+    active: HashSet<(Entity, Entity)>,
+}
+
+impl ContactTracker {
+    pub fn new() -> Self {
+        Self { active: HashSet::new() }
+    }
+
+    /// Number of pairs in contact as of the last `update`.
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Classify this tick's contacts against the previous tick's. Emits one change per
+    /// distinct pair in `events` (duplicates collapse into one), plus an `Ended` change for
+    /// every pair that is no longer touching. A despawned entity produces no events, so
+    /// its contacts end on the next update.
+    pub fn update(&mut self, events: &[CollisionEvent]) -> Vec<ContactChange> {
+        let mut changes = Vec::new();
+        let mut current = HashSet::with_capacity(events.len());
+        for event in events {
+            let (a, b) = pair_key(event.entity_a, event.entity_b);
+            if !current.insert((a, b)) {
+                continue;
+            }
+            let status = if self.active.contains(&(a, b)) {
+                ContactStatus::Persisted
+            } else {
+                ContactStatus::Began
+            };
+            changes.push(ContactChange { entity_a: a, entity_b: b, status });
+        }
+        for &(a, b) in self.active.difference(&current) {
+            changes.push(ContactChange { entity_a: a, entity_b: b, status: ContactStatus::Ended });
+        }
+        self.active = current;
+        changes
+    }
+}
+
+/// Order a pair so (a, b) and (b, a) share a key.
+fn pair_key(a: Entity, b: Entity) -> (Entity, Entity) {
+    if a.to_bits() <= b.to_bits() { (a, b) } else { (b, a) }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+
+    use super::*;
+    use crate::components::GravityAffected;
+    use crate::systems::{physics_step, transform_propagation_system, TransformGuard};
+
+    fn spawn_sphere(world: &mut World, position: Vec3, velocity: Vec3, mass: f32) -> Entity {
+        world.spawn((
+            LocalTransform::new(position),
+            GlobalTransform(Mat4::from_translation(position)),
+            Collider::Sphere { radius: 0.5 },
+            Velocity(velocity),
+            Mass(mass),
+        ))
+    }
+
+    #[test]
+    fn head_on_impulse_is_shared_by_inverse_mass() {
+        let mut world = World::new();
+        let heavy = spawn_sphere(&mut world, Vec3::new(-0.45, 0.0, 0.0), Vec3::X * 2.0, 10.0);
+        let light = spawn_sphere(&mut world, Vec3::new(0.45, 0.0, 0.0), Vec3::X * -2.0, 1.0);
+
+        let (events, resolved) = collision_system(&mut world, &PhysicsConfig::new());
+        assert_eq!(events.len(), 1);
+        assert!(resolved > 0);
+
+        let change = |entity: Entity, before: Vec3| {
+            (world.get::<&Velocity>(entity).unwrap().0 - before).length()
+        };
+        let heavy_change = change(heavy, Vec3::X * 2.0);
+        let light_change = change(light, Vec3::X * -2.0);
+        assert!(heavy_change > 0.0);
+        assert!((light_change / heavy_change - 10.0).abs() < 1e-3);
+    }
+
+    /// Drop a loose pile of spheres onto a floor for two seconds with `config` and return
+    /// where each ended up, in spawn order.
+    fn settle_pile(config: &PhysicsConfig) -> Vec<Vec3> {
+        let mut world = World::new();
+        world.spawn((
+            LocalTransform::new(Vec3::ZERO),
+            GlobalTransform(Mat4::IDENTITY),
+            Collider::Plane { normal: Vec3::Y, offset: 0.0 },
+            Static,
+        ));
+        let spheres: Vec<Entity> = (0..24)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32 * 0.6, 1.0 + (i / 4) as f32 * 0.9, 0.0);
+                let sphere = spawn_sphere(&mut world, position, Vec3::ZERO, 1.0);
+                world.insert_one(sphere, GravityAffected).unwrap();
+                sphere
+            })
+            .collect();
+        // Move every third sphere to another archetype, so query order isn't spawn order.
+        for &sphere in spheres.iter().step_by(3) {
+            world.insert_one(sphere, Restitution(0.3)).unwrap();
+        }
+
+        let mut guard = TransformGuard::new();
+        for _ in 0..120 {
+            physics_step(&mut world, config);
+            collision_system(&mut world, config);
+            transform_propagation_system(&mut world, 1.0, &mut guard);
+        }
+        spheres.iter().map(|&s| world.get::<&LocalTransform>(s).unwrap().position).collect()
+    }
+
+    #[test]
+    fn deterministic_runs_match_exactly() {
+        let mut config = PhysicsConfig::new();
+        config.deterministic = true;
+        config.solver_iterations = 4;
+        assert_eq!(settle_pile(&config), settle_pile(&config));
+    }
+
+    #[test]
+    fn capsule_stands_on_z_up_ground() {
+        let mut config = PhysicsConfig::new();
+        config.set_world_up(WorldUp::Z);
+        let mut world = World::new();
+        world.spawn((
+            LocalTransform::new(Vec3::ZERO),
+            GlobalTransform(Mat4::IDENTITY),
+            Collider::Plane { normal: Vec3::Z, offset: 0.0 },
+            Static,
+        ));
+        // Radius 0.3 around a 1.4 m segment stood along Z: resting, its centre is 1 m up.
+        let mut local = LocalTransform::new(Vec3::Z);
+        local.rotation = WorldUp::Z.align();
+        let capsule = world.spawn((
+            local,
+            GlobalTransform(local.matrix()),
+            Collider::Capsule { radius: 0.3, height: 1.4 },
+            Velocity(Vec3::ZERO),
+            Mass(1.0),
+            GravityAffected,
+        ));
+
+        let mut guard = TransformGuard::new();
+        for _ in 0..120 {
+            physics_step(&mut world, &config);
+            collision_system(&mut world, &config);
+            transform_propagation_system(&mut world, 1.0, &mut guard);
+        }
+        let position = world.get::<&LocalTransform>(capsule).unwrap().position;
+        assert!((position.z - 1.0).abs() < 0.02, "capsule settled at {position}");
+        assert!(position.truncate().length() < 1e-4, "capsule drifted to {position}");
+    }
+
+    #[test]
+    fn box_vs_sphere_normal_follows_rotated_face() {
+        let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_4);
+        let face = rotation * Vec3::Y;
+        // A 0.5 sphere resting 5 cm into the top face of a 2 m cube turned 45° about Z.
+        let center = face * 1.45;
+        let (normal, depth) =
+            box_vs_sphere(Vec3::ZERO, rotation, Vec3::ONE, center, 0.5).expect("overlap");
+        assert!(normal.abs_diff_eq(face, 1e-5), "normal {normal} != face {face}");
+        assert!((depth - 0.05).abs() < 1e-5);
+    }
+
+    #[test]
+    fn box_vs_box_normal_follows_rotated_face() {
+        // A 1 m cube turned 45° about Y pokes its vertical edge 5 cm into the +X face of an
+        // unrotated 2 m cube. Its reach along X is 0.5 * (cos 45° + sin 45°) = 1/√2.
+        let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_4);
+        let beside = Vec3::X * (1.0 + std::f32::consts::FRAC_1_SQRT_2 - 0.05);
+        let (normal, depth) =
+            box_vs_box(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE, beside, rotation, Vec3::splat(0.5))
+                .expect("overlap");
+        assert!(normal.abs_diff_eq(Vec3::X, 1e-5), "normal {normal} != +X");
+        assert!((depth - 0.05).abs() < 1e-5, "depth {depth}");
+
+        // Swapped, the normal still points from the first box to the second.
+        let (normal, depth) =
+            box_vs_box(beside, rotation, Vec3::splat(0.5), Vec3::ZERO, Quat::IDENTITY, Vec3::ONE)
+                .expect("overlap");
+        assert!(normal.abs_diff_eq(-Vec3::X, 1e-5), "normal {normal} != -X");
+        assert!((depth - 0.05).abs() < 1e-5, "depth {depth}");
+    }
+
+    #[test]
+    fn grid_contacts_match_brute_force() {
+        let mut world = World::new();
+        let mut seed = 0x2545_f491_u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+        let mut entries: Vec<ColliderEntry> = (0..500)
+            .map(|_| ColliderEntry {
+                entity: world.spawn(()),
+                position: Vec3::new(next() * 20.0, next() * 5.0, next() * 20.0),
+                collider_kind: ColliderKind::Sphere { radius: 0.2 + next() * 0.5 },
+                body_owner: None,
+            })
+            .collect();
+        // The ground is unbounded, so the grid pairs it with everything.
+        entries.push(ColliderEntry {
+            entity: world.spawn(()),
+            position: Vec3::ZERO,
+            collider_kind: ColliderKind::Plane { normal: Vec3::Y, offset: 0.5 },
+            body_owner: None,
+        });
+
+        let mut brute_force = Vec::new();
+        for (i, a) in entries.iter().enumerate() {
+            for b in &entries[i + 1..] {
+                brute_force.extend(test_pair(a, b));
+            }
+        }
+        let pairs = |events: &[CollisionEvent]| -> Vec<(Entity, Entity)> {
+            events.iter().map(|e| (e.entity_a, e.entity_b)).collect()
+        };
+        assert!(brute_force.len() > 100, "only {} contacts", brute_force.len());
+        assert_eq!(pairs(&find_contacts(&entries, 1.0)), pairs(&brute_force));
+        assert_eq!(pairs(&find_contacts(&entries, DEFAULT_CELL_SIZE)), pairs(&brute_force));
+    }
+
+    #[test]
+    fn grid_queries_match_brute_force() {
+        let mut world = World::new();
+        let mut seed = 0x9e37_79b9_u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+        for i in 0..300 {
+            let position = Vec3::new(next() * 40.0 - 20.0, next() * 5.0, next() * 40.0 - 20.0);
+            let rotation = Quat::from_rotation_y(next() * 6.0);
+            let collider = if i % 2 == 0 {
+                Collider::Sphere { radius: 0.2 + next() * 0.8 }
+            } else {
+                Collider::Box { half_extents: Vec3::new(0.2 + next(), 0.2 + next(), 0.2 + next()) }
+            };
+            let global = GlobalTransform(Mat4::from_rotation_translation(rotation, position));
+            world.spawn((global, collider, Static));
+        }
+        let ground = Collider::Plane { normal: Vec3::Y, offset: 0.0 };
+        world.spawn((GlobalTransform(Mat4::IDENTITY), ground, Static));
+
+        // Every collider, in the order the queries gather them.
+        let all: Vec<ColliderEntry> = world
+            .query::<(&GlobalTransform, &Collider)>()
+            .iter()
+            .map(|(entity, (global, collider))| ColliderEntry {
+                entity,
+                position: global.0.w_axis.truncate(),
+                collider_kind: collider_to_kind(collider, collider_rotation(global)),
+                body_owner: None,
+            })
+            .collect();
+
+        let probe = Collider::Sphere { radius: 0.75 };
+        let (mut overlaps, mut blocked) = (0, 0);
+        for _ in 0..200 {
+            let position = Vec3::new(next() * 40.0 - 20.0, next() * 5.0, next() * 40.0 - 20.0);
+            let test_entry = ColliderEntry {
+                entity: Entity::DANGLING,
+                position,
+                collider_kind: collider_to_kind(&probe, Quat::IDENTITY),
+                body_owner: None,
+            };
+            let expected: Vec<Entity> = all
+                .iter()
+                .filter(|other| test_pair(&test_entry, other).is_some())
+                .map(|other| other.entity)
+                .collect();
+            let found: Vec<Entity> = query_collisions_at(&world, &probe, position, &[])
+                .into_iter()
+                .map(|(_, _, entity, _)| entity)
+                .collect();
+            assert_eq!(found, expected);
+            overlaps += expected.len();
+
+            let delta = Vec3::new(next() - 0.5, next() - 0.5, next() - 0.5) * 20.0;
+            let (len, dir) = (delta.length(), delta.normalize());
+            let expected = all.iter().fold(1.0_f32, |t, other| {
+                let kind = &other.collider_kind;
+                t.min(sweep_sphere_vs(0.3, position, dir, len, other.position, kind))
+            });
+            assert_eq!(sweep_sphere_static(&world, 0.3, position, delta, &[]), expected);
+            blocked += usize::from(expected < 1.0);
+        }
+        assert!(overlaps > 50, "only {overlaps} overlaps");
+        assert!(blocked > 50, "only {blocked} blocked sweeps");
+    }
 }
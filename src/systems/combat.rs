@@ -0,0 +1,219 @@
+use std::f32::consts::PI;
+
+use glam::{Quat, Vec3};
+use hecs::World;
+
+use crate::components::{
+    ancestors, descendants, world_matrix, ArmAim, CharacterBody, Collider, DamageNumber,
+    HitEvent, Lifetime, LocalTransform, PlayerFsm, PlayerState, SwordPosition, SwordState,
+    Velocity, DAMAGE_NUMBER_LIFETIME,
+};
+
+use super::collision::query_collisions_at;
+use super::player::{ATTACK_DURATION, COMBO_LENGTH};
+
+/// Fraction of the swing during which the blade can hit (start, end).
+const ACTIVE_WINDOW: (f32, f32) = (0.25, 0.75);
+
+/// One swing of the light combo. Pitch and yaw rotate the right upper arm (player-local,
+/// radians) from their start to end values over the swing.
+struct Swing {
+    pitch: (f32, f32),
+    yaw: (f32, f32),
+    /// Multiplier on the base knockback.
+    knockback: f32,
+}
+
+const SWINGS: [Swing; COMBO_LENGTH as usize] = [
+    // Diagonal chop: raised overhead → down across the front.
+    Swing { pitch: (-2.6, -0.3), yaw: (0.6, -0.6), knockback: 1.0 },
+    // Flat sweep from the right side across to the left.
+    Swing { pitch: (-1.5, -1.5), yaw: (-1.2, 1.0), knockback: 1.4 },
+    // Finisher: straight overhead slam.
+    Swing { pitch: (-3.0, -0.2), yaw: (0.0, 0.0), knockback: 2.0 },
+];
+/// Points along the blade (sword-local Y, mesh units) sampled as hit spheres.
+const BLADE_SAMPLES: [f32; 3] = [0.2, 0.45, 0.7];
+/// World-space radius of each blade hit sphere.
+const HITBOX_RADIUS: f32 = 0.25;
+const SWORD_DAMAGE: f32 = 10.0;
+/// Base knockback applied to struck dynamic bodies (horizontal speed), and the upward
+/// speed added per unit of horizontal.
+const KNOCKBACK_SPEED: f32 = 6.0;
+const KNOCKBACK_LIFT: f32 = 0.4;
+/// Damage numbers: how fast they rise (m/s), and how far apart numbers spawned while
+/// others are still up are spread (horizontal radius, vertical step), so a flurry of hits
+/// doesn't stack into one unreadable blob.
+const DAMAGE_NUMBER_RISE: f32 = 1.2;
+const DAMAGE_NUMBER_SPREAD: f32 = 0.3;
+const DAMAGE_NUMBER_STAGGER: f32 = 0.15;
+/// Angle between successive jittered damage numbers (the golden angle, radians), so
+/// consecutive numbers never land on the same side.
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// Drives the `Attacking` player state: poses the right arm through the current combo swing,
+/// carries the sword in the hand, and during the active frames tests a hitbox along the
+/// blade against everything but the player's own body.
+///
+/// The hitbox is a row of spheres rather than one capsule because capsule colliders are
+/// vertical-only in the narrow phase. Each target (resolved to its physics root) is hit
+/// at most once per swing; the list lives in `PlayerState::Attacking::hits`.
+///
+/// Runs after `player_state_system` so the swing timer is current and the pose overrides
+/// `arm_aim_system` for the frame. Returns the hits landed this frame.
+pub fn sword_attack_system(world: &mut World) -> Vec<HitEvent> {
+    let mut hits = Vec::new();
+
+    let players: Vec<_> = world
+        .query::<(&PlayerFsm, &CharacterBody, &ArmAim)>()
+        .iter()
+        .map(|(e, (fsm, body, aim))| {
+            let exited = fsm.just_entered()
+                && matches!(fsm.previous, PlayerState::Attacking { .. })
+                && !matches!(fsm.state, PlayerState::Attacking { .. });
+            (
+                e,
+                fsm.state.clone(),
+                exited,
+                body.right_upper_arm,
+                body.right_forearm,
+                body.sword,
+                aim.right_rest,
+            )
+        })
+        .collect();
+
+    for (player, state, exited, arm, forearm, sword, rest) in players {
+        if exited {
+            // Swing over: put the sword back in its wielded pose.
+            if let Ok((sword_state, lt)) =
+                world.query_one_mut::<(&SwordState, &mut LocalTransform)>(sword)
+            {
+                if sword_state.position == SwordPosition::Wielded {
+                    lt.position = sword_state.wielded_pos;
+                    lt.rotation = sword_state.wielded_rot;
+                }
+            }
+            continue;
+        }
+        let PlayerState::Attacking { timer, combo_index, hits: mut already_hit } = state else {
+            continue;
+        };
+        let swing = &SWINGS[(combo_index as usize).min(SWINGS.len() - 1)];
+
+        // Pose: ease the arm through the arc; the sword follows the hand, blade outward.
+        let t = (timer / ATTACK_DURATION).clamp(0.0, 1.0);
+        let ease = t * t * (3.0 - 2.0 * t);
+        let pitch = swing.pitch.0 + (swing.pitch.1 - swing.pitch.0) * ease;
+        let yaw = swing.yaw.0 + (swing.yaw.1 - swing.yaw.0) * ease;
+        let arm_rot = Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch) * rest;
+
+        // Hand sits at the far end of the forearm (forearm center + half a segment).
+        let hand_offset = world
+            .get::<&LocalTransform>(forearm)
+            .map(|lt| lt.position * 1.5)
+            .unwrap_or(Vec3::ZERO);
+        let Some(shoulder) = world.get::<&LocalTransform>(arm).ok().map(|lt| lt.position) else {
+            continue;
+        };
+        if let Ok(mut lt) = world.get::<&mut LocalTransform>(arm) {
+            lt.rotation = arm_rot;
+        }
+        if let Ok(mut lt) = world.get::<&mut LocalTransform>(sword) {
+            lt.position = shoulder + arm_rot * hand_offset;
+            lt.rotation = arm_rot * Quat::from_rotation_x(PI);
+        }
+
+        if t < ACTIVE_WINDOW.0 || t > ACTIVE_WINDOW.1 {
+            continue;
+        }
+
+        // Hitbox: never test against the player or anything attached to it.
+        let mut skip = descendants(world, player);
+        skip.push(player);
+        let sword_world = world_matrix(world, sword);
+        // Player-local +Z is forward.
+        let facing = world_matrix(world, player).transform_vector3(Vec3::Z);
+        let facing = Vec3::new(facing.x, 0.0, facing.z).try_normalize();
+        let probe = Collider::Sphere { radius: HITBOX_RADIUS };
+
+        for local_y in BLADE_SAMPLES {
+            let point = sword_world.transform_point3(Vec3::new(0.0, local_y, 0.0));
+            for (push, _depth, entity, is_dynamic) in
+                query_collisions_at(world, &probe, point, &skip)
+            {
+                if !is_dynamic {
+                    continue;
+                }
+                let target = ancestors(world, entity).last().copied().unwrap_or(entity);
+                if target == player || already_hit.contains(&target) {
+                    continue;
+                }
+                already_hit.push(target);
+                hits.push(HitEvent {
+                    attacker: player,
+                    target,
+                    point,
+                    normal: -push,
+                    attacker_forward: facing,
+                    damage: SWORD_DAMAGE,
+                    knockback: KNOCKBACK_SPEED * swing.knockback,
+                });
+            }
+        }
+
+        if let Ok(mut fsm) = world.get::<&mut PlayerFsm>(player) {
+            if let PlayerState::Attacking { hits, .. } = &mut fsm.state {
+                *hits = already_hit;
+            }
+        }
+    }
+
+    hits
+}
+
+/// Knock struck bodies away from the attacker with a little lift along `up`, the world up
+/// axis. The push follows the attacker's facing so a swing sends things where the player
+/// is looking, regardless of which side of the target the blade happened to touch.
+pub fn apply_hit_impulses(world: &mut World, hits: &[HitEvent], up: Vec3) {
+    for hit in hits {
+        if let Ok(mut vel) = world.get::<&mut Velocity>(hit.target) {
+            vel.0 += (knockback_direction(hit, up) + up * KNOCKBACK_LIFT) * hit.knockback;
+        }
+    }
+}
+
+/// Horizontal (perpendicular to `up`) push direction for a hit: the attacker's facing, or
+/// the contact normal when no facing is known (or it is degenerate).
+fn knockback_direction(hit: &HitEvent, up: Vec3) -> Vec3 {
+    let horizontal = |v: Vec3| v - up * v.dot(up);
+    hit.attacker_forward
+        .and_then(|f| horizontal(f).try_normalize())
+        .unwrap_or_else(|| horizontal(hit.normal).normalize_or_zero())
+}
+
+/// Spawn a rising damage number at each hit point. Each new number is offset around the hit
+/// according to how many are already up, spiralling outward by the golden angle.
+pub fn spawn_damage_numbers(world: &mut World, hits: &[HitEvent]) {
+    let mut live = world.query::<&DamageNumber>().iter().count();
+    for hit in hits {
+        let angle = live as f32 * GOLDEN_ANGLE;
+        let jitter = Vec3::new(angle.cos(), 0.0, angle.sin()) * DAMAGE_NUMBER_SPREAD
+            + Vec3::Y * (live % 3) as f32 * DAMAGE_NUMBER_STAGGER;
+        let start = if live == 0 { hit.point } else { hit.point + jitter };
+        world.spawn((
+            DamageNumber::new(hit.damage, start),
+            Lifetime { remaining: DAMAGE_NUMBER_LIFETIME },
+        ));
+        live += 1;
+    }
+}
+
+/// Float damage numbers upward and age them for their fade; `lifetime_system` despawns
+/// them.
+pub fn damage_number_system(world: &mut World, dt: f32) {
+    for (_entity, number) in world.query_mut::<&mut DamageNumber>() {
+        number.elapsed += dt;
+        number.world_pos.y += DAMAGE_NUMBER_RISE * dt;
+    }
+}
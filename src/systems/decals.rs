@@ -0,0 +1,53 @@
+use glam::{Vec2, Vec3};
+use hecs::World;
+
+use crate::components::{Decal, FootprintTrail, Grounded, Lifetime, LocalTransform, Velocity};
+
+use super::raycast::raycast_surface;
+
+/// Ground distance between consecutive footprints (metres).
+const FOOTSTEP_STRIDE: f32 = 0.8;
+/// Sideways offset of each foot from the body centre.
+const FOOT_SPACING: f32 = 0.15;
+/// How far below the body centre the ground is searched for.
+const FOOTPRINT_REACH: f32 = 3.0;
+const FOOTPRINT_SIZE: Vec2 = Vec2::new(0.14, 0.3);
+const FOOTPRINT_COLOR: Vec3 = Vec3::new(0.12, 0.1, 0.08);
+const FOOTPRINT_LIFETIME: f32 = 8.0;
+
+/// Stamp a footprint under each `FootprintTrail` entity every `FOOTSTEP_STRIDE` metres it
+/// walks while grounded, alternating feet. `up` is the current up direction (against gravity).
+pub fn footprint_system(world: &mut World, up: Vec3, dt: f32) {
+    let mut steps: Vec<(Vec3, Vec3)> = Vec::new();
+    for (_e, (trail, local, vel, grounded)) in world.query_mut::<(
+        &mut FootprintTrail,
+        &LocalTransform,
+        &Velocity,
+        Option<&Grounded>,
+    )>() {
+        let ground_vel = vel.0 - up * vel.0.dot(up);
+        if grounded.is_none() {
+            trail.distance = 0.0;
+            continue;
+        }
+        trail.distance += ground_vel.length() * dt;
+        if trail.distance < FOOTSTEP_STRIDE {
+            continue;
+        }
+        trail.distance -= FOOTSTEP_STRIDE;
+        let forward = ground_vel.normalize_or_zero();
+        let side = forward.cross(up) * if trail.left { -FOOT_SPACING } else { FOOT_SPACING };
+        trail.left = !trail.left;
+        steps.push((local.position + side, forward));
+    }
+
+    for (foot, forward) in steps {
+        let Some(hit) = raycast_surface(world, foot, -up, FOOTPRINT_REACH) else {
+            continue;
+        };
+        world.spawn((
+            Decal::new(hit.point, hit.normal, forward, FOOTPRINT_SIZE, FOOTPRINT_COLOR),
+            Lifetime { remaining: FOOTPRINT_LIFETIME },
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+use glam::{Mat4, Quat, Vec3};
+use hecs::{Entity, World};
+
+use crate::components::{
+    GlobalTransform, LocalTransform, NoScaleInherit, Parent, PreviousPosition, Velocity,
+};
+
+use super::raycast::RaycastHit;
+
+/// Default editor grid increment (world units).
+pub const DEFAULT_SNAP_STEP: f32 = 0.5;
+/// Rotation increment while snapping (degrees).
+const SNAP_ANGLE: f32 = 15.0;
+
+/// Editor grid snapping, applied while its modifier is held.
+pub struct GridSnap {
+    /// Position increment (world units).
+    pub step: f32,
+    /// Snap continuously during a drag; otherwise only the final placement snaps.
+    pub live: bool,
+}
+
+impl GridSnap {
+    pub fn new() -> Self {
+        Self { step: DEFAULT_SNAP_STEP, live: true }
+    }
+
+    /// Nearest grid point to `p`.
+    pub fn position(&self, p: Vec3) -> Vec3 {
+        (p / self.step).round() * self.step
+    }
+
+    /// `degrees` rounded to the nearest rotation increment.
+    pub fn angle(&self, degrees: f32) -> f32 {
+        (degrees / SNAP_ANGLE).round() * SNAP_ANGLE
+    }
+}
+
+/// An entity being dragged with the mouse in editor mode.
+///
+/// The entity slides across a plane through the grabbed point that faces the camera, so it
+/// stays under the cursor at the depth it was picked at. Positions are handled in world
+/// space and converted into the parent's frame, so children drag as intuitively as roots.
+pub struct EntityDrag {
+    entity: Entity,
+    plane_point: Vec3,
+    plane_normal: Vec3,
+    /// Entity origin relative to the grabbed surface point.
+    offset: Vec3,
+    /// Entity world position when the drag began, the anchor for axis constraints.
+    start: Vec3,
+    /// Unsnapped world position the cursor last put the entity at.
+    target: Vec3,
+    /// Where the entity actually is (the target, snapped if snapping is live).
+    placed: Vec3,
+    /// Rotation when the drag began, and how far it has been turned about Y since (degrees).
+    start_rotation: Quat,
+    yaw: f32,
+}
+
+impl EntityDrag {
+    /// Start dragging the entity under `hit`. `facing` is the camera's forward vector.
+    pub fn begin(world: &World, hit: &RaycastHit, facing: Vec3) -> Option<Self> {
+        let start = world.get::<&GlobalTransform>(hit.entity).ok()?.0.w_axis.truncate();
+        let start_rotation = world.get::<&LocalTransform>(hit.entity).ok()?.rotation;
+        Some(Self {
+            entity: hit.entity,
+            plane_point: hit.point,
+            plane_normal: facing.normalize_or(Vec3::NEG_Z),
+            offset: start - hit.point,
+            start,
+            target: start,
+            placed: start,
+            start_rotation,
+            yaw: 0.0,
+        })
+    }
+
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// World position the entity has been placed at.
+    pub fn position(&self) -> Vec3 {
+        self.placed
+    }
+
+    /// Move the entity to where the cursor ray (`origin`, `dir`) meets the drag plane. With
+    /// `constrain`, only the world axis the entity has moved furthest along is kept; with a
+    /// live `snap`, the result lands on the grid.
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        origin: Vec3,
+        dir: Vec3,
+        constrain: bool,
+        snap: Option<&GridSnap>,
+    ) {
+        let denom = dir.dot(self.plane_normal);
+        if denom.abs() < 1e-6 {
+            return;
+        }
+        let t = (self.plane_point - origin).dot(self.plane_normal) / denom;
+        if t <= 0.0 {
+            return;
+        }
+        let mut target = origin + dir * t + self.offset;
+        if constrain {
+            let delta = target - self.start;
+            let abs = delta.abs();
+            let axis = if abs.x >= abs.y && abs.x >= abs.z {
+                Vec3::X
+            } else if abs.y >= abs.z {
+                Vec3::Y
+            } else {
+                Vec3::Z
+            };
+            target = self.start + axis * delta.dot(axis);
+        }
+        self.target = target;
+        self.placed = match snap {
+            Some(snap) if snap.live => snap.position(target),
+            _ => target,
+        };
+        set_world_position(world, self.entity, self.placed);
+    }
+
+    /// Turn the entity `degrees` further about the vertical axis. With `snap`, the total turn
+    /// since the drag began rounds to the snap angle.
+    pub fn rotate(&mut self, world: &mut World, degrees: f32, snap: Option<&GridSnap>) {
+        self.yaw += degrees;
+        let yaw = snap.map_or(self.yaw, |snap| snap.angle(self.yaw));
+        if let Ok(mut local) = world.get::<&mut LocalTransform>(self.entity) {
+            local.rotation = Quat::from_rotation_y(yaw.to_radians()) * self.start_rotation;
+        }
+    }
+
+    /// Drop the entity, snapping its final placement with `snap` (the only snap it gets when
+    /// live snapping is off).
+    pub fn finish(self, world: &mut World, snap: Option<&GridSnap>) {
+        if let Some(snap) = snap {
+            set_world_position(world, self.entity, snap.position(self.target));
+        }
+    }
+}
+
+/// Write `entity`'s `LocalTransform` so its world position becomes `target`, and bring it to
+/// rest so interpolation and physics don't fight the move.
+fn set_world_position(world: &mut World, entity: Entity, target: Vec3) {
+    let parent_global = world
+        .get::<&Parent>(entity)
+        .ok()
+        .and_then(|parent| world.get::<&GlobalTransform>(parent.0).ok().map(|g| g.0));
+    let local_target = match parent_global {
+        Some(mut parent) => {
+            // Mirror transform propagation, which drops inherited scale for these.
+            if world.get::<&NoScaleInherit>(entity).is_ok() {
+                let (_scale, rotation, translation) = parent.to_scale_rotation_translation();
+                parent = Mat4::from_rotation_translation(rotation, translation);
+            }
+            parent.inverse().transform_point3(target)
+        }
+        None => target,
+    };
+
+    if let Ok(mut local) = world.get::<&mut LocalTransform>(entity) {
+        local.position = local_target;
+    }
+    if let Ok(mut prev) = world.get::<&mut PreviousPosition>(entity) {
+        prev.0 = local_target;
+    }
+    if let Ok(mut velocity) = world.get::<&mut Velocity>(entity) {
+        velocity.0 = Vec3::ZERO;
+    }
+}
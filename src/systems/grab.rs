@@ -5,7 +5,7 @@ use sdl2::mouse::MouseButton;
 
 use crate::camera::Camera;
 use crate::components::{
-    add_child, remove_child, Collider, GlobalTransform, GrabState, Grabbable, Held, LocalTransform,
+    reparent_keep_world, Collider, GlobalTransform, GrabState, Grabbable, Held, LocalTransform,
     NoSelfCollision, Player, Static, Velocity,
 };
 use crate::engine::input::{InputEvent, InputState};
@@ -16,6 +16,11 @@ use super::raycast::raycast_grabbable;
 const GRAB_DISTANCE: f32 = 5.0;
 const HOLD_RESOLVE_ITERS: usize = 3;
 const HOLD_PUSH_IMPULSE: f32 = 3.0;
+/// Longest move of the held object between collision checks, as a fraction of its collider's
+/// thinnest half-size, so a fast swing can't carry it through thin geometry in one frame.
+const HOLD_SUBSTEP_FRACTION: f32 = 0.5;
+/// Most collision checks along the hold path per frame; past this the steps just lengthen.
+const MAX_HOLD_SUBSTEPS: usize = 8;
 const HOLD_OFFSET: Vec3 = Vec3::new(0.0, 0.5, 1.5);
 const HOLD_LERP_SPEED: f32 = 10.0;
 const MIN_THROW_FORCE: f32 = 5.0;
@@ -50,12 +55,13 @@ fn build_hold_skip_list(
 }
 
 /// Resolve a held object's world position against world colliders using `skip` as the exclusion list.
-/// Dynamic objects that overlap receive a push impulse.
+/// Dynamic objects that overlap receive a push impulse of `impulse`.
 fn resolve_held_pos(
     world: &mut World,
     collider: &Collider,
     world_target: Vec3,
     skip: &[hecs::Entity],
+    impulse: f32,
 ) -> Vec3 {
     let mut pos = world_target;
     for _ in 0..HOLD_RESOLVE_ITERS {
@@ -67,7 +73,7 @@ fn resolve_held_pos(
             pos += push * depth;
             if is_dynamic {
                 if let Ok(mut vel) = world.get::<&mut Velocity>(other) {
-                    vel.0 -= push * HOLD_PUSH_IMPULSE;
+                    vel.0 -= push * impulse;
                 }
             }
         }
@@ -75,6 +81,35 @@ fn resolve_held_pos(
     pos
 }
 
+/// Walk a held object from `from` toward `world_target` in sub-steps, resolving against world
+/// colliders after each, and return where it ends up. Each step heads for the target from
+/// wherever the last one was pushed back to, so a wall between the two holds the object on
+/// the near side instead of the target resolving out of the far side.
+fn resolve_held_path(
+    world: &mut World,
+    collider: &Collider,
+    from: Vec3,
+    world_target: Vec3,
+    skip: &[hecs::Entity],
+) -> Vec3 {
+    let thinnest = match collider {
+        Collider::Sphere { radius } | Collider::Capsule { radius, .. } => *radius,
+        Collider::Box { half_extents } => half_extents.min_element(),
+        Collider::Plane { .. } => f32::INFINITY,
+    };
+    let step_len = (thinnest * HOLD_SUBSTEP_FRACTION).max(1e-3);
+    let steps = ((world_target - from).length() / step_len).ceil() as usize;
+    let steps = steps.clamp(1, MAX_HOLD_SUBSTEPS);
+    // Split the push among the steps so a sub-stepped frame shoves no harder than before.
+    let impulse = HOLD_PUSH_IMPULSE / steps as f32;
+    let mut pos = from;
+    for remaining in (1..=steps).rev() {
+        let next = pos + (world_target - pos) / remaining as f32;
+        pos = resolve_held_pos(world, collider, next, skip, impulse);
+    }
+    pos
+}
+
 /// Grab/throw system.
 ///
 /// Returns `(speed_multiplier, yaw_lock)`:
@@ -123,13 +158,18 @@ pub fn grab_throw_system(
 
     match held_entity {
         None => {
-            // Not holding — check for grab attempt.
+            // Not holding — track what a grab would pick up, then check for a grab attempt.
+            let chest_pos = {
+                let lt = world.get::<&LocalTransform>(player_entity).unwrap();
+                lt.position + camera.up.vector() * CHEST_HEIGHT
+            };
+            let target = raycast_grabbable(world, chest_pos, camera.front(), GRAB_DISTANCE)
+                .filter(|hit| world.get::<&Static>(hit.entity).is_err());
+            world.get::<&mut GrabState>(player_entity).unwrap().target =
+                target.as_ref().map(|hit| hit.entity);
+
             if right_click_pressed && alt_held {
-                let chest_pos = {
-                    let lt = world.get::<&LocalTransform>(player_entity).unwrap();
-                    lt.position + Vec3::Y * CHEST_HEIGHT
-                };
-                if let Some(hit) = raycast_grabbable(world, chest_pos, camera.front(), GRAB_DISTANCE) {
+                if let Some(hit) = target {
                     if world.get::<&Static>(hit.entity).is_ok() {
                         return (1.0, None, None);
                     }
@@ -137,26 +177,16 @@ pub fn grab_throw_system(
                         return (1.0, None, None);
                     }
 
-                    let (player_pos, player_yaw) = {
-                        let lt = world.get::<&LocalTransform>(player_entity).unwrap();
-                        (lt.position, lt.rotation)
-                    };
-                    let (held_world_pos, held_world_rot) = {
-                        let lt = world.get::<&LocalTransform>(hit.entity).unwrap();
-                        (lt.position, lt.rotation)
-                    };
-
-                    let world_offset = held_world_pos - player_pos;
-                    let inv_yaw = player_yaw.inverse();
-                    let local_offset = inv_yaw * world_offset;
-
-                    add_child(world, player_entity, hit.entity);
-
-                    let local_rot = inv_yaw * held_world_rot;
-                    if let Ok(mut lt) = world.get::<&mut LocalTransform>(hit.entity) {
-                        lt.position = local_offset;
-                        lt.rotation = local_rot;
-                    }
+                    let held_world_pos = world
+                        .get::<&LocalTransform>(hit.entity)
+                        .map(|lt| lt.position)
+                        .unwrap();
+
+                    reparent_keep_world(world, hit.entity, Some(player_entity));
+                    let local_rot = world
+                        .get::<&LocalTransform>(hit.entity)
+                        .map(|lt| lt.rotation)
+                        .unwrap();
 
                     // NoSelfCollision lets collision_system treat the object as a kinematic
                     // obstacle that blocks the player's capsule while ignoring limbs/head.
@@ -164,6 +194,7 @@ pub fn grab_throw_system(
                     let _ = world.insert_one(hit.entity, NoSelfCollision(player_entity));
                     let mut grab = world.get::<&mut GrabState>(player_entity).unwrap();
                     grab.held_entity = Some(hit.entity);
+                    grab.target = None;
                     grab.held_rotation = local_rot;
                     grab.wind_up_time = 0.0;
                     grab.is_winding = false;
@@ -176,34 +207,14 @@ pub fn grab_throw_system(
         Some(held) => {
             // Safety: check entity still exists.
             if !world.contains(held) {
-                let mut grab = world.get::<&mut GrabState>(player_entity).unwrap();
-                grab.held_entity = None;
-                grab.wind_up_time = 0.0;
-                grab.is_winding = false;
-                grab.yaw_lock = None;
+                world.get::<&mut GrabState>(player_entity).unwrap().release();
                 return (1.0, None, None);
             }
 
             // Drop when either Alt OR right-click is released (and not winding).
             let should_drop = (!alt_held || !right_held) && !is_winding;
             if should_drop {
-                let (world_pos, world_rot) = extract_world_transform(world, held);
-                remove_child(world, player_entity, held);
-                if let Ok(mut lt) = world.get::<&mut LocalTransform>(held) {
-                    lt.position = world_pos;
-                    lt.rotation = world_rot;
-                }
-                let _ = world.remove_one::<Held>(held);
-                let _ = world.remove_one::<NoSelfCollision>(held);
-                if let Ok(mut vel) = world.get::<&mut Velocity>(held) {
-                    vel.0 = held_velocity * DROP_VELOCITY_DAMPER;
-                }
-                let mut grab = world.get::<&mut GrabState>(player_entity).unwrap();
-                grab.held_entity = None;
-                grab.wind_up_time = 0.0;
-                grab.is_winding = false;
-                grab.held_velocity = Vec3::ZERO;
-                grab.yaw_lock = None;
+                release_held(world, player_entity, held, held_velocity * DROP_VELOCITY_DAMPER);
                 return (1.0, None, None);
             }
 
@@ -226,15 +237,17 @@ pub fn grab_throw_system(
             });
             let skip = build_hold_skip_list(world, held, player_entity);
 
-            // Resolve against geometry. Lateral (XZ) displacement between the resolved position
+            // Resolve against geometry. Lateral displacement between the resolved position
             // and world_target indicates a wall is blocking the object. When that happens and the
             // player is rotating into the wall, start (or maintain) a yaw lock: the player gets
             // YAW_GRACE_DEGREES of further rotation, then yaw freezes until the ball is freed.
             let (effective_target, new_yaw_lock, new_move_block) = if let Some(ref coll) = collider_copy {
-                let resolved = resolve_held_pos(world, coll, world_target, &skip);
+                let (held_world_pos, _) = extract_world_transform(world, held);
+                let resolved = resolve_held_path(world, coll, held_world_pos, world_target, &skip);
 
                 let disp = resolved - world_target;
-                let horiz = Vec3::new(disp.x, 0.0, disp.z);
+                let up = camera.up.vector();
+                let horiz = disp - up * disp.dot(up);
                 let lateral_disp = horiz.length();
 
                 let prev_lock = world.get::<&GrabState>(player_entity).ok().and_then(|g| g.yaw_lock);
@@ -306,23 +319,7 @@ pub fn grab_throw_system(
                 let force = MIN_THROW_FORCE + (MAX_THROW_FORCE - MIN_THROW_FORCE) * throw_t;
                 let throw_vel = camera.front() * force + HELD_VELOCITY_DAMPER * held_velocity;
 
-                let (world_pos, world_rot) = extract_world_transform(world, held);
-                remove_child(world, player_entity, held);
-                if let Ok(mut lt) = world.get::<&mut LocalTransform>(held) {
-                    lt.position = world_pos;
-                    lt.rotation = world_rot;
-                }
-                let _ = world.remove_one::<Held>(held);
-                let _ = world.remove_one::<NoSelfCollision>(held);
-                if let Ok(mut vel) = world.get::<&mut Velocity>(held) {
-                    vel.0 = throw_vel;
-                }
-                let mut grab = world.get::<&mut GrabState>(player_entity).unwrap();
-                grab.held_entity = None;
-                grab.wind_up_time = 0.0;
-                grab.is_winding = false;
-                grab.held_velocity = Vec3::ZERO;
-                grab.yaw_lock = None;
+                release_held(world, player_entity, held, throw_vel);
                 return (1.0, None, None);
             }
 
@@ -332,6 +329,28 @@ pub fn grab_throw_system(
     }
 }
 
+/// Detach `held` from the player back into the world with velocity `vel` and reset the
+/// player's grab state.
+fn release_held(world: &mut World, player_entity: hecs::Entity, held: hecs::Entity, vel: Vec3) {
+    reparent_keep_world(world, held, None);
+    let _ = world.remove_one::<Held>(held);
+    let _ = world.remove_one::<NoSelfCollision>(held);
+    if let Ok(mut v) = world.get::<&mut Velocity>(held) {
+        v.0 = vel;
+    }
+    if let Ok(mut grab) = world.get::<&mut GrabState>(player_entity) {
+        grab.release();
+    }
+}
+
+/// Drop whatever `player_entity` is holding, at rest. No-op if nothing is held.
+pub fn drop_held(world: &mut World, player_entity: hecs::Entity) {
+    let held = world.get::<&GrabState>(player_entity).ok().and_then(|g| g.held_entity);
+    if let Some(held) = held {
+        release_held(world, player_entity, held, Vec3::ZERO);
+    }
+}
+
 /// Extract world-space position and rotation from an entity's GlobalTransform.
 fn extract_world_transform(world: &World, entity: hecs::Entity) -> (Vec3, Quat) {
     world
@@ -0,0 +1,62 @@
+use hecs::{Entity, World};
+
+use crate::components::{despawn_recursive, Despawned, GrabState, Lifetime};
+
+/// Count every `Lifetime` down by `dt` and despawn (recursively) the entities whose time
+/// is up. A player holding one of the despawned entities has its `GrabState` cleared, so
+/// it doesn't go on holding nothing.
+pub fn lifetime_system(world: &mut World, dt: f32) -> Vec<Despawned> {
+    let mut expired: Vec<Entity> = Vec::new();
+    for (entity, lifetime) in world.query_mut::<&mut Lifetime>() {
+        lifetime.remaining -= dt;
+        if lifetime.remaining <= 0.0 {
+            expired.push(entity);
+        }
+    }
+
+    let mut despawned: Vec<Entity> = Vec::new();
+    for entity in expired {
+        // May already be gone, as a descendant of an earlier expired entity.
+        if world.contains(entity) {
+            despawned.extend(despawn_recursive(world, entity));
+        }
+    }
+
+    for (_entity, grab) in world.query_mut::<&mut GrabState>() {
+        if grab.held_entity.is_some_and(|held| despawned.contains(&held)) {
+            grab.release();
+        }
+        if grab.target.is_some_and(|target| despawned.contains(&target)) {
+            grab.target = None;
+        }
+    }
+
+    despawned.into_iter().map(|entity| Despawned { entity }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+
+    #[test]
+    fn expiring_held_entity_releases_the_grab() {
+        let mut world = World::new();
+        let held = world.spawn((Lifetime { remaining: 0.5 },));
+        let mut grab = GrabState::new();
+        grab.held_entity = Some(held);
+        grab.held_velocity = Vec3::X;
+        let player = world.spawn((grab,));
+
+        assert!(lifetime_system(&mut world, 0.25).is_empty());
+        let despawned = lifetime_system(&mut world, 0.25);
+        assert_eq!(despawned.len(), 1);
+        assert_eq!(despawned[0].entity, held);
+        assert!(!world.contains(held));
+
+        let grab = world.get::<&GrabState>(player).unwrap();
+        assert!(grab.held_entity.is_none());
+        assert_eq!(grab.held_velocity, Vec3::ZERO);
+    }
+}
@@ -0,0 +1,142 @@
+use glam::{Quat, Vec3};
+use hecs::{Entity, World};
+
+use crate::components::{Flicker, LocalTransform, PointLight, SpotLight};
+use crate::renderer::GizmoRenderer;
+
+/// Smallest radius the editor shrinks a light to (world units).
+const MIN_RADIUS: f32 = 0.5;
+/// Size of the selection marker drawn around the light (world units).
+const MARKER_SIZE: f32 = 0.4;
+
+/// Live tweaking of point and spot lights. The selection cycles through every light in
+/// entity id order, which stays stable however the world happens to iterate; edits write
+/// straight into the light components, so the next frame's upload picks them up.
+pub struct LightEditor {
+    selected: Option<Entity>,
+}
+
+impl LightEditor {
+    pub fn new() -> Self {
+        Self { selected: None }
+    }
+
+    pub fn selected(&self) -> Option<Entity> {
+        self.selected
+    }
+
+    /// Move the selection to the next light (or the previous one when `backwards`). Stepping
+    /// past the last light clears the selection, ending the edit; with no lights at all the
+    /// selection stays empty.
+    pub fn cycle(&mut self, world: &World, backwards: bool) {
+        let mut lights: Vec<Entity> = world
+            .iter()
+            .map(|entity| entity.entity())
+            .filter(|&entity| is_light(world, entity))
+            .collect();
+        lights.sort();
+        if backwards {
+            lights.reverse();
+        }
+        // The current light may have been removed; pick the next one after its id anyway.
+        self.selected = match self.selected {
+            None => lights.first().copied(),
+            Some(current) if backwards => lights.into_iter().find(|&e| e < current),
+            Some(current) => lights.into_iter().find(|&e| e > current),
+        };
+    }
+
+    /// Forget the selection if the light has been despawned (e.g. by a scene reload).
+    pub fn validate(&mut self, world: &World) {
+        if self.selected.is_some_and(|e| !is_light(world, e)) {
+            self.selected = None;
+        }
+    }
+
+    /// Scale the selected light's intensity. A flickering light keeps flickering around the
+    /// new value.
+    pub fn scale_intensity(&self, world: &mut World, factor: f32) {
+        let Some(entity) = self.selected else {
+            return;
+        };
+        if let Ok(mut flicker) = world.get::<&mut Flicker>(entity) {
+            flicker.base_intensity *= factor;
+        }
+        if let Ok(mut light) = world.get::<&mut PointLight>(entity) {
+            light.intensity *= factor;
+        }
+        if let Ok(mut light) = world.get::<&mut SpotLight>(entity) {
+            light.intensity *= factor;
+        }
+    }
+
+    /// Scale the selected light's radius, re-deriving its falloff coefficients.
+    pub fn scale_radius(&self, world: &mut World, factor: f32) {
+        let Some(entity) = self.selected else {
+            return;
+        };
+        if let Ok(mut light) = world.get::<&mut PointLight>(entity) {
+            let radius = (light.radius * factor).max(MIN_RADIUS);
+            light.set_radius(radius);
+        }
+        if let Ok(mut light) = world.get::<&mut SpotLight>(entity) {
+            let radius = (light.radius * factor).max(MIN_RADIUS);
+            light.set_radius(radius);
+        }
+    }
+
+    /// Turn the selected light's hue by `degrees`, keeping its brightness.
+    pub fn rotate_hue(&self, world: &mut World, degrees: f32) {
+        let Some(entity) = self.selected else {
+            return;
+        };
+        // A rotation about the grey axis moves the hue and leaves the channel sum alone.
+        let turn = Quat::from_axis_angle(Vec3::ONE.normalize(), degrees.to_radians());
+        if let Ok(mut light) = world.get::<&mut PointLight>(entity) {
+            light.color = (turn * light.color).max(Vec3::ZERO);
+        }
+        if let Ok(mut light) = world.get::<&mut SpotLight>(entity) {
+            light.color = (turn * light.color).max(Vec3::ZERO);
+        }
+    }
+
+    /// Move the selected light by `offset` (world units).
+    pub fn nudge(&self, world: &mut World, offset: Vec3) {
+        let Some(entity) = self.selected else {
+            return;
+        };
+        if let Ok(mut local) = world.get::<&mut LocalTransform>(entity) {
+            local.position += offset;
+        }
+    }
+
+    /// Queue a marker around the selected light in its own colour, with its reach drawn as
+    /// three circles of `radius`.
+    pub fn draw_gizmo(&self, world: &World, gizmos: &mut GizmoRenderer) {
+        let Some(entity) = self.selected else {
+            return;
+        };
+        // Lights are uploaded from their LocalTransform; mark the same spot.
+        let Ok(position) = world.get::<&LocalTransform>(entity).map(|local| local.position) else {
+            return;
+        };
+        let (color, radius) = if let Ok(light) = world.get::<&PointLight>(entity) {
+            (light.color, light.radius)
+        } else if let Ok(light) = world.get::<&SpotLight>(entity) {
+            (light.color, light.radius)
+        } else {
+            return;
+        };
+        let color = color / color.max_element().max(1e-3);
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            gizmos.line(position - axis * MARKER_SIZE, position + axis * MARKER_SIZE, color);
+        }
+        gizmos.circle(position, Vec3::X, radius, color);
+        gizmos.circle(position, Vec3::Y, radius, color);
+        gizmos.circle(position, Vec3::Z, radius, color);
+    }
+}
+
+fn is_light(world: &World, entity: Entity) -> bool {
+    world.get::<&PointLight>(entity).is_ok() || world.get::<&SpotLight>(entity).is_ok()
+}
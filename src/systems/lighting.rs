@@ -0,0 +1,32 @@
+use std::f32::consts::TAU;
+
+use hecs::World;
+
+use crate::components::{Flicker, PointLight, SpotLight};
+
+/// Layers of the flicker wave: (frequency multiplier, weight, phase). Non-integer multipliers
+/// keep the pattern from visibly repeating; the weights sum to 1 so the wave stays in [-1, 1].
+const FLICKER_LAYERS: [(f32, f32, f32); 3] = [(1.0, 0.5, 0.0), (2.31, 0.3, 1.7), (5.13, 0.2, 4.1)];
+
+/// Advance each `Flicker` and write the modulated intensity into the entity's light.
+/// Each light is offset in time by its entity id so neighbouring torches don't pulse in sync.
+pub fn flicker_system(world: &mut World, dt: f32) {
+    for (entity, (flicker, point, spot)) in
+        world.query_mut::<(&mut Flicker, Option<&mut PointLight>, Option<&mut SpotLight>)>()
+    {
+        flicker.time += dt;
+        let t = (flicker.time + entity.id() as f32 * 0.618) * flicker.frequency * TAU;
+        let wave: f32 = FLICKER_LAYERS
+            .iter()
+            .map(|&(mult, weight, phase)| weight * (t * mult + phase).sin())
+            .sum();
+        let intensity = (flicker.base_intensity + flicker.amplitude * wave).max(0.0);
+
+        if let Some(point) = point {
+            point.intensity = intensity;
+        }
+        if let Some(spot) = spot {
+            spot.intensity = intensity;
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::f32::consts::TAU;
+
+use glam::{Quat, Vec3};
+use hecs::{Entity, World};
+
+use crate::components::{ArmAim, CharacterBody, Grounded, LocalTransform, Locomotion, Velocity};
+
+/// Distance covered per full stride cycle (two steps), in world units.
+const STRIDE_LENGTH: f32 = 1.6;
+/// Speed at which the swing reaches full amplitude; slower movement swings less.
+const FULL_SWING_SPEED: f32 = 6.0;
+/// How quickly the tracked velocity follows the body's (1/s); smooths stops and turns.
+const VELOCITY_SMOOTHING: f32 = 10.0;
+/// Peak upper-leg swing walking forward/back and stepping sideways (radians).
+const LEG_SWING: f32 = 0.6;
+const LEG_SIDE_SWING: f32 = 0.35;
+/// Peak knee bend while a foot is lifted (radians).
+const KNEE_BEND: f32 = 0.8;
+/// Peak arm swing walking forward/back and sideways (radians). Arms barely move when
+/// strafing.
+const ARM_SWING: f32 = 0.5;
+const ARM_SIDE_SWING: f32 = 0.1;
+
+/// Walk cycle for characters with a `CharacterBody`. Horizontal velocity is taken into the
+/// body's frame and split into a forward part (+Z) and a sideways part (+X), each driving its
+/// own leg swing from the same stride phase: forward walking swings the legs about X, going
+/// backwards plays the same cycle mirrored, strafing swings them about Z so they step out
+/// and across, and diagonals blend the two by their share of the speed. Arms swing against
+/// the legs, mostly with the forward part.
+///
+/// Runs before `arm_aim_system`, which blends aiming over the arm swing by `ArmAim::weight`.
+pub fn locomotion_system(world: &mut World, dt: f32) {
+    struct Pose {
+        /// (upper leg, lower leg, rest position, side sign), left then right.
+        legs: [(Entity, Entity, Vec3, f32); 2],
+        arms: [Entity; 2],
+        phase: f32,
+        forward: f32,
+        side: f32,
+        hip_pivot: f32,
+        aim: Option<(f32, [Quat; 2])>,
+    }
+
+    let blend = 1.0 - (-VELOCITY_SMOOTHING * dt).exp();
+    let mut poses = Vec::new();
+    for (_entity, (local, velocity, loco, body, grounded, aim)) in world.query_mut::<(
+        &LocalTransform,
+        &Velocity,
+        &mut Locomotion,
+        &CharacterBody,
+        Option<&Grounded>,
+        Option<&ArmAim>,
+    )>() {
+        // No stride in the air: the limbs settle back to rest.
+        let horizontal = if grounded.is_some() {
+            let v = local.rotation.inverse() * velocity.0;
+            Vec3::new(v.x, 0.0, v.z)
+        } else {
+            Vec3::ZERO
+        };
+        loco.velocity = loco.velocity.lerp(horizontal, blend);
+        let speed = loco.velocity.length();
+        loco.phase = (loco.phase + speed / STRIDE_LENGTH * TAU * dt) % TAU;
+
+        // Forward and sideways parts of the swing, signed so backpedalling mirrors the stride.
+        let amount = (speed / FULL_SWING_SPEED).min(1.0);
+        let dir = loco.velocity.normalize_or_zero();
+        let (forward, side) = (dir.z * amount, dir.x * amount);
+        poses.push(Pose {
+            legs: [
+                (body.left_upper_leg, body.left_lower_leg, loco.hips[0], 1.0),
+                (body.right_upper_leg, body.right_lower_leg, loco.hips[1], -1.0),
+            ],
+            arms: [body.left_upper_arm, body.right_upper_arm],
+            phase: loco.phase,
+            forward,
+            side,
+            hip_pivot: loco.hip_pivot,
+            aim: aim.map(|aim| (aim.weight, [aim.left_rest, aim.right_rest])),
+        });
+    }
+
+    for Pose { legs, arms, phase, forward, side, hip_pivot, aim } in poses {
+        for (upper, lower, hip, sign) in legs {
+            // The legs run half a cycle apart.
+            let swing = phase.sin() * sign;
+            // Negative X rotation carries the hanging leg forward; positive Z carries it left.
+            let rotation = Quat::from_rotation_x(-LEG_SWING * forward * swing)
+                * Quat::from_rotation_z(LEG_SIDE_SWING * side * swing);
+            if let Ok(mut lt) = world.get::<&mut LocalTransform>(upper) {
+                // Pivot at the hip rather than the middle of the thigh.
+                let pivot = Vec3::Y * hip_pivot;
+                lt.rotation = rotation;
+                lt.position = hip + pivot - rotation * pivot;
+            }
+            // Bend the knee while this leg swings through.
+            let lift = (phase.cos() * sign).max(0.0) * (forward.abs() + side.abs()).min(1.0);
+            if let Ok(mut lt) = world.get::<&mut LocalTransform>(lower) {
+                lt.rotation = Quat::from_rotation_x(KNEE_BEND * lift);
+            }
+        }
+
+        let Some((weight, rests)) = aim else {
+            continue;
+        };
+        for ((arm, rest), sign) in arms.into_iter().zip(rests).zip([1.0, -1.0]) {
+            // Each arm swings with the opposite leg.
+            let swing = -phase.sin() * sign;
+            let rotation = rest
+                * Quat::from_rotation_x(-ARM_SWING * forward * swing)
+                * Quat::from_rotation_z(ARM_SIDE_SWING * side * swing);
+            if let Ok(mut lt) = world.get::<&mut LocalTransform>(arm) {
+                lt.rotation = rotation.slerp(lt.rotation, weight);
+            }
+        }
+    }
+}
@@ -1,13 +1,41 @@
+mod arm_aim;
+mod broadphase;
 mod collision;
+mod combat;
+mod decals;
+mod editor;
 mod grab;
+mod lifetime;
+mod light_edit;
+mod lighting;
+mod locomotion;
 mod physics;
+mod physics_debug;
 mod player;
 mod raycast;
+mod toggles;
 mod transform;
 
-pub use grab::grab_throw_system;
-pub use collision::collision_system;
-pub use physics::{physics_step, PHYSICS_DT};
+pub use arm_aim::arm_aim_system;
+pub use grab::{drop_held, grab_throw_system};
+pub use collision::{collision_system, find_free_spawn, sweep_sphere_static, ContactTracker};
+pub use combat::{
+    apply_hit_impulses, damage_number_system, spawn_damage_numbers, sword_attack_system,
+};
+pub use decals::footprint_system;
+pub use editor::{EntityDrag, GridSnap, DEFAULT_SNAP_STEP};
+pub use lifetime::lifetime_system;
+pub use light_edit::LightEditor;
+pub use lighting::flicker_system;
+pub use locomotion::locomotion_system;
+pub use physics::{
+    physics_step, PhysicsConfig, DEFAULT_REST_THRESHOLD, DEFAULT_RESTITUTION_FALLOFF,
+    DEFAULT_SOLVER_ITERATIONS, PHYSICS_DT,
+};
+pub use physics_debug::{PhysicsDebug, DEFAULT_VELOCITY_ARROW_SCALE};
 pub use player::{grounded_system, player_movement_system, player_state_system};
-pub use raycast::raycast_static;
-pub use transform::transform_propagation_system;
+pub use raycast::{raycast_screen, raycast_static, screen_ray};
+pub use toggles::{SystemKind, SystemToggles};
+pub use transform::{
+    billboard_system, draw_hierarchy, transform_propagation_system, TransformGuard,
+};
@@ -1,24 +1,87 @@
 use glam::Vec3;
 use hecs::{Entity, World};
 
-use crate::components::{Acceleration, Drag, GravityAffected, Held, LocalTransform, PreviousPosition, Velocity};
+use crate::components::{
+    Acceleration, Drag, GravityAffected, Held, LocalTransform, Noclip, NoclipRecovery,
+    PreviousPosition, QuadraticDrag, Velocity,
+};
+use crate::engine::world_up::WorldUp;
+
+use super::broadphase::DEFAULT_CELL_SIZE;
 
 pub const PHYSICS_DT: f32 = 1.0 / 60.0;
-const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+const DEFAULT_GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+/// Closing speed (m/s) below which contacts don't bounce at all.
+pub const DEFAULT_REST_THRESHOLD: f32 = 0.5;
+/// Velocity-solver passes per tick; one matches the original single pass.
+pub const DEFAULT_SOLVER_ITERATIONS: usize = 1;
+/// Speed range (m/s) above the rest threshold over which bounces fade in to full restitution.
+pub const DEFAULT_RESTITUTION_FALLOFF: f32 = 1.0;
+
+/// Global physics settings, owned by the app and passed to the systems that need them.
+pub struct PhysicsConfig {
+    /// Gravity acceleration (m/s²). Any direction; "up" is derived from it.
+    pub gravity: Vec3,
+    /// Process colliders in `Entity` order instead of hecs archetype order, so the same
+    /// spawn sequence gives bit-identical results across runs (replay, headless tests).
+    /// Costs a sort per tick, so it's off by default.
+    pub deterministic: bool,
+    /// Edge length of the collision broadphase's grid cells (world units). Around the size
+    /// of a typical dynamic body's neighbourhood; see `SpatialGrid`.
+    pub broadphase_cell_size: f32,
+    /// Contacts closing slower than this (m/s) come to rest instead of bouncing.
+    pub rest_threshold: f32,
+    /// Width (m/s) of the band above `rest_threshold` where restitution ramps up to its
+    /// full value; 0 switches abruptly between resting and bouncing.
+    pub restitution_falloff: f32,
+    /// Passes of the collision velocity solver per tick. More passes let impulses propagate
+    /// through stacks and piles so they hold still, but each costs another sweep over the
+    /// tick's contacts.
+    pub solver_iterations: usize,
+    /// World up axis. Set through `set_world_up`, which points gravity down along it.
+    pub world_up: WorldUp,
+}
+
+impl PhysicsConfig {
+    pub fn new() -> Self {
+        Self {
+            gravity: DEFAULT_GRAVITY,
+            deterministic: false,
+            broadphase_cell_size: DEFAULT_CELL_SIZE,
+            rest_threshold: DEFAULT_REST_THRESHOLD,
+            restitution_falloff: DEFAULT_RESTITUTION_FALLOFF,
+            solver_iterations: DEFAULT_SOLVER_ITERATIONS,
+            world_up: WorldUp::Y,
+        }
+    }
+
+    /// Change the world up axis, turning gravity (same magnitude) to pull straight down it.
+    pub fn set_world_up(&mut self, up: WorldUp) {
+        self.world_up = up;
+        self.gravity = -up.vector() * self.gravity.length();
+    }
+
+    /// Unit vector opposite gravity, used for grounded checks and jumping.
+    /// Falls back to the world up axis when gravity is zero.
+    pub fn up(&self) -> Vec3 {
+        (-self.gravity).try_normalize().unwrap_or(self.world_up.vector())
+    }
+}
 
 /// Integrates one fixed physics step (PHYSICS_DT seconds) for all dynamic entities.
 ///
 /// Snapshots previous positions for render interpolation, applies gravity, acceleration,
-/// drag, and semi-implicit Euler integration.  Does NOT run collision detection — the
-/// caller is responsible for calling `collision_system` after each `physics_step` and for
-/// managing the fixed-timestep accumulator.
-pub fn physics_step(world: &mut World) {
+/// linear and quadratic drag, and semi-implicit Euler integration.  Does NOT run collision
+/// detection — the caller is responsible for calling `collision_system` after each
+/// `physics_step` and for managing the fixed-timestep accumulator.
+pub fn physics_step(world: &mut World, config: &PhysicsConfig) {
     // Snapshot previous positions for render interpolation.
     // Collect first (drops the borrow), then insert/update.
     let prev_snapshots: Vec<(Entity, Vec3)> = world
         .query::<&LocalTransform>()
         .with::<&Velocity>()
         .without::<&Held>()
+        .without::<&Noclip>()
         .iter()
         .map(|(e, lt)| (e, lt.position))
         .collect();
@@ -29,21 +92,23 @@ pub fn physics_step(world: &mut World) {
     }
 
     // Integrate velocity + position
-    for (_entity, (local, vel, accel, gravity, drag, held)) in world
+    for (_entity, (local, vel, accel, gravity, drag, air_drag, held, noclip)) in world
         .query_mut::<(
             &mut LocalTransform,
             &mut Velocity,
             Option<&Acceleration>,
             Option<&GravityAffected>,
             Option<&Drag>,
+            Option<&QuadraticDrag>,
             Option<&Held>,
+            Option<&Noclip>,
         )>()
     {
-        if held.is_some() {
+        if held.is_some() || noclip.is_some() {
             continue;
         }
         if gravity.is_some() {
-            vel.0 += GRAVITY * PHYSICS_DT;
+            vel.0 += config.gravity * PHYSICS_DT;
         }
         if let Some(accel) = accel {
             vel.0 += accel.0 * PHYSICS_DT;
@@ -53,7 +118,25 @@ pub fn physics_step(world: &mut World) {
             let damping = (1.0 - drag.0 * PHYSICS_DT).max(0.0);
             vel.0 *= damping;
         }
+        // Quadratic drag, dv/dt = -k|v|v, integrated implicitly: v /= 1 + k|v|dt. This only
+        // ever shrinks the speed, so a large k or a fast body can't flip the velocity over.
+        if let Some(air_drag) = air_drag {
+            let k = air_drag.0.max(0.0);
+            vel.0 /= 1.0 + k * vel.0.length() * PHYSICS_DT;
+        }
         // Semi-implicit Euler: update velocity first, then position
         local.position += vel.0 * PHYSICS_DT;
     }
+
+    // Count down post-noclip recovery windows.
+    let mut recovered = Vec::new();
+    for (entity, recovery) in world.query_mut::<&mut NoclipRecovery>() {
+        recovery.0 -= PHYSICS_DT;
+        if recovery.0 <= 0.0 {
+            recovered.push(entity);
+        }
+    }
+    for entity in recovered {
+        let _ = world.remove_one::<NoclipRecovery>(entity);
+    }
 }
@@ -0,0 +1,116 @@
+use glam::Vec3;
+use hecs::World;
+
+use crate::components::{Collider, CollisionEvent, GlobalTransform, Static, Velocity};
+use crate::renderer::GizmoRenderer;
+
+/// Seconds of travel a velocity arrow shows by default: a body's arrow reaches where it will
+/// be this long from now.
+pub const DEFAULT_VELOCITY_ARROW_SCALE: f32 = 0.1;
+/// Longest velocity arrow drawn (world units), however fast the body.
+const MAX_ARROW_LENGTH: f32 = 3.0;
+/// Arrowhead size as a fraction of the arrow, and its cap (world units).
+const ARROW_HEAD: f32 = 0.2;
+const MAX_ARROW_HEAD: f32 = 0.25;
+/// Half-size of the cross marking a contact point, and length of its normal line.
+const CONTACT_SIZE: f32 = 0.08;
+const NORMAL_LENGTH: f32 = 0.5;
+
+const VELOCITY_COLOR: Vec3 = Vec3::new(0.2, 0.9, 1.0);
+const CONTACT_COLOR: Vec3 = Vec3::new(1.0, 0.2, 0.2);
+const NORMAL_COLOR: Vec3 = Vec3::new(1.0, 0.9, 0.2);
+
+/// Overlay of dynamic bodies' velocities and the frame's collision contacts, drawn with
+/// gizmo lines. Contacts are recorded as the physics ticks produce them and drawn (then
+/// forgotten) with the next frame.
+pub struct PhysicsDebug {
+    visible: bool,
+    velocity_scale: f32,
+    /// Contact points and normals (from `entity_a` toward `entity_b`) since the last draw.
+    contacts: Vec<(Vec3, Vec3)>,
+}
+
+impl PhysicsDebug {
+    pub fn new() -> Self {
+        Self { visible: false, velocity_scale: DEFAULT_VELOCITY_ARROW_SCALE, contacts: Vec::new() }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.contacts.clear();
+    }
+
+    /// Arrow length per unit of speed (seconds).
+    pub fn set_velocity_scale(&mut self, scale: f32) {
+        self.velocity_scale = scale.max(0.0);
+    }
+
+    /// Remember where `events` touch, for the next `draw`. Call right after detection, while
+    /// the bodies are still where the contacts were found.
+    pub fn record(&mut self, world: &World, events: &[CollisionEvent]) {
+        if !self.visible {
+            return;
+        }
+        for event in events {
+            let point = contact_point(world, event);
+            self.contacts.extend(point.map(|p| (p, event.contact_normal)));
+        }
+    }
+
+    pub fn draw(&mut self, world: &World, gizmos: &mut GizmoRenderer) {
+        if !self.visible {
+            return;
+        }
+        for (_entity, (gt, velocity)) in
+            world.query::<(&GlobalTransform, &Velocity)>().without::<&Static>().iter()
+        {
+            let arrow = (velocity.0 * self.velocity_scale).clamp_length_max(MAX_ARROW_LENGTH);
+            if arrow.length_squared() > 1e-6 {
+                draw_arrow(gizmos, gt.0.w_axis.truncate(), arrow, VELOCITY_COLOR);
+            }
+        }
+        for (point, normal) in self.contacts.drain(..) {
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                let offset = axis * CONTACT_SIZE;
+                gizmos.line(point - offset, point + offset, CONTACT_COLOR);
+            }
+            gizmos.line(point, point + normal * NORMAL_LENGTH, NORMAL_COLOR);
+        }
+    }
+}
+
+fn draw_arrow(gizmos: &mut GizmoRenderer, from: Vec3, arrow: Vec3, color: Vec3) {
+    let tip = from + arrow;
+    gizmos.line(from, tip, color);
+    let length = arrow.length();
+    let dir = arrow / length;
+    let head = (length * ARROW_HEAD).min(MAX_ARROW_HEAD);
+    let (u, v) = dir.any_orthonormal_pair();
+    for side in [u, -u, v, -v] {
+        gizmos.line(tip, tip - dir * head + side * head * 0.5, color);
+    }
+}
+
+/// Approximate contact point: the deepest point of one body along the contact normal, moved
+/// back by half the penetration. Events don't carry a point of their own, so this stands in
+/// for one; it is exact for spheres and close enough elsewhere to see where things touch.
+fn contact_point(world: &World, event: &CollisionEvent) -> Option<Vec3> {
+    let surface = |entity, normal: Vec3| {
+        let collider = world.get::<&Collider>(entity).ok()?;
+        let (_, rotation, center) =
+            world.get::<&GlobalTransform>(entity).ok()?.0.to_scale_rotation_translation();
+        let reach = match *collider {
+            Collider::Sphere { radius } => radius,
+            Collider::Capsule { radius, height } => {
+                radius + (rotation * Vec3::Y).dot(normal).abs() * height * 0.5
+            }
+            Collider::Box { half_extents } => (rotation.inverse() * normal).abs().dot(half_extents),
+            Collider::Plane { .. } => return None,
+        };
+        Some(center + normal * reach)
+    };
+    let half_depth = event.contact_normal * event.penetration_depth * 0.5;
+    surface(event.entity_a, event.contact_normal)
+        .map(|p| p - half_depth)
+        .or_else(|| surface(event.entity_b, -event.contact_normal).map(|p| p + half_depth))
+}
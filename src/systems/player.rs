@@ -1,12 +1,15 @@
 use glam::{Quat, Vec3};
-use hecs::World;
-use sdl2::keyboard::Scancode;
+use hecs::{Entity, World};
 
-use crate::camera::Camera;
+use crate::camera::{Camera, Perspective};
 use crate::components::{
-    CollisionEvent, Grounded, LocalTransform, Parent, Player, PlayerFsm, PlayerState, Velocity,
+    CharacterBody, CollisionEvent, GrabState, Grounded, LocalTransform, Parent, Player, PlayerFsm,
+    PlayerState, SwordCombo, SwordPosition, SwordState, Velocity,
 };
-use crate::engine::input::InputState;
+use crate::engine::input::{InputBuffer, InputState};
+use crate::engine::input_map::{GameAction, InputMap};
+
+use super::physics::PhysicsConfig;
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -15,12 +18,30 @@ use crate::engine::input::InputState;
 const PLAYER_WALK_SPEED: f32 = 6.0;
 const PLAYER_RUN_SPEED: f32 = 10.0;
 const JUMP_IMPULSE: f32 = 7.0;
-
-// Stub durations for states not yet triggerable from input.
-// These keep the match exhaustive and ready for the issues that add them.
+const DASH_SPEED: f32 = 16.0;
 const DASH_DURATION: f32 = 0.2;
+
+// Input buffer windows: how early a press may arrive and still trigger the action.
+const JUMP_BUFFER: f32 = 0.15;
+const DASH_BUFFER: f32 = 0.15;
+const ATTACK_BUFFER: f32 = 0.2;
+
+// Fixed durations of the timed states (seconds).
 const LANDING_DURATION: f32 = 0.05; // short — just enough for a skid; no animation yet
 const SHEATHE_DURATION: f32 = 0.3;
+pub const ATTACK_DURATION: f32 = 0.45;
+
+// Facing the movement direction (`Camera::face_movement`): the body eases toward it at
+// TURN_SHARPNESS (1/s, exponential) but never trails it by more than MAX_TURN_LAG (radians),
+// so a sudden reversal still swings round within a few frames.
+const TURN_SHARPNESS: f32 = 12.0;
+const MAX_TURN_LAG: f32 = std::f32::consts::FRAC_PI_2;
+
+// Light combo: clicks within COMBO_WINDOW of a swing ending (or during it) chain up to
+// COMBO_LENGTH swings; the last one is followed by COMBO_RECOVERY of lockout.
+pub const COMBO_LENGTH: u8 = 3;
+const COMBO_WINDOW: f32 = 0.35;
+const COMBO_RECOVERY: f32 = 0.5;
 
 // Air control — reduced max speed + acceleration-based steering (not instant override)
 const AIR_CONTROL_SPEED: f32 = 4.0;  // max speed achievable through air input
@@ -33,8 +54,11 @@ const AIR_ACCELERATION: f32 = 10.0;  // m/s² added per second toward desired di
 /// Context passed to [`PlayerState::next`] each frame.
 pub struct PlayerCtx<'a> {
     pub input: &'a InputState,
+    pub map: &'a InputMap,
     pub grounded: bool,
     pub velocity: Vec3,
+    /// Unit vector opposite gravity.
+    pub up: Vec3,
     pub dt: f32,
 }
 
@@ -50,6 +74,7 @@ impl PlayerState {
             Self::Landing { timer }     => *timer += dt,
             Self::Sheathing { timer }   => *timer += dt,
             Self::Unsheathing { timer } => *timer += dt,
+            Self::Attacking { timer, .. } => *timer += dt,
             _ => {}
         }
     }
@@ -60,12 +85,13 @@ impl PlayerState {
     /// Global transitions (jump, walk-off-edge) are checked separately in
     /// [`check_global_transitions`] and evaluated first.
     pub fn next(&self, ctx: &PlayerCtx) -> Option<PlayerState> {
-        let moving = ctx.input.is_key_held(Scancode::W)
-            || ctx.input.is_key_held(Scancode::A)
-            || ctx.input.is_key_held(Scancode::S)
-            || ctx.input.is_key_held(Scancode::D);
+        let held = |action| ctx.map.is_action_held(ctx.input, action);
+        let moving = held(GameAction::MoveForward)
+            || held(GameAction::MoveLeft)
+            || held(GameAction::MoveBack)
+            || held(GameAction::MoveRight);
 
-        let sprinting = ctx.input.is_key_held(Scancode::LShift);
+        let sprinting = held(GameAction::Sprint);
 
         match self {
             Self::Idle => {
@@ -92,8 +118,8 @@ impl PlayerState {
 
             // Jump-to-fall: velocity turned non-positive, or key released early.
             Self::Jumping { has_released_jump } => {
-                let key_up = !ctx.input.is_key_held(Scancode::Space);
-                if ctx.velocity.y <= 0.0 || (key_up && !*has_released_jump) {
+                let key_up = !held(GameAction::Jump);
+                if ctx.velocity.dot(ctx.up) <= 0.0 || (key_up && !*has_released_jump) {
                     Some(Self::Falling)
                 } else {
                     None
@@ -117,6 +143,12 @@ impl PlayerState {
                 if *timer >= SHEATHE_DURATION { Some(Self::Idle) }
                 else                          { None }
             }
+
+            // Swing commits for its full duration.
+            Self::Attacking { timer, .. } => {
+                if *timer >= ATTACK_DURATION { Some(Self::Idle) }
+                else                         { None }
+            }
         }
     }
 
@@ -138,6 +170,8 @@ impl PlayerState {
             Self::Running => Some(PLAYER_RUN_SPEED),
             // Airborne: handled by is_airborne() path — should not reach here.
             Self::Jumping { .. } | Self::Falling => None,
+            // Attacking roots the player in place for the swing.
+            Self::Attacking { .. } => Some(0.0),
             // Locked states (Dashing, Landing, Sheathing, Unsheathing):
             // leave velocity alone so momentum carries through the state.
             _ => None,
@@ -145,11 +179,16 @@ impl PlayerState {
     }
 
     /// Whether jump input is accepted in this state.
-    /// Landing is included so a buffered jump (Space held through landing)
+    /// Landing is included so a buffered jump (jump held through landing)
     /// fires on the first frame of ground contact.
     pub fn can_jump(&self) -> bool {
         matches!(self, Self::Idle | Self::Walking | Self::Running | Self::Landing { .. })
     }
+
+    /// Whether a sword swing may start from this state.
+    pub fn can_attack(&self) -> bool {
+        matches!(self, Self::Idle | Self::Walking | Self::Running | Self::Landing { .. })
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -157,17 +196,51 @@ impl PlayerState {
 // ---------------------------------------------------------------------------
 
 /// Returns a transition that should fire regardless of current state, or `None`.
-/// Jump and walk-off-edge are global because they can fire from multiple states.
+/// Jump, dash, attack and walk-off-edge are global because they can fire from multiple
+/// states.
 fn check_global_transitions(
     state: &PlayerState,
     input: &InputState,
+    map: &InputMap,
+    buffer: &mut InputBuffer,
     grounded: bool,
+    horizontal: Vec3,
+    attack_combo: Option<u8>,
 ) -> Option<PlayerState> {
     // Jump: from any grounded state that permits it.
-    // Using is_key_held (not just KeyPressed) so holding Space through a fall
-    // immediately re-triggers the jump on landing — a simple jump buffer.
-    if grounded && state.can_jump() && input.is_key_held(Scancode::Space) {
-        return Some(PlayerState::Jumping { has_released_jump: false });
+    // A buffered press (tapped just before landing) counts, and so does holding jump
+    // through a fall, which re-triggers the jump on landing. The buffered press is
+    // consumed either way so it can't fire a second jump later.
+    if grounded && state.can_jump() {
+        let buffered = buffer.consume_buffered(GameAction::Jump, JUMP_BUFFER);
+        if buffered || map.is_action_held(input, GameAction::Jump) {
+            return Some(PlayerState::Jumping { has_released_jump: false });
+        }
+    }
+
+    // Dash: a burst along the current ground direction of travel (`horizontal` is the
+    // velocity with its world-up component removed).
+    if grounded
+        && state.can_jump()
+        && horizontal.length_squared() > 0.01
+        && buffer.consume_buffered(GameAction::Dash, DASH_BUFFER)
+    {
+        return Some(PlayerState::Dashing {
+            direction: horizontal.normalize(),
+            timer: 0.0,
+            cooldown_remaining: 0.0,
+        });
+    }
+
+    // Attack: left click with the sword wielded and hands otherwise free.
+    // `attack_combo` is the combo swing to start, or `None` while attacks are locked out.
+    if let Some(combo_index) = attack_combo {
+        if grounded
+            && state.can_attack()
+            && buffer.consume_buffered(GameAction::Attack, ATTACK_BUFFER)
+        {
+            return Some(PlayerState::Attacking { timer: 0.0, combo_index, hits: Vec::new() });
+        }
     }
 
     // Walked off an edge: was in a ground-locomotion state but ground was lost.
@@ -184,36 +257,132 @@ fn check_global_transitions(
 // Systems
 // ---------------------------------------------------------------------------
 
+/// A combo swing just finished. Returns the next swing if a click was queued during it;
+/// otherwise opens the chain window (or, after the final swing, starts recovery).
+fn end_swing(combo: &mut SwordCombo, finished: u8) -> Option<PlayerState> {
+    let next_index = finished + 1;
+    if next_index >= COMBO_LENGTH {
+        *combo = SwordCombo { recovery: COMBO_RECOVERY, ..SwordCombo::new() };
+        return None;
+    }
+    if std::mem::take(&mut combo.queued) {
+        let hits = Vec::new();
+        return Some(PlayerState::Attacking { timer: 0.0, combo_index: next_index, hits });
+    }
+    combo.next_index = next_index;
+    combo.window = COMBO_WINDOW;
+    None
+}
+
 /// Drive player FSM transitions. Runs **before** `player_movement_system`.
 ///
 /// Timing note: `fsm.tick(dt)` is called **first** each frame so that the
 /// `just_entered` flag stays `true` for the entire frame a transition fires,
 /// allowing downstream systems (movement, animation) to react on the same frame.
-pub fn player_state_system(world: &mut World, input: &InputState, dt: f32) {
-    for (_e, (fsm, grounded, vel)) in
-        world.query_mut::<(&mut PlayerFsm, Option<&Grounded>, &mut Velocity)>()
-    {
+pub fn player_state_system(
+    world: &mut World,
+    input: &InputState,
+    map: &InputMap,
+    buffer: &mut InputBuffer,
+    physics: &PhysicsConfig,
+    dt: f32,
+) {
+    let up = physics.up();
+    let world_up = physics.world_up.vector();
+
+    // Players whose sword is wielded and who aren't holding anything can attack.
+    let sword_ready: Vec<Entity> = world
+        .query::<(&PlayerFsm, &CharacterBody, &GrabState)>()
+        .iter()
+        .filter(|(_e, (_fsm, body, grab))| {
+            grab.held_entity.is_none()
+                && world
+                    .get::<&SwordState>(body.sword)
+                    .map(|s| s.position == SwordPosition::Wielded)
+                    .unwrap_or(false)
+        })
+        .map(|(e, _)| e)
+        .collect();
+
+    for (entity, (fsm, grounded, vel, mut combo)) in world.query_mut::<(
+        &mut PlayerFsm,
+        Option<&Grounded>,
+        &mut Velocity,
+        Option<&mut SwordCombo>,
+    )>() {
         let is_grounded = grounded.is_some();
         let velocity = vel.0;
 
         // 1. Advance elapsed timer and clear last frame's just_entered flag.
         fsm.tick(dt);
 
-        // 2. Global transitions (jump, walk-off-edge) take priority.
-        let global_next = check_global_transitions(&fsm.state, input, is_grounded);
+        // Combo timers. A click mid-swing is queued rather than dropped.
+        let sword_ready = sword_ready.contains(&entity);
+        if !sword_ready {
+            // Clicks while sheathed or holding something (throw wind-up) aren't attacks.
+            buffer.clear(GameAction::Attack);
+        }
+        let mut attack_combo = sword_ready.then_some(0);
+        if let Some(combo) = combo.as_deref_mut() {
+            combo.window = (combo.window - dt).max(0.0);
+            combo.recovery = (combo.recovery - dt).max(0.0);
+            if combo.window <= 0.0 {
+                combo.next_index = 0;
+            }
+            if matches!(fsm.state, PlayerState::Attacking { .. })
+                && buffer.consume_buffered(GameAction::Attack, ATTACK_BUFFER)
+            {
+                combo.queued = true;
+            }
+            attack_combo =
+                attack_combo.filter(|_| combo.recovery <= 0.0).map(|_| combo.next_index);
+        }
+
+        // 2. Global transitions (jump, dash, attack, walk-off-edge) take priority.
+        let global_next = check_global_transitions(
+            &fsm.state,
+            input,
+            map,
+            buffer,
+            is_grounded,
+            velocity - world_up * velocity.dot(world_up),
+            attack_combo,
+        );
 
         if let Some(next) = global_next {
-            // Apply jump impulse here so movement_system never needs to.
-            if matches!(next, PlayerState::Jumping { .. }) {
-                vel.0.y = JUMP_IMPULSE;
+            // Apply jump/dash impulses here so movement_system never needs to.
+            match &next {
+                PlayerState::Jumping { .. } => {
+                    // Replace the velocity component along "up" with the jump impulse.
+                    vel.0 += up * (JUMP_IMPULSE - vel.0.dot(up));
+                }
+                PlayerState::Dashing { direction, .. } => {
+                    vel.0 = *direction * DASH_SPEED + world_up * vel.0.dot(world_up);
+                }
+                _ => {}
+            }
+            if let (PlayerState::Attacking { .. }, Some(combo)) = (&next, combo.as_deref_mut()) {
+                combo.window = 0.0;
+                combo.queued = false;
             }
             fsm.go(next);
         } else {
             // 3. Advance intra-state timers, then check per-state transitions.
             fsm.state.tick_timers(dt);
-            let ctx = PlayerCtx { input, grounded: is_grounded, velocity, dt };
+            let ctx = PlayerCtx { input, map, grounded: is_grounded, velocity, up, dt };
             if let Some(next) = fsm.state.next(&ctx) {
-                fsm.go(next);
+                // Leaving a swing: chain a queued click into the next one (same variant,
+                // so it needs force_go) or open the combo window.
+                let chained = match (&fsm.state, combo.as_deref_mut()) {
+                    (PlayerState::Attacking { combo_index, .. }, Some(combo)) => {
+                        end_swing(combo, *combo_index)
+                    }
+                    _ => None,
+                };
+                match chained {
+                    Some(swing) => fsm.force_go(swing),
+                    None => fsm.go(next),
+                }
             }
         }
 
@@ -229,12 +398,34 @@ pub fn player_state_system(world: &mut World, input: &InputState, dt: f32) {
                 PlayerState::Landing { .. }    => "Landing",
                 PlayerState::Sheathing { .. }  => "Sheathing",
                 PlayerState::Unsheathing { .. } => "Unsheathing",
+                PlayerState::Attacking { .. }   => "Attacking",
             };
-            println!("[player_state] → {}", label);
+            match &fsm.state {
+                PlayerState::Attacking { combo_index, .. } => {
+                    println!("[player_state] → {} {}", label, combo_index + 1)
+                }
+                _ => println!("[player_state] → {}", label),
+            }
         }
     }
 }
 
+/// Body rotation about Y that faces horizontal direction `dir`.
+fn yaw_facing(dir: Vec3) -> Quat {
+    Quat::from_rotation_y(-dir.z.atan2(dir.x) + std::f32::consts::FRAC_PI_2)
+}
+
+/// Ease `current` toward `target` (see TURN_SHARPNESS and MAX_TURN_LAG).
+fn turn_toward(current: Quat, target: Quat, dt: f32) -> Quat {
+    let turned = current.slerp(target, 1.0 - (-TURN_SHARPNESS * dt).exp());
+    let lag = turned.angle_between(target);
+    if lag > MAX_TURN_LAG {
+        target.slerp(turned, MAX_TURN_LAG / lag)
+    } else {
+        turned
+    }
+}
+
 /// Apply movement based on the current FSM state.
 /// Jump velocity is already applied by `player_state_system`.
 ///
@@ -244,12 +435,15 @@ pub fn player_state_system(world: &mut World, input: &InputState, dt: f32) {
 ///   no input = velocity untouched (no air braking).
 /// - **Locked** (Landing/Dashing/Sheathing): leave velocity alone so momentum
 ///   carries through the state naturally.
+#[allow(clippy::too_many_arguments)]
 pub fn player_movement_system(
     world: &mut World,
     input: &InputState,
+    map: &InputMap,
     camera: &Camera,
     speed_multiplier: f32,
     move_block: Option<Vec3>,
+    physics: &PhysicsConfig,
     dt: f32,
 ) {
     // Movement direction is always relative to the player body, not the camera.
@@ -261,15 +455,23 @@ pub fn player_movement_system(
 
     // Build input direction once outside the loop.
     let mut move_dir = Vec3::ZERO;
-    if input.is_key_held(Scancode::W) { move_dir += forward; }
-    if input.is_key_held(Scancode::S) { move_dir -= forward; }
-    if input.is_key_held(Scancode::A) { move_dir -= right; }
-    if input.is_key_held(Scancode::D) { move_dir += right; }
+    let held = |action| map.is_action_held(input, action);
+    if held(GameAction::MoveForward) { move_dir += forward; }
+    if held(GameAction::MoveBack)    { move_dir -= forward; }
+    if held(GameAction::MoveLeft)    { move_dir -= right; }
+    if held(GameAction::MoveRight)   { move_dir += right; }
+    let (stick_x, stick_y) = input.left_stick();
+    move_dir += forward * stick_y + right * stick_x;
+
+    // Input is built in a Y-up frame; `world_align` carries it onto the world's ground plane.
+    let world_align = physics.world_up.align();
+    let world_up = physics.world_up.vector();
 
     // If the held object is pressed against a wall, remove the movement component
     // that would push the player (and thus the ball) further into that wall.
     // `move_block` points away from the wall; negative dot = moving toward it.
     if let Some(block) = move_block {
+        let block = world_align.inverse() * block;
         let dot = move_dir.dot(block);
         if dot < 0.0 {
             move_dir -= block * dot;
@@ -277,37 +479,46 @@ pub fn player_movement_system(
     }
 
     let has_input = move_dir.length_squared() > 0.0;
+
+    // Stand the body along "up" so it flips with gravity. Movement itself stays in the
+    // plane perpendicular to the world up axis.
+    let up_align = Quat::from_rotation_arc(Vec3::Y, physics.up());
     let move_dir_norm = if has_input { move_dir.normalize() } else { Vec3::ZERO };
+    let world_move = world_align * move_dir_norm;
 
     for (_entity, (local, vel, _player, fsm)) in
         world.query_mut::<(&mut LocalTransform, &mut Velocity, &Player, &PlayerFsm)>()
     {
-        // Body always faces body_yaw. During free-look this stays frozen;
+        // Body faces body_yaw, or with face_movement in third person turns toward where it's
+        // heading (and keeps that facing when it stops). During free-look this stays frozen;
         // otherwise body_yaw lerps toward camera.yaw each frame (~200 ms).
         if !camera.free_look {
-            local.rotation = Quat::from_rotation_y(-body_yaw_rad + std::f32::consts::FRAC_PI_2);
+            let yaw = if camera.face_movement && camera.perspective != Perspective::FirstPerson {
+                let current = up_align.inverse() * local.rotation;
+                let target = if has_input { yaw_facing(move_dir_norm) } else { current };
+                turn_toward(current, target, dt)
+            } else {
+                Quat::from_rotation_y(-body_yaw_rad + std::f32::consts::FRAC_PI_2)
+            };
+            local.rotation = up_align * yaw;
         }
 
+        let vertical = world_up * vel.0.dot(world_up);
         if fsm.state.is_airborne() {
             // Air control: nudge velocity toward desired direction.
             // No input = velocity preserved (no air friction from player).
             if has_input {
-                let desired_x = move_dir_norm.x * AIR_CONTROL_SPEED * speed_multiplier;
-                let desired_z = move_dir_norm.z * AIR_CONTROL_SPEED * speed_multiplier;
-                let diff_x = desired_x - vel.0.x;
-                let diff_z = desired_z - vel.0.z;
-                let dist = (diff_x * diff_x + diff_z * diff_z).sqrt();
+                let desired = world_move * AIR_CONTROL_SPEED * speed_multiplier;
+                let diff = desired - (vel.0 - vertical);
+                let dist = diff.length();
                 if dist > 0.0 {
                     let step = (AIR_ACCELERATION * dt).min(dist);
-                    vel.0.x += diff_x / dist * step;
-                    vel.0.z += diff_z / dist * step;
+                    vel.0 += diff / dist * step;
                 }
             }
         } else if let Some(speed) = fsm.state.move_speed() {
             // Ground: directly override horizontal velocity.
-            let horizontal = move_dir_norm * speed * speed_multiplier;
-            vel.0.x = horizontal.x;
-            vel.0.z = horizontal.z;
+            vel.0 = world_move * speed * speed_multiplier + vertical;
         }
         // else Locked (Landing, Dashing, Sheathing, etc.): leave velocity alone.
     }
@@ -331,10 +542,16 @@ fn find_root(world: &World, entity: hecs::Entity) -> hecs::Entity {
 /// generated, so we must NOT clear Grounded — contacts from last tick are still
 /// valid. Clearing it would trigger a spurious Falling transition every other
 /// frame on hardware faster than 60fps.
-pub fn grounded_system(world: &mut World, events: &[CollisionEvent], physics_ticks: usize) {
+pub fn grounded_system(
+    world: &mut World,
+    events: &[CollisionEvent],
+    physics_ticks: usize,
+    physics: &PhysicsConfig,
+) {
     if physics_ticks == 0 {
         return;
     }
+    let up = physics.up();
 
     // A physics tick ran — clear and rebuild from this tick's contacts.
     let players: Vec<_> = world
@@ -354,10 +571,10 @@ pub fn grounded_system(world: &mut World, events: &[CollisionEvent], physics_tic
         let a_is_player = world.get::<&Player>(root_a).is_ok();
         let b_is_player = world.get::<&Player>(root_b).is_ok();
 
-        if a_is_player && (-event.contact_normal).dot(Vec3::Y) > 0.7 {
+        if a_is_player && (-event.contact_normal).dot(up) > 0.7 {
             let _ = world.insert_one(root_a, Grounded);
         }
-        if b_is_player && event.contact_normal.dot(Vec3::Y) > 0.7 {
+        if b_is_player && event.contact_normal.dot(up) > 0.7 {
             let _ = world.insert_one(root_b, Grounded);
         }
     }
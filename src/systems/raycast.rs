@@ -1,50 +1,87 @@
-use glam::Vec3;
+use std::ops::BitOr;
+
+use glam::{Mat4, Vec2, Vec3};
 use hecs::{Entity, World};
 
-use crate::components::{Collider, GlobalTransform, Grabbable, Static};
+use crate::components::{Collider, GlobalTransform, Grabbable, Hidden, Static};
 
 #[allow(dead_code)]
 pub struct RaycastHit {
     pub entity: Entity,
     pub distance: f32,
     pub point: Vec3,
+    /// Unit surface normal at `point`.
+    pub normal: Vec3,
 }
 
-/// Cast a ray against all Grabbable entities, returning the nearest hit within max_distance.
-pub fn raycast_grabbable(
+/// Which colliders `raycast_all` considers, combined with `|`. A collider is a candidate
+/// when it is in any of the selected sets; ground planes only ever count under `PLANES`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RaycastFilter(u8);
+
+impl RaycastFilter {
+    pub const STATIC: Self = Self(1 << 0);
+    /// Everything without `Static`, grabbable objects and character body parts included.
+    pub const DYNAMIC: Self = Self(1 << 1);
+    pub const GRABBABLE: Self = Self(1 << 2);
+    /// Infinite ground planes. Opt-in, since nearly every downward ray would hit one.
+    pub const PLANES: Self = Self(1 << 3);
+    /// Not a set but a restriction: skip `Hidden` entities (the player's own body in first
+    /// person).
+    pub const VISIBLE_ONLY: Self = Self(1 << 4);
+    #[allow(dead_code)]
+    pub const ALL: Self = Self(Self::STATIC.0 | Self::DYNAMIC.0 | Self::PLANES.0);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for RaycastFilter {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Cast a ray against every collider `filter` selects, returning the nearest hit within
+/// max_distance.
+pub fn raycast_all(
     world: &World,
     origin: Vec3,
     direction: Vec3,
     max_distance: f32,
+    filter: RaycastFilter,
 ) -> Option<RaycastHit> {
     let dir = direction.normalize();
     let mut best: Option<RaycastHit> = None;
 
-    for (entity, (_grabbable, global, collider)) in
-        world.query::<(&Grabbable, &GlobalTransform, &Collider)>().iter()
-    {
-        let center = Vec3::new(global.0.w_axis.x, global.0.w_axis.y, global.0.w_axis.z);
-
-        let t = match collider {
-            Collider::Sphere { radius } => ray_sphere_intersection(origin, dir, center, *radius),
-            Collider::Capsule { radius, height } => {
-                ray_capsule_intersection(origin, dir, center, *radius, *height)
-            }
-            Collider::Box { half_extents } => {
-                ray_aabb_intersection(origin, dir, center, *half_extents)
-            }
-            Collider::Plane { .. } => None,
+    let mut query = world.query::<(
+        &Collider,
+        &GlobalTransform,
+        Option<&Static>,
+        Option<&Grabbable>,
+        Option<&Hidden>,
+    )>();
+    for (entity, (collider, global, is_static, grabbable, hidden)) in query.iter() {
+        let selected = if matches!(collider, Collider::Plane { .. }) {
+            filter.contains(RaycastFilter::PLANES)
+        } else {
+            (is_static.is_some() && filter.contains(RaycastFilter::STATIC))
+                || (is_static.is_none() && filter.contains(RaycastFilter::DYNAMIC))
+                || (grabbable.is_some() && filter.contains(RaycastFilter::GRABBABLE))
         };
+        if !selected || (hidden.is_some() && filter.contains(RaycastFilter::VISIBLE_ONLY)) {
+            continue;
+        }
 
-        if let Some(t) = t {
+        if let Some((t, normal)) = ray_collider(origin, dir, collider, global) {
             if t > 0.0 && t <= max_distance {
                 let is_closer = best.as_ref().map_or(true, |b| t < b.distance);
                 if is_closer {
-                    best = Some(RaycastHit {
-                        entity,
-                        distance: t,
-                        point: origin + dir * t,
-                    });
+                    let point = origin + dir * t;
+                    best = Some(RaycastHit { entity, distance: t, point, normal });
                 }
             }
         }
@@ -53,45 +90,128 @@ pub fn raycast_grabbable(
     best
 }
 
+/// Cast a ray against all Grabbable entities, returning the nearest hit within max_distance.
+pub fn raycast_grabbable(
+    world: &World,
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+) -> Option<RaycastHit> {
+    raycast_all(world, origin, direction, max_distance, RaycastFilter::GRABBABLE)
+}
+
 /// Cast a ray against all Static geometry, returning the nearest hit distance within max_distance.
-/// Used for camera wall-clip occlusion queries.
+/// Used for camera wall-clip occlusion queries. Infinite floor planes are skipped.
 pub fn raycast_static(
     world: &World,
     origin: Vec3,
     direction: Vec3,
     max_distance: f32,
 ) -> Option<f32> {
-    let dir = direction.normalize();
-    let mut best: Option<f32> = None;
+    raycast_all(world, origin, direction, max_distance, RaycastFilter::STATIC)
+        .map(|hit| hit.distance)
+}
 
-    for (_, (_, collider, global)) in
-        world.query::<(&Static, &Collider, &GlobalTransform)>().iter()
-    {
-        let center = Vec3::new(global.0.w_axis.x, global.0.w_axis.y, global.0.w_axis.z);
+/// Cast a ray against all Static geometry, ground planes included, returning the nearest
+/// hit with its surface normal. Used to place decals.
+pub fn raycast_surface(
+    world: &World,
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+) -> Option<RaycastHit> {
+    let filter = RaycastFilter::STATIC | RaycastFilter::PLANES;
+    raycast_all(world, origin, direction, max_distance, filter)
+}
 
-        let t = match collider {
-            Collider::Sphere { radius } => ray_sphere_intersection(origin, dir, center, *radius),
-            Collider::Capsule { radius, height } => {
-                ray_capsule_intersection(origin, dir, center, *radius, *height)
-            }
-            Collider::Box { half_extents } => {
-                ray_aabb_intersection(origin, dir, center, *half_extents)
-            }
-            // Plane colliders are infinite floors — skip them for camera occlusion.
-            Collider::Plane { .. } => None,
-        };
+/// World-space ray under a cursor at `cursor` (window pixels, top-left origin) in a
+/// `viewport`-sized window seen through `view_proj`. Returns (origin on the near plane,
+/// unit direction).
+pub fn screen_ray(view_proj: &Mat4, cursor: Vec2, viewport: Vec2) -> (Vec3, Vec3) {
+    let ndc_x = cursor.x / viewport.x * 2.0 - 1.0;
+    let ndc_y = 1.0 - cursor.y / viewport.y * 2.0;
+    let inverse = view_proj.inverse();
+    let near = inverse.project_point3(Vec3::new(ndc_x, ndc_y, -1.0));
+    let far = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 1.0));
+    (near, (far - near).normalize_or(Vec3::NEG_Z))
+}
 
-        if let Some(t) = t {
-            if t > 0.0 && t <= max_distance {
-                let is_closer = best.map_or(true, |b| t < b);
-                if is_closer {
-                    best = Some(t);
-                }
-            }
+/// Pick the nearest visible collider under the cursor, static or dynamic. Planes and
+/// `Hidden` entities (the player's own body in first person) are skipped.
+pub fn raycast_screen(
+    world: &World,
+    view_proj: &Mat4,
+    cursor: Vec2,
+    viewport: Vec2,
+    max_distance: f32,
+) -> Option<RaycastHit> {
+    let (origin, dir) = screen_ray(view_proj, cursor, viewport);
+    let filter = RaycastFilter::STATIC | RaycastFilter::DYNAMIC | RaycastFilter::VISIBLE_ONLY;
+    raycast_all(world, origin, dir, max_distance, filter)
+}
+
+/// Nearest hit of the unit ray with `collider` placed by `global`, as (distance, outward
+/// unit surface normal).
+fn ray_collider(
+    origin: Vec3,
+    dir: Vec3,
+    collider: &Collider,
+    global: &GlobalTransform,
+) -> Option<(f32, Vec3)> {
+    let center = global.0.w_axis.truncate();
+    match collider {
+        Collider::Sphere { radius } => {
+            let t = ray_sphere_intersection(origin, dir, center, *radius)?;
+            Some((t, (origin + dir * t - center).normalize_or(Vec3::Y)))
+        }
+        Collider::Capsule { radius, height } => {
+            // The capsule runs along its rotated Y axis.
+            let axis = global.0.to_scale_rotation_translation().1 * Vec3::Y;
+            let t = ray_capsule_intersection(origin, dir, center, axis, *radius, *height)?;
+            let point = origin + dir * t;
+            let half_h = height * 0.5;
+            let along = (point - center).dot(axis).clamp(-half_h, half_h);
+            Some((t, (point - (center + axis * along)).normalize_or(axis)))
+        }
+        Collider::Box { half_extents } => {
+            // Boxes may be rotated: intersect in the box's own frame, where it is an AABB.
+            let rotation = global.0.to_scale_rotation_translation().1;
+            let to_local = rotation.inverse();
+            let local_origin = to_local * (origin - center);
+            let local_dir = to_local * dir;
+            let t = ray_aabb_intersection(local_origin, local_dir, Vec3::ZERO, *half_extents)?;
+            let normal = box_face_normal(*half_extents, local_origin + local_dir * t);
+            Some((t, rotation * normal))
+        }
+        Collider::Plane { normal, offset } => {
+            Some((ray_plane_intersection(origin, dir, *normal, *offset)?, *normal))
         }
     }
+}
 
-    best
+/// Outward normal of the face of a box (centred at the origin, axis-aligned) nearest a
+/// surface point `local`: the one whose slab the point is deepest into, relative to its
+/// extent.
+fn box_face_normal(half_extents: Vec3, local: Vec3) -> Vec3 {
+    let local = local / half_extents;
+    let abs = local.abs();
+    if abs.x >= abs.y && abs.x >= abs.z {
+        Vec3::X * local.x.signum()
+    } else if abs.y >= abs.z {
+        Vec3::Y * local.y.signum()
+    } else {
+        Vec3::Z * local.z.signum()
+    }
+}
+
+/// Front-face hit of the plane `p · normal = offset`.
+fn ray_plane_intersection(origin: Vec3, dir: Vec3, normal: Vec3, offset: f32) -> Option<f32> {
+    let denom = dir.dot(normal);
+    if denom >= -1e-6 {
+        return None;
+    }
+    let t = (offset - origin.dot(normal)) / denom;
+    (t > 0.0).then_some(t)
 }
 
 fn ray_sphere_intersection(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
@@ -118,16 +238,18 @@ fn ray_sphere_intersection(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -
     }
 }
 
+/// `axis` is the unit direction the capsule's segment runs along.
 fn ray_capsule_intersection(
     origin: Vec3,
     dir: Vec3,
     center: Vec3,
+    axis: Vec3,
     radius: f32,
     height: f32,
 ) -> Option<f32> {
     let half_h = height * 0.5;
-    let top = center + Vec3::Y * half_h;
-    let bottom = center - Vec3::Y * half_h;
+    let top = center + axis * half_h;
+    let bottom = center - axis * half_h;
 
     // Test both hemisphere centers as spheres (approximation suitable for grab detection)
     let t_top = ray_sphere_intersection(origin, dir, top, radius);
@@ -163,3 +285,37 @@ fn ray_aabb_intersection(origin: Vec3, dir: Vec3, center: Vec3, half: Vec3) -> O
     // If tmin < 0, ray starts inside the box — return tmax
     Some(if tmin < 0.0 { tmax } else { tmin })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearer_hit_wins_and_faces_the_ray() {
+        let mut world = World::new();
+        let wall_pos = Vec3::new(0.0, 0.0, -10.0);
+        let wall = world.spawn((
+            GlobalTransform(Mat4::from_translation(wall_pos)),
+            Collider::Box { half_extents: Vec3::new(5.0, 5.0, 0.5) },
+            Static,
+        ));
+        let sphere_pos = Vec3::new(0.0, 0.0, -5.0);
+        let sphere = world.spawn((
+            GlobalTransform(Mat4::from_translation(sphere_pos)),
+            Collider::Sphere { radius: 1.0 },
+        ));
+
+        let dir = Vec3::NEG_Z;
+        let hit = raycast_all(&world, Vec3::ZERO, dir, 100.0, RaycastFilter::ALL).expect("hit");
+        assert_eq!(hit.entity, sphere);
+        assert!((hit.distance - 4.0).abs() < 1e-4);
+        assert!(hit.normal.dot(dir) < 0.0);
+        assert!(hit.normal.abs_diff_eq(Vec3::Z, 1e-4));
+
+        // The wall is still there behind it.
+        let hit = raycast_all(&world, Vec3::ZERO, dir, 100.0, RaycastFilter::STATIC).expect("hit");
+        assert_eq!(hit.entity, wall);
+        assert!((hit.distance - 9.5).abs() < 1e-4);
+        assert!(hit.normal.abs_diff_eq(Vec3::Z, 1e-4));
+    }
+}
@@ -0,0 +1,60 @@
+/// A system that can be switched off at runtime for debugging.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SystemKind {
+    Grab,
+    ArmAim,
+    Player,
+    Combat,
+    Physics,
+    Collision,
+}
+
+impl SystemKind {
+    pub const ALL: [SystemKind; 6] = [
+        SystemKind::Grab,
+        SystemKind::ArmAim,
+        SystemKind::Player,
+        SystemKind::Combat,
+        SystemKind::Physics,
+        SystemKind::Collision,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SystemKind::Grab => "grab",
+            SystemKind::ArmAim => "arm_aim",
+            SystemKind::Player => "player",
+            SystemKind::Combat => "combat",
+            SystemKind::Physics => "physics",
+            SystemKind::Collision => "collision",
+        }
+    }
+}
+
+/// Which systems `update_systems` runs. Everything starts enabled.
+///
+/// A disabled system is simply skipped, so the components it would write keep their last
+/// values: with `Physics` off, velocities are still set by player movement but never
+/// integrated; with `Collision` off, bodies fall through the world.
+pub struct SystemToggles {
+    enabled: [bool; SystemKind::ALL.len()],
+}
+
+impl SystemToggles {
+    pub fn new() -> Self {
+        Self {
+            enabled: [true; SystemKind::ALL.len()],
+        }
+    }
+
+    pub fn is_enabled(&self, kind: SystemKind) -> bool {
+        self.enabled[kind as usize]
+    }
+
+    /// Flip `kind` on/off. Returns the new state.
+    pub fn toggle(&mut self, kind: SystemKind) -> bool {
+        let slot = &mut self.enabled[kind as usize];
+        *slot = !*slot;
+        *slot
+    }
+}
@@ -1,20 +1,70 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
-use glam::Mat4;
+use glam::{Mat3, Mat4, Quat, Vec3};
 use hecs::{Entity, World};
 
-use crate::components::{Children, GlobalTransform, LocalTransform, Parent, PreviousPosition};
+use crate::components::{
+    Billboard, Children, GlobalTransform, LocalTransform, NoScaleInherit, Parent,
+    PreviousPosition,
+};
+use crate::renderer::GizmoRenderer;
+
+const BONE_COLOR: Vec3 = Vec3::new(0.3, 1.0, 0.4);
+/// Marks children whose parent no longer exists.
+const ORPHAN_COLOR: Vec3 = Vec3::new(1.0, 0.2, 0.8);
+const ORPHAN_MARKER_SIZE: f32 = 0.1;
+
+/// Catches non-finite (NaN or infinite) global transforms during propagation, so one bad
+/// value — say a NaN velocity — can't spread to an entity's whole subtree and make it
+/// vanish. The entity keeps its last good `GlobalTransform` (identity if it never had one)
+/// and is reported once.
+pub struct TransformGuard {
+    /// Entities already reported; they aren't logged again.
+    warned: HashSet<Entity>,
+}
+
+impl TransformGuard {
+    pub fn new() -> Self {
+        Self { warned: HashSet::new() }
+    }
+
+    /// `global` if it is finite, otherwise `entity`'s last good global transform.
+    fn check(&mut self, world: &World, entity: Entity, global: Mat4) -> Mat4 {
+        if global.is_finite() {
+            return global;
+        }
+        if self.warned.insert(entity) {
+            eprintln!(
+                "[transform] {entity:?} has a non-finite transform (NaN or infinite position, \
+                 rotation or scale); keeping its last good one"
+            );
+        }
+        world
+            .get::<&GlobalTransform>(entity)
+            .ok()
+            .map(|gt| gt.0)
+            .filter(Mat4::is_finite)
+            .unwrap_or(Mat4::IDENTITY)
+    }
+}
 
 /// Propagates LocalTransform down the hierarchy via BFS.
 /// Roots (entities with LocalTransform but no Parent) compute GlobalTransform
 /// from their own LocalTransform. Children inherit parent's GlobalTransform
 /// multiplied by their own LocalTransform.
 ///
+/// Scale is inherited: a child under a 2× parent renders at 2× (and its local offset is
+/// scaled too). Children tagged `NoScaleInherit` strip the parent's scale first and only
+/// inherit its rotation and translation.
+///
 /// `alpha` is the render interpolation factor (0..1): how far into the current
 /// physics step this render frame falls. Root physics entities with a
 /// `PreviousPosition` component have their translation lerped between the
 /// previous and current physics position, eliminating fixed-timestep jitter.
-pub fn transform_propagation_system(world: &mut World, alpha: f32) {
+///
+/// Non-finite results are replaced through `guard` before they reach the entity or its
+/// children.
+pub fn transform_propagation_system(world: &mut World, alpha: f32, guard: &mut TransformGuard) {
     let mut queue: VecDeque<(Entity, Mat4)> = VecDeque::new();
 
     // Phase 1: update roots and seed BFS with their children.
@@ -36,24 +86,32 @@ pub fn transform_propagation_system(world: &mut World, alpha: f32) {
         })
         .collect();
 
-    for (entity, global_mat) in &roots {
-        if let Ok(mut gt) = world.get::<&mut GlobalTransform>(*entity) {
-            gt.0 = *global_mat;
+    for (entity, global_mat) in roots {
+        let global_mat = guard.check(world, entity, global_mat);
+        if let Ok(mut gt) = world.get::<&mut GlobalTransform>(entity) {
+            gt.0 = global_mat;
         }
-        if let Ok(children) = world.get::<&Children>(*entity) {
+        if let Ok(children) = world.get::<&Children>(entity) {
             for &child in &children.0 {
-                queue.push_back((child, *global_mat));
+                queue.push_back((child, global_mat));
             }
         }
     }
 
     // Phase 2: BFS propagation
     while let Some((entity, parent_global)) = queue.pop_front() {
+        let parent_global = if world.get::<&NoScaleInherit>(entity).is_ok() {
+            let (_scale, rotation, translation) = parent_global.to_scale_rotation_translation();
+            Mat4::from_rotation_translation(rotation, translation)
+        } else {
+            parent_global
+        };
         let child_global = if let Ok(local) = world.get::<&LocalTransform>(entity) {
             parent_global * local.matrix()
         } else {
             parent_global
         };
+        let child_global = guard.check(world, entity, child_global);
 
         if let Ok(mut gt) = world.get::<&mut GlobalTransform>(entity) {
             gt.0 = child_global;
@@ -66,3 +124,76 @@ pub fn transform_propagation_system(world: &mut World, alpha: f32) {
         }
     }
 }
+
+/// Turn every `Billboard` entity's `GlobalTransform` to face `camera_pos`, keeping its world
+/// translation and scale and standing it along `up`, the world up axis. Runs after
+/// `transform_propagation_system`, so billboards under a rotated parent still face the
+/// camera in world space; their own children keep the un-billboarded frame.
+pub fn billboard_system(world: &mut World, camera_pos: Vec3, up: Vec3) {
+    for (_entity, (billboard, global)) in world.query_mut::<(&Billboard, &mut GlobalTransform)>() {
+        let (scale, rotation, translation) = global.0.to_scale_rotation_translation();
+        let mut forward = camera_pos - translation;
+        if *billboard == Billboard::Cylindrical {
+            forward -= up * forward.dot(up);
+        }
+        let Some(forward) = forward.try_normalize() else {
+            continue;
+        };
+        // Looking straight up or down: any horizontal right axis will do.
+        let right = up.cross(forward).try_normalize().unwrap_or(rotation * Vec3::X);
+        let up = forward.cross(right);
+        let facing = Quat::from_mat3(&Mat3::from_cols(right, up, forward));
+        global.0 = Mat4::from_scale_rotation_translation(scale, facing, translation);
+    }
+}
+
+/// Queue a line from every `Parent`ed entity to its parent's world position, drawing the
+/// hierarchy as a stick figure (the player rig's limb chains show up as bones). The lines are
+/// overlays, so bones inside meshes stay visible. A child whose parent has been despawned
+/// gets a cross in `ORPHAN_COLOR` instead.
+pub fn draw_hierarchy(world: &World, gizmos: &mut GizmoRenderer) {
+    for (_entity, (gt, parent)) in world.query::<(&GlobalTransform, &Parent)>().iter() {
+        let position = gt.0.w_axis.truncate();
+        match world.get::<&GlobalTransform>(parent.0) {
+            Ok(parent_gt) => {
+                gizmos.overlay_line(position, parent_gt.0.w_axis.truncate(), BONE_COLOR);
+            }
+            Err(_) => {
+                for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                    let offset = axis * ORPHAN_MARKER_SIZE;
+                    gizmos.overlay_line(position - offset, position + offset, ORPHAN_COLOR);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::add_child;
+
+    /// World-space scale of a child placed under a 2× parent, tagged `NoScaleInherit` or not.
+    fn child_scale_under_doubled_parent(no_scale_inherit: bool) -> Vec3 {
+        let mut world = World::new();
+        let mut parent_local = LocalTransform::new(Vec3::new(1.0, 2.0, 3.0));
+        parent_local.scale = Vec3::splat(2.0);
+        let parent = world.spawn((parent_local, GlobalTransform(Mat4::IDENTITY)));
+        let child_local = LocalTransform::new(Vec3::X);
+        let child = world.spawn((child_local, GlobalTransform(Mat4::IDENTITY)));
+        if no_scale_inherit {
+            world.insert_one(child, NoScaleInherit).unwrap();
+        }
+        add_child(&mut world, parent, child);
+
+        transform_propagation_system(&mut world, 1.0, &mut TransformGuard::new());
+        let global = world.get::<&GlobalTransform>(child).unwrap().0;
+        global.to_scale_rotation_translation().0
+    }
+
+    #[test]
+    fn no_scale_inherit_drops_the_parent_scale() {
+        assert!(child_scale_under_doubled_parent(false).abs_diff_eq(Vec3::splat(2.0), 1e-5));
+        assert!(child_scale_under_doubled_parent(true).abs_diff_eq(Vec3::ONE, 1e-5));
+    }
+}
@@ -0,0 +1,20 @@
+use glam::Vec3;
+use hecs::World;
+
+use crate::components::DamageNumber;
+use crate::ui::text::TextRenderer;
+use crate::ui::world_text::{draw_world_label, LabelView};
+
+const DAMAGE_COLOR: Vec3 = Vec3::new(1.0, 0.35, 0.2);
+
+/// Draw every live `DamageNumber` as a fading "-N" label at its world position.
+///
+/// Caller must set up the GL blend state.
+pub fn draw_damage_numbers(text_renderer: &mut TextRenderer, world: &World, view: &LabelView) {
+    for (_e, number) in world.query::<&DamageNumber>().iter() {
+        text_renderer.set_opacity(number.opacity());
+        let text = format!("-{:.0}", number.value);
+        draw_world_label(text_renderer, view, &text, number.world_pos, DAMAGE_COLOR);
+    }
+    text_renderer.set_opacity(1.0);
+}
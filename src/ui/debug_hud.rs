@@ -20,6 +20,10 @@ pub struct DebugHud {
     fps_timer: f32,
     /// Last computed SMA FPS, updated once per second.
     displayed_fps: f32,
+    /// Collider pairs in contact, and contacts begun / ended during the last frame.
+    contacts: (usize, usize, usize),
+    /// Velocity-solver passes per tick, and impulses it applied during the last frame.
+    solver: (usize, usize),
 }
 
 impl DebugHud {
@@ -31,6 +35,8 @@ impl DebugHud {
             fps_count: 0,
             fps_timer: 0.0,
             displayed_fps: 0.0,
+            contacts: (0, 0, 0),
+            solver: (0, 0),
         }
     }
 
@@ -61,6 +67,16 @@ impl DebugHud {
         }
     }
 
+    /// Record the frame's contact counts for display.
+    pub fn set_contacts(&mut self, active: usize, began: usize, ended: usize) {
+        self.contacts = (active, began, ended);
+    }
+
+    /// Record the collision solver's pass count and the frame's resolved contacts.
+    pub fn set_solver(&mut self, iterations: usize, resolved: usize) {
+        self.solver = (iterations, resolved);
+    }
+
     /// Render HUD lines at the top-left of the screen.
     ///
     /// `pos` — world position to display. In Player mode pass the player body
@@ -80,6 +96,10 @@ impl DebugHud {
         let line0 = format!("FPS: {:.0}", self.displayed_fps);
         let line1 = format!("Pos: {:.2} {:.2} {:.2}", pos.x, pos.y, pos.z);
         let line2 = format!("Yaw: {:.1}  Pitch: {:.1}", yaw, pitch);
+        let (active, began, ended) = self.contacts;
+        let line3 = format!("Contacts: {} (+{} -{})", active, began, ended);
+        let (iterations, resolved) = self.solver;
+        let line4 = format!("Solver: {} iter, {} resolved", iterations, resolved);
 
         text_renderer.draw_text(&line0, x, y, HUD_SCALE, HUD_COLOR, projection);
         text_renderer.draw_text(&line1, x, y + LINE_HEIGHT, HUD_SCALE, HUD_COLOR, projection);
@@ -91,5 +111,21 @@ impl DebugHud {
             HUD_COLOR,
             projection,
         );
+        text_renderer.draw_text(
+            &line3,
+            x,
+            y + LINE_HEIGHT * 3.0,
+            HUD_SCALE,
+            HUD_COLOR,
+            projection,
+        );
+        text_renderer.draw_text(
+            &line4,
+            x,
+            y + LINE_HEIGHT * 4.0,
+            HUD_SCALE,
+            HUD_COLOR,
+            projection,
+        );
     }
 }
@@ -0,0 +1,202 @@
+use glam::{Mat4, Vec3};
+use hecs::{Component, Entity, World};
+
+use crate::components::*;
+use crate::ui::text::TextRenderer;
+use crate::ui::world_text::{draw_world_label, LabelView};
+
+const BROWSER_SCALE: f32 = 1.5;
+const BROWSER_MARGIN: f32 = 8.0;
+// 8px glyph height * scale + 3px padding
+const LINE_HEIGHT: f32 = 8.0 * BROWSER_SCALE + 3.0;
+const HEADER_COLOR: Vec3 = Vec3::new(1.0, 1.0, 0.0);
+const ID_COLOR: Vec3 = Vec3::new(0.55, 0.85, 1.0);
+const COMPONENT_COLOR: Vec3 = Vec3::new(0.9, 0.9, 0.9);
+/// Indent of wrapped component lines, past the entity id column.
+const COMPONENT_INDENT: f32 = 60.0;
+/// Gap between the top of an entity's collider and its floating id label (metres).
+const LABEL_LIFT: f32 = 0.25;
+
+fn has<T: Component>(world: &World, entity: Entity) -> bool {
+    world.get::<&T>(entity).is_ok()
+}
+
+/// Every component type the browser can name, with a probe for it.
+const PROBES: &[(&str, fn(&World, Entity) -> bool)] = &[
+    ("LocalTransform", has::<LocalTransform>),
+    ("GlobalTransform", has::<GlobalTransform>),
+    ("Parent", has::<Parent>),
+    ("Children", has::<Children>),
+    ("NoScaleInherit", has::<NoScaleInherit>),
+    ("Persistent", has::<Persistent>),
+    ("Lifetime", has::<Lifetime>),
+    ("MeshHandle", has::<MeshHandle>),
+    ("Color", has::<Color>),
+    ("Checkerboard", has::<Checkerboard>),
+    ("Hidden", has::<Hidden>),
+    ("Billboard", has::<Billboard>),
+    ("NoShadowCast", has::<NoShadowCast>),
+    ("NoShadowReceive", has::<NoShadowReceive>),
+    ("XRay", has::<XRay>),
+    ("Decal", has::<Decal>),
+    ("FootprintTrail", has::<FootprintTrail>),
+    ("Velocity", has::<Velocity>),
+    ("Acceleration", has::<Acceleration>),
+    ("Mass", has::<Mass>),
+    ("GravityAffected", has::<GravityAffected>),
+    ("Collider", has::<Collider>),
+    ("Static", has::<Static>),
+    ("Restitution", has::<Restitution>),
+    ("Friction", has::<Friction>),
+    ("Drag", has::<Drag>),
+    ("QuadraticDrag", has::<QuadraticDrag>),
+    ("Grounded", has::<Grounded>),
+    ("PreviousPosition", has::<PreviousPosition>),
+    ("Noclip", has::<Noclip>),
+    ("NoclipRecovery", has::<NoclipRecovery>),
+    ("Player", has::<Player>),
+    ("PlayerFsm", has::<PlayerFsm>),
+    ("CharacterBody", has::<CharacterBody>),
+    ("GrabState", has::<GrabState>),
+    ("Grabbable", has::<Grabbable>),
+    ("Held", has::<Held>),
+    ("NoSelfCollision", has::<NoSelfCollision>),
+    ("ArmAim", has::<ArmAim>),
+    ("SwordCombo", has::<SwordCombo>),
+    ("SwordState", has::<SwordState>),
+    ("DamageNumber", has::<DamageNumber>),
+    ("DirectionalLight", has::<DirectionalLight>),
+    ("PointLight", has::<PointLight>),
+    ("SpotLight", has::<SpotLight>),
+    ("Flicker", has::<Flicker>),
+];
+
+/// Debug panel listing every entity with the names of its known components.
+///
+/// The list is rebuilt from the world each frame it's drawn and shown a page at a time;
+/// `scroll` moves through it by whole pages, so large scenes never overflow the screen.
+pub struct EntityBrowser {
+    visible: bool,
+    /// First list line shown.
+    first_line: usize,
+    /// Lines that fit on screen, from the last draw.
+    page_lines: usize,
+}
+
+impl EntityBrowser {
+    pub fn new() -> Self {
+        Self { visible: false, first_line: 0, page_lines: 1 }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Move `pages` pages down (negative: up). Clamped to the list on the next draw.
+    pub fn scroll(&mut self, pages: i32) {
+        let delta = pages.unsigned_abs() as usize * self.page_lines;
+        self.first_line = if pages < 0 {
+            self.first_line.saturating_sub(delta)
+        } else {
+            self.first_line + delta
+        };
+    }
+
+    /// Render the panel over the right half of a `width` x `height` screen.
+    ///
+    /// Caller must set up the orthographic projection and GL blend state.
+    pub fn draw(
+        &mut self,
+        text_renderer: &mut TextRenderer,
+        world: &World,
+        width: f32,
+        height: f32,
+        projection: &Mat4,
+    ) {
+        let x = width * 0.5;
+        let wrap_width = width - x - BROWSER_MARGIN - COMPONENT_INDENT;
+
+        let mut entities: Vec<Entity> = world.iter().map(|e| e.entity()).collect();
+        entities.sort();
+
+        // (entity id or None for a continuation line, component names)
+        let mut lines: Vec<(Option<Entity>, String)> = Vec::new();
+        for &entity in &entities {
+            let mut line = String::new();
+            let mut first = true;
+            for &(name, probe) in PROBES {
+                if !probe(world, entity) {
+                    continue;
+                }
+                let candidate =
+                    if line.is_empty() { name.to_string() } else { format!("{line} {name}") };
+                if !line.is_empty()
+                    && text_renderer.measure_text(&candidate, BROWSER_SCALE) > wrap_width
+                {
+                    lines.push((first.then_some(entity), std::mem::take(&mut line)));
+                    first = false;
+                    line = name.to_string();
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push((first.then_some(entity), line));
+        }
+
+        // One line for the header.
+        let rows = ((height - 2.0 * BROWSER_MARGIN) / LINE_HEIGHT) as usize;
+        self.page_lines = rows.saturating_sub(1).max(1);
+        let last_page_start = lines.len().saturating_sub(1) / self.page_lines * self.page_lines;
+        self.first_line = self.first_line.min(last_page_start);
+        let shown = &lines[self.first_line..lines.len().min(self.first_line + self.page_lines)];
+
+        let header = format!(
+            "Entities: {}  lines {}-{} of {}  (PgUp/PgDn)",
+            entities.len(),
+            self.first_line + 1,
+            self.first_line + shown.len(),
+            lines.len(),
+        );
+        let mut y = BROWSER_MARGIN;
+        text_renderer.draw_text(&header, x, y, BROWSER_SCALE, HEADER_COLOR, projection);
+
+        for (entity, components) in shown {
+            y += LINE_HEIGHT;
+            if let Some(entity) = entity {
+                let id = format!("#{}", entity.id());
+                text_renderer.draw_text(&id, x, y, BROWSER_SCALE, ID_COLOR, projection);
+            }
+            text_renderer.draw_text(
+                components,
+                x + COMPONENT_INDENT,
+                y,
+                BROWSER_SCALE,
+                COMPONENT_COLOR,
+                projection,
+            );
+        }
+    }
+    /// Float each visible entity's id above it in the 3D view. Entities without a collider
+    /// (limbs, lights) and ground planes are skipped to keep the view readable.
+    ///
+    /// Caller must set up the GL blend state.
+    pub fn draw_labels(&self, text_renderer: &mut TextRenderer, world: &World, view: &LabelView) {
+        for (entity, (global, collider)) in
+            world.query::<(&GlobalTransform, &Collider)>().without::<&Hidden>().iter()
+        {
+            let top = match *collider {
+                Collider::Sphere { radius } => radius,
+                Collider::Capsule { radius, height } => height * 0.5 + radius,
+                Collider::Box { half_extents } => half_extents.y,
+                Collider::Plane { .. } => continue,
+            };
+            let pos = global.0.w_axis.truncate() + Vec3::Y * (top + LABEL_LIFT);
+            let id = format!("#{}", entity.id());
+            draw_world_label(text_renderer, view, &id, pos, ID_COLOR);
+        }
+    }
+}
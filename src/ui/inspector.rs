@@ -0,0 +1,94 @@
+use glam::{Mat4, Vec3};
+use hecs::{Entity, World};
+
+use crate::components::{Collider, Color, GlobalTransform, LocalTransform, Mass, Velocity};
+use crate::ui::text::TextRenderer;
+
+const INSPECTOR_SCALE: f32 = 1.5;
+const INSPECTOR_MARGIN: f32 = 8.0;
+// 8px glyph height * scale + 3px padding
+const LINE_HEIGHT: f32 = 8.0 * INSPECTOR_SCALE + 3.0;
+/// Below the debug HUD's lines so the two can be open together.
+const INSPECTOR_TOP: f32 = 140.0;
+const TITLE_COLOR: Vec3 = Vec3::new(0.55, 0.85, 1.0);
+const VALUE_COLOR: Vec3 = Vec3::new(0.9, 0.9, 0.9);
+
+fn format_vec3(v: Vec3) -> String {
+    format!("{:7.2} {:7.2} {:7.2}", v.x, v.y, v.z)
+}
+
+/// Detail panel for one picked entity, showing its component values as of this frame.
+pub struct Inspector {
+    selected: Option<Entity>,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self { selected: None }
+    }
+
+    /// Show `entity`, or close the panel with `None`.
+    pub fn select(&mut self, entity: Option<Entity>) {
+        self.selected = entity;
+    }
+
+    pub fn selected(&self) -> Option<Entity> {
+        self.selected
+    }
+
+    /// Render the panel at the left edge, below the debug HUD. Closes it instead if the
+    /// selected entity has been despawned.
+    ///
+    /// Caller must set up the orthographic projection and GL blend state.
+    pub fn draw(&mut self, text_renderer: &mut TextRenderer, world: &World, projection: &Mat4) {
+        let Some(entity) = self.selected else {
+            return;
+        };
+        if !world.contains(entity) {
+            self.selected = None;
+            return;
+        }
+
+        let mut lines = Vec::new();
+        if let Ok(global) = world.get::<&GlobalTransform>(entity) {
+            lines.push(format!("world {}", format_vec3(global.0.w_axis.truncate())));
+        }
+        if let Ok(local) = world.get::<&LocalTransform>(entity) {
+            lines.push(format!("local {}", format_vec3(local.position)));
+            lines.push(format!("scale {}", format_vec3(local.scale)));
+        }
+        if let Ok(velocity) = world.get::<&Velocity>(entity) {
+            lines.push(format!("vel   {}", format_vec3(velocity.0)));
+            lines.push(format!("speed {:7.2}", velocity.0.length()));
+        }
+        if let Ok(mass) = world.get::<&Mass>(entity) {
+            lines.push(format!("mass  {:7.2}", mass.0));
+        }
+        if let Ok(collider) = world.get::<&Collider>(entity) {
+            lines.push(match *collider {
+                Collider::Sphere { radius } => format!("collider sphere r={radius:.2}"),
+                Collider::Capsule { radius, height } => {
+                    format!("collider capsule r={radius:.2} h={height:.2}")
+                }
+                Collider::Box { half_extents: h } => {
+                    format!("collider box {:.2}x{:.2}x{:.2}", h.x * 2.0, h.y * 2.0, h.z * 2.0)
+                }
+                Collider::Plane { normal, offset } => {
+                    format!("collider plane n={} d={offset:.2}", format_vec3(normal))
+                }
+            });
+        }
+        if let Ok(color) = world.get::<&Color>(entity) {
+            lines.push(format!("color {:.2} {:.2} {:.2}", color.0.x, color.0.y, color.0.z));
+        }
+
+        let x = INSPECTOR_MARGIN;
+        let mut y = INSPECTOR_TOP;
+        let title = format!("Entity #{}", entity.id());
+        text_renderer.draw_text(&title, x, y, INSPECTOR_SCALE, TITLE_COLOR, projection);
+        for line in &lines {
+            y += LINE_HEIGHT;
+            text_renderer.draw_text(line, x, y, INSPECTOR_SCALE, VALUE_COLOR, projection);
+        }
+    }
+}
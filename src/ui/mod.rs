@@ -1,7 +1,15 @@
+pub mod damage_numbers;
 pub mod debug_hud;
+pub mod entity_browser;
+pub mod inspector;
 pub mod pause_menu;
 pub mod text;
+pub mod world_text;
 
+pub use damage_numbers::draw_damage_numbers;
 pub use debug_hud::DebugHud;
+pub use entity_browser::EntityBrowser;
+pub use inspector::Inspector;
 pub use pause_menu::{GameState, PauseAction, PauseMenu};
 pub use text::TextRenderer;
+pub use world_text::{draw_world_label, LabelView};
@@ -2,8 +2,10 @@ use gl::types::*;
 use glam::{Mat4, Vec3};
 use std::mem;
 
+use crate::engine::audio::Volumes;
 use crate::engine::input::InputEvent;
 use crate::renderer::shader::ShaderProgram;
+use crate::renderer::{DEFAULT_EXPOSURE, MAX_EXPOSURE, MIN_EXPOSURE};
 use crate::ui::text::TextRenderer;
 use sdl2::keyboard::Scancode;
 
@@ -20,16 +22,36 @@ pub enum GameState {
 pub enum PauseAction {
     None,
     Resume,
+    Restart,
     Quit,
 }
 
-const MENU_ITEMS: &[&str] = &["Resume", "Quit"];
+const MENU_ITEMS: &[&str] = &[
+    "Resume",
+    "Exposure",
+    "Master Volume",
+    "SFX Volume",
+    "Music Volume",
+    "Restart",
+    "Quit",
+];
+/// Indices of the sliders in `MENU_ITEMS`; Left/Right adjust them.
+const EXPOSURE_ITEM: usize = 1;
+const MASTER_VOLUME_ITEM: usize = 2;
+const SFX_VOLUME_ITEM: usize = 3;
+const MUSIC_VOLUME_ITEM: usize = 4;
+const RESTART_ITEM: usize = 5;
+const QUIT_ITEM: usize = 6;
+const EXPOSURE_STEP: f32 = 0.1;
+const VOLUME_STEP: f32 = 0.1;
 
 pub struct PauseMenu {
     shader: ShaderProgram,
     vao: GLuint,
     vbo: GLuint,
     selected: usize,
+    exposure: f32,
+    volumes: Volumes,
 }
 
 impl PauseMenu {
@@ -66,9 +88,47 @@ impl PauseMenu {
             vao,
             vbo,
             selected: 0,
+            exposure: DEFAULT_EXPOSURE,
+            volumes: Volumes::default(),
         }
     }
 
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure.clamp(MIN_EXPOSURE, MAX_EXPOSURE);
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn volumes(&self) -> Volumes {
+        self.volumes
+    }
+
+    pub fn set_volumes(&mut self, volumes: Volumes) {
+        self.volumes = volumes;
+    }
+
+    /// Move the selected slider one step left (`-1.0`) or right (`1.0`).
+    fn adjust(&mut self, direction: f32) {
+        let volume = match self.selected {
+            EXPOSURE_ITEM => {
+                self.set_exposure(self.exposure + direction * EXPOSURE_STEP);
+                return;
+            }
+            MASTER_VOLUME_ITEM => &mut self.volumes.master,
+            SFX_VOLUME_ITEM => &mut self.volumes.sfx,
+            MUSIC_VOLUME_ITEM => &mut self.volumes.music,
+            _ => return,
+        };
+        // Round so repeated steps land exactly on 0 and 1.
+        *volume = ((*volume + direction * VOLUME_STEP) * 10.0).round().clamp(0.0, 10.0) / 10.0;
+    }
+
     pub fn handle_input(&mut self, events: &[InputEvent]) -> PauseAction {
         for event in events {
             match event {
@@ -82,10 +142,13 @@ impl PauseMenu {
                 InputEvent::KeyPressed(Scancode::Down | Scancode::S) => {
                     self.selected = (self.selected + 1) % MENU_ITEMS.len();
                 }
+                InputEvent::KeyPressed(Scancode::Left | Scancode::A) => self.adjust(-1.0),
+                InputEvent::KeyPressed(Scancode::Right | Scancode::D) => self.adjust(1.0),
                 InputEvent::KeyPressed(Scancode::Return | Scancode::KpEnter) => {
                     return match self.selected {
                         0 => PauseAction::Resume,
-                        1 => PauseAction::Quit,
+                        RESTART_ITEM => PauseAction::Restart,
+                        QUIT_ITEM => PauseAction::Quit,
                         _ => PauseAction::None,
                     };
                 }
@@ -120,7 +183,15 @@ impl PauseMenu {
         let item_start_y = height * 0.48;
         let item_spacing = 40.0;
 
-        for (i, item) in MENU_ITEMS.iter().enumerate() {
+        for (i, &item) in MENU_ITEMS.iter().enumerate() {
+            let label = match i {
+                EXPOSURE_ITEM => format!("{item} < {:.1} >", self.exposure),
+                MASTER_VOLUME_ITEM => format!("{item} < {:.0}% >", self.volumes.master * 100.0),
+                SFX_VOLUME_ITEM => format!("{item} < {:.0}% >", self.volumes.sfx * 100.0),
+                MUSIC_VOLUME_ITEM => format!("{item} < {:.0}% >", self.volumes.music * 100.0),
+                _ => item.to_string(),
+            };
+            let item = label.as_str();
             let item_w = text_renderer.measure_text(item, item_scale);
             let item_x = (width - item_w) / 2.0;
             let item_y = item_start_y + i as f32 * item_spacing;
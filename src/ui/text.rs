@@ -11,8 +11,42 @@ const GLYPH_W: u32 = 8;
 const GLYPH_H: u32 = 8;
 const ATLAS_COLS: u32 = 16;
 const ATLAS_ROWS: u32 = 6;
-const ATLAS_W: u32 = ATLAS_COLS * GLYPH_W; // 128
-const ATLAS_H: u32 = ATLAS_ROWS * GLYPH_H; // 48
+
+// The atlas stores a signed distance field built from the bitmap font rather than the bitmap
+// itself, so glyphs scaled far past 8 px keep smooth, anti-aliased edges. Each glyph cell is
+// padded by SDF_PAD font pixels so the field can fall off outside the glyph, and sampled at
+// SDF_TEXELS texels per font pixel.
+const SDF_TEXELS: u32 = 4;
+const SDF_PAD: u32 = 1;
+/// Distance (font pixels) from the edge at which the field saturates to 0 or 1.
+const SDF_SPREAD: f32 = 1.0;
+const CELL_W: u32 = (GLYPH_W + 2 * SDF_PAD) * SDF_TEXELS; // 40
+const CELL_H: u32 = (GLYPH_H + 2 * SDF_PAD) * SDF_TEXELS; // 40
+const ATLAS_W: u32 = ATLAS_COLS * CELL_W; // 640
+const ATLAS_H: u32 = ATLAS_ROWS * CELL_H; // 240
+
+// Proportional spacing: each glyph advances by its inked width plus GLYPH_SPACING.
+/// Blank font pixels between neighbouring glyphs.
+const GLYPH_SPACING: u32 = 1;
+/// Advance of the (inkless) space character.
+const SPACE_ADVANCE: u32 = 4;
+/// Advance of characters the font has no glyph for.
+const DEFAULT_ADVANCE: u32 = GLYPH_W;
+
+/// Pair adjustments (font pixels) applied between two glyphs on top of their advances, for
+/// pairs whose shapes leave a visible gap.
+const KERNING: &[(char, char, i32)] = &[
+    ('A', 'V', -1),
+    ('V', 'A', -1),
+    ('A', 'T', -1),
+    ('T', 'A', -1),
+    ('L', 'T', -1),
+    ('T', 'a', -1),
+    ('T', 'e', -1),
+    ('T', 'o', -1),
+    ('F', 'a', -1),
+    ('P', 'a', -1),
+];
 
 // 4 floats per vertex (x, y, u, v), 6 vertices per quad
 const FLOATS_PER_CHAR: usize = 4 * 6;
@@ -216,11 +250,37 @@ const FONT_8X8: [u8; 96 * 8] = [
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
 ];
 
+/// Horizontal metrics of one glyph, in font pixels.
+#[derive(Clone, Copy)]
+struct GlyphMetrics {
+    /// First inked column; the glyph is drawn shifted left by this much.
+    left: i32,
+    /// Pen advance after the glyph.
+    advance: u32,
+}
+
+impl GlyphMetrics {
+    /// Measure the inked columns of an 8x8 glyph.
+    fn from_bitmap(glyph: &[u8]) -> Self {
+        let columns = glyph.iter().fold(0u8, |acc, &row| acc | row);
+        if columns == 0 {
+            return Self { left: 0, advance: SPACE_ADVANCE };
+        }
+        let left = columns.leading_zeros();
+        let right = GLYPH_W - 1 - columns.trailing_zeros();
+        Self { left: left as i32, advance: right - left + 1 + GLYPH_SPACING }
+    }
+}
+
 pub struct TextRenderer {
     shader: ShaderProgram,
     vao: GLuint,
     vbo: GLuint,
     font_texture: GLuint,
+    /// Metrics for ASCII 32–127, indexed like the atlas.
+    metrics: [GlyphMetrics; 96],
+    /// Alpha multiplier applied to everything drawn, for fading text out.
+    opacity: f32,
 }
 
 impl TextRenderer {
@@ -228,18 +288,22 @@ impl TextRenderer {
         let shader = ShaderProgram::from_sources(TEXT_VERT_SRC, TEXT_FRAG_SRC)
             .expect("Failed to compile text shaders");
 
-        // Build font atlas from embedded bitmap data
+        // Build the distance-field atlas from the embedded bitmap data
         let mut atlas = vec![0u8; (ATLAS_W * ATLAS_H) as usize];
         for ch_idx in 0..96u32 {
             let col = ch_idx % ATLAS_COLS;
             let row = ch_idx / ATLAS_COLS;
-            for y in 0..GLYPH_H {
-                let byte = FONT_8X8[(ch_idx * 8 + y) as usize];
-                for x in 0..GLYPH_W {
-                    let pixel = if byte & (0x80 >> x) != 0 { 255 } else { 0 };
-                    let atlas_x = col * GLYPH_W + x;
-                    let atlas_y = row * GLYPH_H + y;
-                    atlas[(atlas_y * ATLAS_W + atlas_x) as usize] = pixel;
+            let glyph = &FONT_8X8[(ch_idx * 8) as usize..(ch_idx * 8 + 8) as usize];
+            for ty in 0..CELL_H {
+                for tx in 0..CELL_W {
+                    // Texel centre in font pixels, relative to the glyph's top-left corner.
+                    let px = (tx as f32 + 0.5) / SDF_TEXELS as f32 - SDF_PAD as f32;
+                    let py = (ty as f32 + 0.5) / SDF_TEXELS as f32 - SDF_PAD as f32;
+                    let d = glyph_distance(glyph, px, py);
+                    let value = (0.5 - d / (2.0 * SDF_SPREAD)).clamp(0.0, 1.0);
+                    let atlas_x = col * CELL_W + tx;
+                    let atlas_y = row * CELL_H + ty;
+                    atlas[(atlas_y * ATLAS_W + atlas_x) as usize] = (value * 255.0).round() as u8;
                 }
             }
         }
@@ -264,8 +328,8 @@ impl TextRenderer {
                 gl::UNSIGNED_BYTE,
                 atlas.as_ptr() as *const _,
             );
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
             gl::TexParameteri(
                 gl::TEXTURE_2D,
                 gl::TEXTURE_WRAP_S,
@@ -308,14 +372,54 @@ impl TextRenderer {
             gl::BindVertexArray(0);
         }
 
+        let mut metrics: [GlyphMetrics; 96] =
+            std::array::from_fn(|i| GlyphMetrics::from_bitmap(&FONT_8X8[i * 8..i * 8 + 8]));
+        // Digits share one advance (centred in it) so changing numbers in the HUD don't
+        // make the text after them jitter.
+        let digits = (b'0' - 32) as usize..=(b'9' - 32) as usize;
+        let digit_advance = metrics[digits.clone()].iter().map(|m| m.advance).max().unwrap();
+        for m in &mut metrics[digits] {
+            m.left -= ((digit_advance - m.advance) / 2) as i32;
+            m.advance = digit_advance;
+        }
+
         Self {
             shader,
             vao,
             vbo,
             font_texture,
+            metrics,
+            opacity: 1.0,
         }
     }
 
+    /// Lay out `text` on a baseline starting at 0. Returns each drawable glyph's atlas index
+    /// and pen position, plus the total advance, all in font pixels.
+    fn layout(&self, text: &str) -> (Vec<(u32, f32)>, f32) {
+        let mut glyphs = Vec::new();
+        let mut pen = 0.0;
+        let mut prev: Option<char> = None;
+        for ch in text.chars().take(MAX_CHARS) {
+            let idx = ch as u32;
+            if !(32..=127).contains(&idx) {
+                pen += DEFAULT_ADVANCE as f32;
+                prev = None;
+                continue;
+            }
+            if let Some(prev) = prev {
+                pen += KERNING
+                    .iter()
+                    .find(|&&(a, b, _)| a == prev && b == ch)
+                    .map_or(0, |&(_, _, kern)| kern) as f32;
+            }
+            let glyph_idx = idx - 32;
+            glyphs.push((glyph_idx, pen));
+            pen += self.metrics[glyph_idx as usize].advance as f32;
+            prev = Some(ch);
+        }
+        (glyphs, pen)
+    }
+
     pub fn draw_text(
         &mut self,
         text: &str,
@@ -325,33 +429,30 @@ impl TextRenderer {
         color: Vec3,
         projection: &Mat4,
     ) {
-        let gw = GLYPH_W as f32 * scale;
-        let gh = GLYPH_H as f32 * scale;
+        // Quads cover the whole padded cell, shifted so the first inked column sits at the pen.
+        let pad = SDF_PAD as f32 * scale;
+        let cell_w = GLYPH_W as f32 * scale + 2.0 * pad;
+        let cell_h = GLYPH_H as f32 * scale + 2.0 * pad;
         let atlas_w = ATLAS_W as f32;
         let atlas_h = ATLAS_H as f32;
 
-        let mut vertices: Vec<f32> = Vec::with_capacity(text.len().min(MAX_CHARS) * FLOATS_PER_CHAR);
-        let mut cursor_x = x;
+        let (glyphs, _) = self.layout(text);
+        let mut vertices: Vec<f32> = Vec::with_capacity(glyphs.len() * FLOATS_PER_CHAR);
 
-        for ch in text.chars().take(MAX_CHARS) {
-            let idx = ch as u32;
-            if !(32..=127).contains(&idx) {
-                cursor_x += gw;
-                continue;
-            }
-            let glyph_idx = idx - 32;
+        for (glyph_idx, pen) in glyphs {
             let col = glyph_idx % ATLAS_COLS;
             let row = glyph_idx / ATLAS_COLS;
 
-            let u0 = (col * GLYPH_W) as f32 / atlas_w;
-            let v0 = (row * GLYPH_H) as f32 / atlas_h;
-            let u1 = ((col + 1) * GLYPH_W) as f32 / atlas_w;
-            let v1 = ((row + 1) * GLYPH_H) as f32 / atlas_h;
+            let u0 = (col * CELL_W) as f32 / atlas_w;
+            let v0 = (row * CELL_H) as f32 / atlas_h;
+            let u1 = ((col + 1) * CELL_W) as f32 / atlas_w;
+            let v1 = ((row + 1) * CELL_H) as f32 / atlas_h;
 
-            let x0 = cursor_x;
-            let y0 = y;
-            let x1 = cursor_x + gw;
-            let y1 = y + gh;
+            let left = self.metrics[glyph_idx as usize].left as f32 * scale;
+            let x0 = x + pen * scale - left - pad;
+            let y0 = y - pad;
+            let x1 = x0 + cell_w;
+            let y1 = y0 + cell_h;
 
             // Two triangles per quad
             #[rustfmt::skip]
@@ -364,8 +465,6 @@ impl TextRenderer {
                 x1, y1, u1, v1,
                 x0, y1, u0, v1,
             ]);
-
-            cursor_x += gw;
         }
 
         if vertices.is_empty() {
@@ -376,6 +475,7 @@ impl TextRenderer {
             self.shader.bind();
             self.shader.set_mat4("u_projection", projection);
             self.shader.set_vec3("u_text_color", color);
+            self.shader.set_float("u_text_opacity", self.opacity);
 
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, self.font_texture);
@@ -395,9 +495,45 @@ impl TextRenderer {
         }
     }
 
+    /// Fade all following `draw_text` calls to `opacity` (0–1) until it is set back to 1.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    /// Width `draw_text` advances over `text`, including the spacing after the last glyph.
     pub fn measure_text(&self, text: &str, scale: f32) -> f32 {
-        let gw = GLYPH_W as f32 * scale;
-        text.chars().take(MAX_CHARS).count() as f32 * gw
+        self.layout(text).1 * scale
+    }
+}
+
+/// Signed distance (font pixels, negative inside) from (`px`, `py`) to the edge of an 8x8
+/// bitmap glyph, treating each set bit as a unit square. Brute force over all 64 pixels;
+/// only run while building the atlas.
+fn glyph_distance(glyph: &[u8], px: f32, py: f32) -> f32 {
+    let filled = |x: i32, y: i32| {
+        (0..GLYPH_W as i32).contains(&x)
+            && (0..GLYPH_H as i32).contains(&y)
+            && glyph[y as usize] & (0x80 >> x) != 0
+    };
+    let inside = filled(px.floor() as i32, py.floor() as i32);
+
+    // Nearest pixel of the opposite kind. Outside the 8x8 grid everything is empty, so from
+    // inside the glyph the cell border is also a candidate edge.
+    let mut nearest = f32::MAX;
+    for y in -1..=GLYPH_H as i32 {
+        for x in -1..=GLYPH_W as i32 {
+            if filled(x, y) == inside {
+                continue;
+            }
+            let dx = (x as f32 - px).max(px - (x + 1) as f32).max(0.0);
+            let dy = (y as f32 - py).max(py - (y + 1) as f32).max(0.0);
+            nearest = nearest.min((dx * dx + dy * dy).sqrt());
+        }
+    }
+    if inside {
+        -nearest
+    } else {
+        nearest
     }
 }
 
@@ -0,0 +1,66 @@
+use glam::{Mat4, Vec2, Vec3, Vec4};
+
+use crate::ui::text::TextRenderer;
+
+/// Text scale of a label `LABEL_REFERENCE_DISTANCE` metres from the camera; labels shrink
+/// in proportion further out and grow closer in, within [MIN, MAX]_LABEL_SCALE.
+const LABEL_SCALE: f32 = 2.0;
+const LABEL_REFERENCE_DISTANCE: f32 = 5.0;
+const MIN_LABEL_SCALE: f32 = 1.0;
+const MAX_LABEL_SCALE: f32 = 3.0;
+/// Glyph height in font pixels, before scaling.
+const GLYPH_HEIGHT: f32 = 8.0;
+
+/// Everything needed to place world-space labels on screen for one frame.
+pub struct LabelView {
+    view_proj: Mat4,
+    camera_pos: Vec3,
+    screen: Vec2,
+    /// Orthographic UI projection the text is drawn with.
+    projection: Mat4,
+}
+
+impl LabelView {
+    /// `view_proj` is the 3D camera's; `screen` the window size in pixels.
+    pub fn new(view_proj: Mat4, camera_pos: Vec3, screen: Vec2) -> Self {
+        let projection = Mat4::orthographic_rh_gl(0.0, screen.x, screen.y, 0.0, -1.0, 1.0);
+        Self { view_proj, camera_pos, screen, projection }
+    }
+
+    /// Window position (pixels, top-left origin) of `world_pos`, or `None` when it's behind
+    /// the camera or off-screen.
+    pub fn to_screen(&self, world_pos: Vec3) -> Option<Vec2> {
+        let clip = self.view_proj * Vec4::new(world_pos.x, world_pos.y, world_pos.z, 1.0);
+        if clip.w <= 1e-4 {
+            return None;
+        }
+        let ndc = clip.truncate() / clip.w;
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 || ndc.z > 1.0 {
+            return None;
+        }
+        Some(Vec2::new((ndc.x * 0.5 + 0.5) * self.screen.x, (0.5 - ndc.y * 0.5) * self.screen.y))
+    }
+}
+
+/// Draw `text` centred just above `world_pos`, sized by its distance from the camera.
+/// Labels behind the camera or off-screen are skipped.
+///
+/// Caller must set up the GL blend state.
+pub fn draw_world_label(
+    text_renderer: &mut TextRenderer,
+    view: &LabelView,
+    text: &str,
+    world_pos: Vec3,
+    color: Vec3,
+) {
+    let Some(anchor) = view.to_screen(world_pos) else {
+        return;
+    };
+    let distance = world_pos.distance(view.camera_pos).max(1e-3);
+    let scale = (LABEL_SCALE * LABEL_REFERENCE_DISTANCE / distance)
+        .clamp(MIN_LABEL_SCALE, MAX_LABEL_SCALE);
+    let width = text_renderer.measure_text(text, scale);
+    let x = anchor.x - width * 0.5;
+    let y = anchor.y - GLYPH_HEIGHT * scale;
+    text_renderer.draw_text(text, x, y, scale, color, &view.projection);
+}